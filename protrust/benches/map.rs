@@ -0,0 +1,49 @@
+#![feature(test)]
+
+extern crate test;
+
+use protrust::collections::{MapField, RepeatedValue};
+use protrust::io::{CodedReader, CodedWriter, FieldNumber, LengthBuilder};
+use protrust::raw::{Int32, String};
+use test::Bencher;
+
+const COUNT: i32 = 10_000;
+
+type Entry = (Int32, String);
+
+fn map() -> MapField<i32, std::string::String> {
+    (0..COUNT).map(|i| (i, format!("value {}", i))).collect()
+}
+
+fn field() -> FieldNumber {
+    FieldNumber::new(1).unwrap()
+}
+
+fn encode(map: &MapField<i32, std::string::String>) -> Vec<u8> {
+    let len = RepeatedValue::<Entry>::calculate_size(map, LengthBuilder::new(), field()).unwrap().build();
+    let mut output = vec![0; len.get() as usize];
+    RepeatedValue::<Entry>::write_to(map, &mut CodedWriter::with_slice(&mut output), field()).unwrap();
+    output
+}
+
+#[bench]
+fn write_map(b: &mut Bencher) {
+    let map = map();
+    let mut output = encode(&map);
+    b.iter(|| {
+        RepeatedValue::<Entry>::write_to(&map, &mut CodedWriter::with_slice(&mut output), field()).unwrap();
+    });
+}
+
+#[bench]
+fn read_map(b: &mut Bencher) {
+    let input = encode(&map());
+    b.iter(|| {
+        let mut map = MapField::new();
+        let mut reader = CodedReader::with_slice(&input);
+        while let Some(field) = reader.read_field().unwrap() {
+            field.add_entries_to::<_, Entry>(self::field(), &mut map).unwrap();
+        }
+        map
+    });
+}
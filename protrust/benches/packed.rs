@@ -0,0 +1,68 @@
+#![feature(test)]
+
+extern crate test;
+
+use protrust::collections::{RepeatedField, RepeatedValue};
+use protrust::io::{CodedReader, CodedWriter, FieldNumber, Length};
+use protrust::raw::{Bool, Fixed64, Packed};
+use test::Bencher;
+
+const COUNT: usize = 100_000;
+
+fn values() -> Vec<u64> {
+    (0..COUNT as u64).collect()
+}
+
+fn output() -> Vec<u8> {
+    vec![0; COUNT * 8 + 5]
+}
+
+#[bench]
+fn write_packed_fixed64_per_value(b: &mut Bencher) {
+    let values = values();
+    let mut output = output();
+    b.iter(|| {
+        let mut writer = CodedWriter::with_slice(&mut output);
+        writer.write_length(Length::new((COUNT * 8) as i32).unwrap()).unwrap();
+        for value in &values {
+            writer.write_value::<Fixed64>(value).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn write_packed_fixed64_slice(b: &mut Bencher) {
+    let values = values();
+    let mut output = output();
+    b.iter(|| {
+        let mut writer = CodedWriter::with_slice(&mut output);
+        writer.write_fixed_slice::<Fixed64>(&values).unwrap();
+    });
+}
+
+fn bools() -> Vec<bool> {
+    (0..COUNT).map(|i| i % 3 == 0).collect()
+}
+
+#[bench]
+fn write_packed_bool(b: &mut Bencher) {
+    let values = bools();
+    let mut output = vec![0; COUNT + 6];
+    let num = FieldNumber::new(1).unwrap();
+    b.iter(|| {
+        let mut writer = CodedWriter::with_slice(&mut output);
+        RepeatedValue::<Packed<Bool>>::write_to(&values, &mut writer, num).unwrap();
+    });
+}
+
+#[bench]
+fn read_packed_bool(b: &mut Bencher) {
+    let values = bools();
+    let mut input = vec![0; COUNT + 6];
+    CodedWriter::with_slice(&mut input).write_fixed_slice::<Bool>(&values).unwrap();
+    b.iter(|| {
+        let mut field = RepeatedField::<bool>::new();
+        RepeatedValue::<Packed<Bool>>::add_entries_from(&mut field, &mut CodedReader::with_slice(&input)).unwrap();
+        field
+    });
+}
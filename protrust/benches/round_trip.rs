@@ -0,0 +1,77 @@
+#![feature(test)]
+
+extern crate test;
+
+use protrust::UnknownFieldSet;
+use protrust::io::{read, write, CodedReader, CodedWriter, FieldNumber, Input, Output};
+use protrust::raw::{Int32, Int64};
+use test::Bencher;
+
+const COUNT: usize = 10_000;
+
+const SECONDS: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+const NANOS: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+
+/// A small message shaped like a `google.protobuf.Timestamp`, merged and written the same way generated code does.
+#[derive(Default)]
+struct Time {
+    seconds: i64,
+    nanos: i32,
+    unknown_fields: UnknownFieldSet,
+}
+
+impl Time {
+    fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+        while let Some(field) = input.read_field()? {
+            match field.tag() {
+                8 => field.merge_value::<Int64>(SECONDS, &mut self.seconds)?,
+                16 => field.merge_value::<Int32>(NANOS, &mut self.nanos)?,
+                _ => field.check_and_try_add_field_to(&mut self.unknown_fields)?.or_skip()?,
+            }
+        }
+        Ok(())
+    }
+    fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
+        if self.seconds != 0 {
+            output.write_field::<Int64>(SECONDS, &self.seconds)?;
+        }
+        if self.nanos != 0 {
+            output.write_field::<Int32>(NANOS, &self.nanos)?;
+        }
+        output.write_fields(&self.unknown_fields)
+    }
+}
+
+// seconds = 1580000000, nanos = 500000000
+const INPUT: [u8; 12] = [8, 128, 198, 179, 241, 5, 16, 128, 202, 181, 238, 1];
+
+#[bench]
+fn decode_encode_slice(b: &mut Bencher) {
+    let mut output = [0u8; INPUT.len()];
+    b.iter(|| {
+        for _ in 0..COUNT {
+            let mut message = Time::default();
+            message.merge_from(&mut CodedReader::with_slice(&INPUT)).unwrap();
+            message.write_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+        }
+        output
+    });
+    assert_eq!(output, INPUT);
+}
+
+#[bench]
+fn decode_encode_stream(b: &mut Bencher) {
+    let mut output = Vec::with_capacity(INPUT.len());
+    b.iter(|| {
+        for _ in 0..COUNT {
+            let mut message = Time::default();
+            message.merge_from(&mut CodedReader::with_stream(&INPUT[..])).unwrap();
+            output.clear();
+            let mut writer = CodedWriter::with_stream(&mut output);
+            message.write_to(&mut writer).unwrap();
+            writer.finish().unwrap();
+        }
+        output.len()
+    });
+    assert_eq!(output, INPUT);
+}
@@ -0,0 +1,78 @@
+#![feature(test)]
+
+extern crate test;
+
+use protrust::io::{CodedReader, CodedWriter};
+use protrust::raw::String;
+use test::Bencher;
+
+const COUNT: usize = 10_000;
+
+fn encode() -> Vec<u8> {
+    let values: Vec<_> = (0..COUNT).map(|i| format!("value number {}", i)).collect();
+    let mut output = vec![0; values.iter().map(|v| v.len() + 5).sum()];
+    let mut writer = CodedWriter::with_slice(&mut output);
+    for value in &values {
+        writer.write_length_delimited(value.as_bytes()).unwrap();
+    }
+    let remaining = writer.into_inner().len();
+    let len = output.len() - remaining;
+    output.truncate(len);
+    output
+}
+
+#[bench]
+fn read_value(b: &mut Bencher) {
+    let input = encode();
+    b.iter(|| {
+        let mut reader = CodedReader::with_slice(&input);
+        let mut total = 0;
+        for _ in 0..COUNT {
+            total += reader.read_value::<String>().unwrap().len();
+        }
+        total
+    });
+}
+
+#[bench]
+fn read_string_into(b: &mut Bencher) {
+    let input = encode();
+    let mut buf = std::string::String::new();
+    b.iter(|| {
+        let mut reader = CodedReader::with_slice(&input);
+        let mut total = 0;
+        for _ in 0..COUNT {
+            reader.read_string_into(&mut buf).unwrap();
+            total += buf.len();
+        }
+        total
+    });
+}
+
+#[bench]
+fn read_value_stream(b: &mut Bencher) {
+    let input = encode();
+    b.iter(|| {
+        let mut reader = CodedReader::with_stream(&input[..]);
+        let mut total = 0;
+        for _ in 0..COUNT {
+            total += reader.read_value::<String>().unwrap().len();
+        }
+        total
+    });
+}
+
+#[bench]
+fn read_string_into_stream(b: &mut Bencher) {
+    let input = encode();
+    let mut buf = std::string::String::new();
+    b.iter(|| {
+        let mut reader = CodedReader::with_stream(&input[..]);
+        let mut total = 0;
+        for _ in 0..COUNT {
+            reader.read_string_into(&mut buf).unwrap();
+            total += buf.len();
+        }
+        total
+    });
+}
@@ -0,0 +1,88 @@
+#![feature(test)]
+
+extern crate test;
+
+use protrust::io::{CodedReader, CodedWriter};
+use test::Bencher;
+
+const COUNT: usize = 100_000;
+
+/// Values spread evenly over every varint length from 1 to 10 bytes.
+fn values() -> Vec<u64> {
+    (0..COUNT as u64).map(|i| 1u64.wrapping_shl((i % 10) as u32 * 7)).collect()
+}
+
+fn encode(values: &[u64]) -> Vec<u8> {
+    let mut output = vec![0; values.len() * 10];
+    let mut writer = CodedWriter::with_slice(&mut output);
+    for &value in values {
+        writer.write_varint64(value).unwrap();
+    }
+    let remaining = writer.into_inner().len();
+    let len = output.len() - remaining;
+    output.truncate(len);
+    output
+}
+
+#[bench]
+fn write_varint32(b: &mut Bencher) {
+    let values: Vec<u32> = values().into_iter().map(|v| v as u32).collect();
+    let mut output = vec![0; COUNT * 5];
+    b.iter(|| {
+        let mut writer = CodedWriter::with_slice(&mut output);
+        for &value in &values {
+            writer.write_varint32(value).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn write_varint64(b: &mut Bencher) {
+    let values = values();
+    let mut output = vec![0; COUNT * 10];
+    b.iter(|| {
+        let mut writer = CodedWriter::with_slice(&mut output);
+        for &value in &values {
+            writer.write_varint64(value).unwrap();
+        }
+    });
+}
+
+#[bench]
+fn read_varint32(b: &mut Bencher) {
+    let input = encode(&values());
+    b.iter(|| {
+        let mut reader = CodedReader::with_slice(&input);
+        let mut sum = 0u32;
+        for _ in 0..COUNT {
+            sum = sum.wrapping_add(reader.read_varint32().unwrap());
+        }
+        sum
+    });
+}
+
+#[bench]
+fn read_varint64(b: &mut Bencher) {
+    let input = encode(&values());
+    b.iter(|| {
+        let mut reader = CodedReader::with_slice(&input);
+        let mut sum = 0u64;
+        for _ in 0..COUNT {
+            sum = sum.wrapping_add(reader.read_varint64().unwrap());
+        }
+        sum
+    });
+}
+
+#[bench]
+fn read_varint64_stream(b: &mut Bencher) {
+    let input = encode(&values());
+    b.iter(|| {
+        let mut reader = CodedReader::with_stream(&input[..]);
+        let mut sum = 0u64;
+        for _ in 0..COUNT {
+            sum = sum.wrapping_add(reader.read_varint64().unwrap());
+        }
+        sum
+    });
+}
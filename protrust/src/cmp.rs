@@ -0,0 +1,193 @@
+//! Comparison functions used by generated code to implement a total order for messages.
+//!
+//! Generated `Ord` implementations are structural: they compare fields in field number order,
+//! comparing repeated fields lexicographically and map fields by their entries in key order.
+//! Unknown fields aren't compared, so two messages that only differ in unknown fields are ordered as equal.
+//! Since `Eq` must agree with `Ord`, the ordering is only consistent with
+//! [`Message::semantic_eq`](../trait.Message.html#method.semantic_eq), not the derived `PartialEq`.
+//!
+//! Floating point values are compared by their total order, where negative values and negative zero order
+//! before positive zero and positive values, and NaNs order at the ends by their sign and payload.
+//!
+//! This also contains [`eq_unordered`](fn.eq_unordered.html), used to compare repeated fields
+//! whose order isn't significant.
+
+use std::cmp::Ordering;
+use crate::collections::MapField;
+use std::hash::Hash;
+
+/// Compares two `f32` values using their total order.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use protrust::cmp::total_cmp_f32;
+///
+/// assert_eq!(total_cmp_f32(-0.0, 0.0), Ordering::Less);
+/// assert_eq!(total_cmp_f32(std::f32::NAN, std::f32::INFINITY), Ordering::Greater);
+/// assert_eq!(total_cmp_f32(std::f32::NAN, std::f32::NAN), Ordering::Equal);
+/// ```
+pub fn total_cmp_f32(a: f32, b: f32) -> Ordering {
+    let key = |x: f32| {
+        let bits = x.to_bits() as i32;
+        bits ^ (((bits >> 31) as u32) >> 1) as i32
+    };
+    key(a).cmp(&key(b))
+}
+
+/// Compares two `f64` values using their total order.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use protrust::cmp::total_cmp_f64;
+///
+/// assert_eq!(total_cmp_f64(-1.0, 1.0), Ordering::Less);
+/// assert_eq!(total_cmp_f64(-std::f64::NAN, std::f64::NEG_INFINITY), Ordering::Less);
+/// ```
+pub fn total_cmp_f64(a: f64, b: f64) -> Ordering {
+    let key = |x: f64| {
+        let bits = x.to_bits() as i64;
+        bits ^ (((bits >> 63) as u64) >> 1) as i64
+    };
+    key(a).cmp(&key(b))
+}
+
+/// Compares two repeated fields lexicographically, comparing values with the specified function.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use protrust::cmp::{cmp_repeated, total_cmp_f64};
+///
+/// let a = [1.0, 2.0];
+/// let b = [1.0, 2.0, 0.0];
+/// assert_eq!(cmp_repeated(&a, &b, |a, b| total_cmp_f64(*a, *b)), Ordering::Less);
+/// ```
+pub fn cmp_repeated<T, F: FnMut(&T, &T) -> Ordering>(a: &[T], b: &[T], mut f: F) -> Ordering {
+    for (a, b) in a.iter().zip(b) {
+        match f(a, b) {
+            Ordering::Equal => { },
+            ord => return ord,
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Compares two map fields by their entries in ascending key order, comparing values with the specified function.
+///
+/// # Examples
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use std::collections::HashMap;
+/// use protrust::cmp::cmp_maps;
+///
+/// let a: HashMap<_, _> = vec![(1, "b"), (2, "a")].into_iter().collect();
+/// let b: HashMap<_, _> = vec![(1, "b"), (3, "a")].into_iter().collect();
+///
+/// // the entries are compared as [(1, "b"), (2, "a")] and [(1, "b"), (3, "a")]
+/// assert_eq!(cmp_maps(&a, &b, Ord::cmp), Ordering::Less);
+/// ```
+pub fn cmp_maps<K: Ord + Hash, V, F: FnMut(&V, &V) -> Ordering>(a: &MapField<K, V>, b: &MapField<K, V>, mut f: F) -> Ordering {
+    cmp_repeated(&sorted_entries(a), &sorted_entries(b), |(ak, av), (bk, bv)| ak.cmp(bk).then_with(|| f(av, bv)))
+}
+
+fn sorted_entries<K: Ord + Hash, V>(map: &MapField<K, V>) -> Vec<(&K, &V)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+/// Returns whether two repeated fields contain the same values the same number of times, in any order,
+/// comparing values with the specified function.
+///
+/// This is used for repeated fields that represent an unordered set, where the derived `PartialEq` would
+/// report that two fields with the same values in a different order aren't equal.
+/// Values only need to be comparable for equality, so this works with messages and floating point values,
+/// but it compares every pair of values in the worst case.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::cmp::eq_unordered;
+///
+/// let a = ["red", "green", "red"];
+/// let b = ["red", "red", "green"];
+///
+/// assert_ne!(a, b);
+/// assert!(eq_unordered(&a, &b, PartialEq::eq));
+/// assert!(!eq_unordered(&a, &["red", "green", "green"], PartialEq::eq));
+/// ```
+pub fn eq_unordered<T, F: FnMut(&T, &T) -> bool>(a: &[T], b: &[T], mut f: F) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut matched = vec![false; b.len()];
+    a.iter().all(|a| {
+        let found = b.iter().zip(matched.iter_mut()).find(|(b, matched)| !**matched && f(a, b));
+        match found {
+            Some((_, matched)) => { *matched = true; true },
+            None => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+    use std::{f32, f64};
+    use super::{total_cmp_f32, total_cmp_f64, cmp_repeated, cmp_maps, eq_unordered};
+
+    #[test]
+    fn floats_sort_totally() {
+        let mut values = vec![f64::NAN, 1.0, -f64::NAN, 0.0, f64::NEG_INFINITY, -0.0, -1.0, f64::INFINITY];
+        values.sort_by(|a, b| total_cmp_f64(*a, *b));
+
+        let bits = values.iter().map(|v| v.to_bits()).collect::<Vec<_>>();
+        let expected = [-f64::NAN, f64::NEG_INFINITY, -1.0, -0.0, 0.0, 1.0, f64::INFINITY, f64::NAN];
+        assert_eq!(bits, expected.iter().map(|v| v.to_bits()).collect::<Vec<_>>());
+
+        assert_eq!(total_cmp_f32(-f32::NAN, f32::NEG_INFINITY), Ordering::Less);
+        assert_eq!(total_cmp_f32(1.5, 1.5), Ordering::Equal);
+        assert_eq!(total_cmp_f32(2.0, 1.5), Ordering::Greater);
+    }
+
+    #[test]
+    fn repeated_compare_lexicographically() {
+        assert_eq!(cmp_repeated::<i32, _>(&[], &[], Ord::cmp), Ordering::Equal);
+        assert_eq!(cmp_repeated(&[1, 3], &[2], Ord::cmp), Ordering::Less);
+        assert_eq!(cmp_repeated(&[1, 2], &[1], Ord::cmp), Ordering::Greater);
+    }
+
+    #[test]
+    fn maps_compare_in_key_order() {
+        let map = |entries: &[(i32, i32)]| entries.iter().cloned().collect::<HashMap<_, _>>();
+
+        assert_eq!(cmp_maps(&map(&[(2, 0), (1, 5)]), &map(&[(1, 5), (2, 0)]), Ord::cmp), Ordering::Equal);
+        assert_eq!(cmp_maps(&map(&[(1, 5)]), &map(&[(1, 6)]), Ord::cmp), Ordering::Less);
+        assert_eq!(cmp_maps(&map(&[(1, 5), (3, 0)]), &map(&[(1, 5), (2, 9)]), Ord::cmp), Ordering::Greater);
+        assert_eq!(cmp_maps(&map(&[]), &map(&[(1, 0)]), Ord::cmp), Ordering::Less);
+    }
+
+    #[test]
+    fn repeated_compare_unordered() {
+        let tags = |tags: &[&str]| tags.iter().map(|&t| t.to_string()).collect::<Vec<_>>();
+
+        let a = tags(&["b", "a", "c"]);
+        let b = tags(&["c", "b", "a"]);
+        assert_ne!(a, b);
+        assert!(eq_unordered(&a, &b, PartialEq::eq));
+
+        assert!(eq_unordered::<String, _>(&[], &[], PartialEq::eq));
+        assert!(!eq_unordered(&tags(&["a", "a", "b"]), &tags(&["a", "b", "b"]), PartialEq::eq));
+        assert!(!eq_unordered(&tags(&["a"]), &tags(&["a", "a"]), PartialEq::eq));
+        assert!(!eq_unordered(&[0.0, f64::NAN], &[f64::NAN, -0.0], |a, b| total_cmp_f64(*a, *b) == Ordering::Equal));
+        assert!(eq_unordered(&[1.0, f64::NAN], &[f64::NAN, 1.0], |a, b| total_cmp_f64(*a, *b) == Ordering::Equal));
+    }
+}
@@ -3,6 +3,8 @@
 use crate::{Mergable, internal::Sealed};
 use crate::io::{self, read, write, WireType, FieldNumber, Tag, LengthBuilder, Length, CodedReader, CodedWriter, Input, Output};
 use crate::raw::{self, Value, Packable, Packed};
+use std::cmp;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::hash::Hash;
 
@@ -64,6 +66,10 @@ impl<'a, T: Input> TryRead<'a, T> {
 }
 
 /// The type used by generated code to represent a repeated field.
+/// 
+/// This is an alias rather than a newtype so repeated fields can be used anywhere a `Vec` can,
+/// without conversions at API boundaries. Helpers specific to repeated fields are provided
+/// by the [`RepeatedFieldExt`](trait.RepeatedFieldExt.html) extension trait instead of inherent methods.
 pub type RepeatedField<T> = Vec<T>;
 
 impl<T> Sealed for RepeatedField<T> { }
@@ -124,7 +130,7 @@ impl<V: Value + Packable> RepeatedValue<Packed<V>> for RepeatedField<V::Inner> {
 
     #[inline]
     fn add_entries_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
-        input.read_limit()?.for_all(|input| input.read_value::<V>().map(|v| self.push(v)))
+        <Self as PackedEntries<V>>::add_packed_from(self, input)
     }
     #[inline]
     fn calculate_size(&self, builder: LengthBuilder, num: FieldNumber) -> Option<LengthBuilder> {
@@ -151,11 +157,7 @@ impl<V: Value + Packable> RepeatedValue<Packed<V>> for RepeatedField<V::Inner> {
                 .build();
 
         output.write_tag(Tag::new(num, WireType::LengthDelimited))?;
-        output.write_length(len)?;
-        for value in self {
-            output.write_value::<V>(value)?;
-        }
-        Ok(())
+        <Self as PackedEntries<V>>::write_packed_to(self, output, len)
     }
     fn is_initialized(&self) -> bool {
         self.iter().all(V::is_initialized)
@@ -168,11 +170,107 @@ impl<V: Clone> Mergable for RepeatedField<V> {
     }
 }
 
+/// An extension trait for repeated fields, providing helpers for fields used as logical sets.
+///
+/// Values only need to be `PartialEq`, so these work with floats and messages, which can't be hashed.
+/// Since values are compared against each other one at a time, `push_unique` takes linear time
+/// and `dedup_retain_order` takes quadratic time in the length of the field. For large fields,
+/// collect the values into a `HashSet` or `BTreeSet` instead.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::collections::{RepeatedField, RepeatedFieldExt};
+///
+/// let mut tags = RepeatedField::new();
+/// assert!(tags.push_unique("foo"));
+/// assert!(tags.push_unique("bar"));
+/// assert!(!tags.push_unique("foo"));
+///
+/// assert_eq!(tags, ["foo", "bar"]);
+/// ```
+pub trait RepeatedFieldExt<T>: Sealed {
+    /// Pushes the value to the end of the field if an equal value isn't already present,
+    /// returning whether the value was inserted.
+    fn push_unique(&mut self, value: T) -> bool;
+    /// Removes all duplicate values from the field, keeping the first occurrence of each value
+    /// and preserving the order of the remaining values.
+    ///
+    /// Each value is compared with every value retained before it, so this takes O(n²) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::collections::{RepeatedField, RepeatedFieldExt};
+    ///
+    /// let mut values: RepeatedField<i32> = vec![3, 1, 3, 2, 1];
+    /// values.dedup_retain_order();
+    ///
+    /// assert_eq!(values, [3, 1, 2]);
+    /// ```
+    fn dedup_retain_order(&mut self);
+}
+
+impl<T: PartialEq> RepeatedFieldExt<T> for RepeatedField<T> {
+    fn push_unique(&mut self, value: T) -> bool {
+        if self.contains(&value) {
+            false
+        } else {
+            self.push(value);
+            true
+        }
+    }
+    fn dedup_retain_order(&mut self) {
+        // swap each first occurrence down into the retained prefix, then cut off the duplicates
+        let mut retained = 0;
+        for i in 0..self.len() {
+            if !self[..retained].contains(&self[i]) {
+                self.swap(retained, i);
+                retained += 1;
+            }
+        }
+        self.truncate(retained);
+    }
+}
+
+/// Packs a repeated bool field into a bitset, with the first value in the lowest bit of the first byte.
+///
+/// Packed bool fields use a full byte for each value on the wire,
+/// so this can be used to store large boolean arrays compactly in an application.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::collections::{bools_to_bits, bits_to_bools};
+///
+/// let values = [true, false, true, true, false, false, false, false, true];
+/// let bits = bools_to_bits(&values);
+/// assert_eq!(bits, [0b0000_1101, 0b0000_0001]);
+///
+/// assert_eq!(bits_to_bools(&bits, values.len()), values);
+/// ```
+pub fn bools_to_bits(values: &[bool]) -> Vec<u8> {
+    values
+        .chunks(8)
+        .map(|chunk| chunk.iter().rev().fold(0, |byte, &b| (byte << 1) | b as u8))
+        .collect()
+}
+
+/// Unpacks the first `len` values of a bitset created by [`bools_to_bits`](fn.bools_to_bits.html) into a repeated bool field.
+///
+/// # Panics
+///
+/// Panics if the bitset has fewer than `len` bits.
+pub fn bits_to_bools(bits: &[u8], len: usize) -> RepeatedField<bool> {
+    assert!(bits.len() * 8 >= len, "the bitset is too short for the requested length");
+    (0..len).map(|i| bits[i / 8] & (1 << (i % 8)) != 0).collect()
+}
+
 /// The type used by generated code to represent a map field.
 pub type MapField<K, V> = std::collections::HashMap<K, V>;
 
-const KEY_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
-const VALUE_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+pub(crate) const KEY_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+pub(crate) const VALUE_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
 
 impl<K, V> Sealed for MapField<K, V> { }
 impl<K, V> RepeatedValue<(K, V)> for MapField<K::Inner, V::Inner>
@@ -185,94 +283,322 @@ impl<K, V> RepeatedValue<(K, V)> for MapField<K::Inner, V::Inner>
     const WIRE_TYPE: WireType = WireType::LengthDelimited;
     
     fn add_entries_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
-        let key_tag = Tag::new(KEY_FIELD, K::WIRE_TYPE);
-        let value_tag = Tag::new(VALUE_FIELD, V::WIRE_TYPE);
-
-        let mut key = None::<K::Inner>;
-        let mut value = None::<V::Inner>;
-        input.read_limit()?.then(|input| {
-            while let Some(field) = input.read_field()? {
-                match field.tag() {
-                    k if k == key_tag.get() => field.and_then(key_tag, |input| input.read_value::<K>().map(|k| key = Some(k))),
-                    v if v == value_tag.get() => field.and_then(value_tag, |input| input.read_value::<V>().map(|v| value = Some(v))),
-                    _ => input.skip(),
-                }?
-            }
-            Ok(())
-        })?;
-        self.insert(key.unwrap_or_default(), value.unwrap_or_default());
+        let (key, value) = input.read_map_entry::<K, V>()?;
+        self.insert(key, value);
 
         Ok(())
     }
     fn calculate_size(&self, builder: LengthBuilder, num: FieldNumber) -> Option<LengthBuilder> {
-        if self.is_empty() {
-            return Some(builder);
-        }
-
-        let len: i32 = self.len().try_into().ok()?;
-        let tag = Tag::new(num, WireType::LengthDelimited);
-        let tag_len = io::raw_varint32_size(tag.get()).get();
-        let start_len = // every size calculation starts with the size of all tags
-            if cfg!(feature = "checked_size") {
-                len.checked_mul(tag_len)?.checked_add(len.checked_mul(2)?)?
-            } else {
-                (len * tag_len) + (len * 2)
-            };
-        let mut builder = builder.add_bytes(Length::new(start_len)?)?;
-        for (key, value) in self {
-            let entry_len = 
-                LengthBuilder::new()
-                    .add_bytes(unsafe { Length::new_unchecked(2) })?
-                    .add_value::<K>(key)?
-                    .add_value::<V>(value)?
-                    .build();
-            builder = builder.add_value::<raw::Uint32>(&(entry_len.get() as u32))?.add_bytes(entry_len)?; // add the length size with the entry size
-        }
-        Some(builder)
+        map_entries_size::<K, V, _>(self.len(), self, builder, num)
     }
     fn write_to<T: Output>(&self, output: &mut CodedWriter<T>, num: FieldNumber) -> write::Result {
-        if self.is_empty() {
-            return Ok(());
-        }
-
-        let tag = Tag::new(num, WireType::LengthDelimited);
-        for (key, value) in self {
-            output.write_tag(tag)?;
-            let length = 
-                LengthBuilder::new()
-                    .add_bytes(unsafe { Length::new_unchecked(2) }).ok_or(write::Error::ValueTooLarge)?
-                    .add_value::<K>(key).ok_or(write::Error::ValueTooLarge)?
-                    .add_value::<V>(value).ok_or(write::Error::ValueTooLarge)?
-                    .build();
-            output.write_length(length)?;
-            output.write_tag(Tag::new(KEY_FIELD, K::WIRE_TYPE))?;
-            output.write_value::<K>(key)?;
-            output.write_tag(Tag::new(VALUE_FIELD, V::WIRE_TYPE))?;
-            output.write_value::<V>(value)?;
-        }
-
-        Ok(())
+        write_map_entries::<K, V, _, _>(self, output, num)
     }
     fn is_initialized(&self) -> bool {
         self.values().all(V::is_initialized)
     }
 }
 
+/// Merges map entries the way protobuf merges map fields: each entry in the other map replaces the entry
+/// with the same key, even if the value is a message. To merge message values in place, use
+/// [`merge_map_in_place`](fn.merge_map_in_place.html).
 impl<K, V> Mergable for std::collections::HashMap<K, V>
     where
         K: Clone + Eq + Hash,
-        V: Clone + Mergable
+        V: Clone
 {
     fn merge(&mut self, other: &Self) {
         for (k, v) in other {
             self.raw_entry_mut() // use a raw entry so we can defer the cloning of the key until we need it
                 .from_key(k)
-                .and_modify(|_, e| e.merge(v))
+                .and_modify(|_, e| e.clone_from(v))
                 .or_insert_with(|| (k.clone(), v.clone()));
         }
     }
 }
 
+/// Merges the entries of another map into this map, merging the values of keys in both maps in place.
+///
+/// This differs from protobuf's map merge semantics, used by the map's `Mergable` implementation, where an entry
+/// replaces the existing value. Merging in place keeps the fields of the existing value that aren't set in the other value.
+///
+/// # Examples
+///
+/// ```ignore
+/// # use protrust::doctest::timestamp::Timestamp;
+/// use protrust::collections::{MapField, merge_map_in_place};
+///
+/// let mut first = Timestamp::new();
+/// *first.seconds_mut() = 5;
+/// let mut second = Timestamp::new();
+/// *second.nanos_mut() = 10;
+///
+/// let mut map = MapField::new();
+/// map.insert(1, first);
+/// let mut other = MapField::new();
+/// other.insert(1, second);
+///
+/// merge_map_in_place(&mut map, &other);
+/// assert_eq!((map[&1].seconds(), map[&1].nanos()), (&5, &10));
+/// ```
+pub fn merge_map_in_place<K, V>(map: &mut MapField<K, V>, other: &MapField<K, V>)
+    where
+        K: Clone + Eq + Hash,
+        V: Clone + Mergable
+{
+    for (k, v) in other {
+        map.raw_entry_mut()
+            .from_key(k)
+            .and_modify(|_, e| e.merge(v))
+            .or_insert_with(|| (k.clone(), v.clone()));
+    }
+}
+
+fn map_entries_size<'a, K, V, I>(len: usize, entries: I, builder: LengthBuilder, num: FieldNumber) -> Option<LengthBuilder>
+    where
+        K: Value,
+        K::Inner: 'a,
+        V: Value,
+        V::Inner: 'a,
+        I: IntoIterator<Item = (&'a K::Inner, &'a V::Inner)>
+{
+    if len == 0 {
+        return Some(builder);
+    }
+
+    let len: i32 = len.try_into().ok()?;
+    let tag = Tag::new(num, WireType::LengthDelimited);
+    let tag_len = io::raw_varint32_size(tag.get()).get();
+    let start_len = // every size calculation starts with the size of all tags, the key and value tags are counted with each entry
+        if cfg!(feature = "checked_size") {
+            len.checked_mul(tag_len)?
+        } else {
+            len * tag_len
+        };
+    let mut builder = builder.add_bytes(Length::new(start_len)?)?;
+    for (key, value) in entries {
+        let entry_len = 
+            LengthBuilder::new()
+                .add_bytes(unsafe { Length::new_unchecked(2) })?
+                .add_value::<K>(key)?
+                .add_value::<V>(value)?
+                .build();
+        builder = builder.add_value::<raw::Uint32>(&(entry_len.get() as u32))?.add_bytes(entry_len)?; // add the length size with the entry size
+    }
+    Some(builder)
+}
+
+fn write_map_entries<'a, K, V, I, T>(entries: I, output: &mut CodedWriter<T>, num: FieldNumber) -> write::Result
+    where
+        K: Value,
+        K::Inner: 'a,
+        V: Value,
+        V::Inner: 'a,
+        I: IntoIterator<Item = (&'a K::Inner, &'a V::Inner)>,
+        T: Output
+{
+    let tag = Tag::new(num, WireType::LengthDelimited);
+    for (key, value) in entries {
+        output.write_tag(tag)?;
+        let length = 
+            LengthBuilder::new()
+                .add_bytes(unsafe { Length::new_unchecked(2) }).ok_or(write::Error::ValueTooLarge)?
+                .add_value::<K>(key).ok_or(write::Error::ValueTooLarge)?
+                .add_value::<V>(value).ok_or(write::Error::ValueTooLarge)?
+                .build();
+        output.write_length(length)?;
+        output.write_tag(Tag::new(KEY_FIELD, K::WIRE_TYPE))?;
+        output.write_value::<K>(key)?;
+        output.write_tag(Tag::new(VALUE_FIELD, V::WIRE_TYPE))?;
+        output.write_value::<V>(value)?;
+    }
+
+    Ok(())
+}
+
+impl<K, V> Sealed for BTreeMap<K, V> { }
+/// A sorted map field. Entries are written in ascending key order, so the output is deterministic.
+impl<K, V> RepeatedValue<(K, V)> for BTreeMap<K::Inner, V::Inner>
+    where 
+        K: Value,
+        K::Inner: Default + Ord,
+        V: Value,
+        V::Inner: Default
+{
+    const WIRE_TYPE: WireType = WireType::LengthDelimited;
+    
+    fn add_entries_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+        let (key, value) = input.read_map_entry::<K, V>()?;
+        self.insert(key, value);
+
+        Ok(())
+    }
+    fn calculate_size(&self, builder: LengthBuilder, num: FieldNumber) -> Option<LengthBuilder> {
+        map_entries_size::<K, V, _>(self.len(), self, builder, num)
+    }
+    fn write_to<T: Output>(&self, output: &mut CodedWriter<T>, num: FieldNumber) -> write::Result {
+        write_map_entries::<K, V, _, _>(self, output, num)
+    }
+    fn is_initialized(&self) -> bool {
+        self.values().all(V::is_initialized)
+    }
+}
+
+/// Merges map entries the way protobuf merges map fields: each entry in the other map replaces the entry
+/// with the same key, even if the value is a message.
+impl<K, V> Mergable for BTreeMap<K, V>
+    where
+        K: Clone + Ord,
+        V: Clone
+{
+    fn merge(&mut self, other: &Self) {
+        for (k, v) in other {
+            match self.get_mut(k) {
+                Some(e) => e.clone_from(v),
+                None => { self.insert(k.clone(), v.clone()); },
+            }
+        }
+    }
+}
+
+/// The differences between two map fields, as returned by [`map_diff`](fn.map_diff.html).
+///
+/// All keys are sorted in ascending order, so the diff of two maps is the same across runs.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MapDiff<'a, K, V> {
+    /// The keys present in the new map but not the old map
+    pub added: Vec<&'a K>,
+    /// The keys present in the old map but not the new map
+    pub removed: Vec<&'a K>,
+    /// The keys present in both maps with different values, along with the old and new values
+    pub changed: Vec<(&'a K, &'a V, &'a V)>,
+}
+
+impl<K, V> MapDiff<'_, K, V> {
+    /// Returns whether the two maps compared were equal
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two map fields, returning the keys that were added, removed, or changed between them.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::collections::{MapField, map_diff};
+///
+/// let mut old = MapField::new();
+/// old.insert("a", 1);
+/// old.insert("b", 2);
+/// old.insert("c", 3);
+///
+/// let mut new = old.clone();
+/// new.remove("a");
+/// new.insert("b", 4);
+/// new.insert("d", 5);
+///
+/// let diff = map_diff(&old, &new);
+/// assert_eq!(diff.added, [&"d"]);
+/// assert_eq!(diff.removed, [&"a"]);
+/// assert_eq!(diff.changed, [(&"b", &2, &4)]);
+/// ```
+pub fn map_diff<'a, K: Ord + Hash, V: PartialEq>(old: &'a MapField<K, V>, new: &'a MapField<K, V>) -> MapDiff<'a, K, V> {
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    for (key, old_value) in old {
+        match new.get(key) {
+            Some(new_value) if new_value != old_value => changed.push((key, old_value, new_value)),
+            Some(_) => { },
+            None => removed.push(key),
+        }
+    }
+    let mut added: Vec<_> = new.keys().filter(|k| !old.contains_key(k)).collect();
+
+    added.sort_unstable();
+    removed.sort_unstable();
+    changed.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+    MapDiff { added, removed, changed }
+}
+
+trait PackedEntries<T> {
+    fn add_packed_from<I: Input>(&mut self, input: &mut CodedReader<I>) -> read::Result<()>;
+    fn write_packed_to<O: Output>(&self, output: &mut CodedWriter<O>, len: Length) -> write::Result;
+}
+
+impl<V> PackedEntries<V> for RepeatedField<V::Inner>
+    where V: Value
+{
+    default fn add_packed_from<I: Input>(&mut self, input: &mut CodedReader<I>) -> read::Result<()> {
+        // varints are at most 10 bytes, so this never reserves more than the number of values in the field
+        let limit = input.read_limit()?;
+        self.reserve(cmp::min(limit.remaining() as usize, io::MAX_PACKED_RESERVE) / 10);
+        limit.for_all(|input| input.read_value::<V>().map(|v| self.push(v)))
+    }
+    default fn write_packed_to<O: Output>(&self, output: &mut CodedWriter<O>, len: Length) -> write::Result {
+        output.write_length(len)?;
+        for value in self {
+            output.write_value::<V>(value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<V> PackedEntries<V> for RepeatedField<V::Inner>
+    where V: raw::ConstSized
+{
+    default fn add_packed_from<I: Input>(&mut self, input: &mut CodedReader<I>) -> read::Result<()> {
+        input.read_packed_into::<V>(self)
+    }
+    default fn write_packed_to<O: Output>(&self, output: &mut CodedWriter<O>, _len: Length) -> write::Result {
+        output.write_fixed_slice::<V>(self)
+    }
+}
+
+impl PackedEntries<raw::Bool> for RepeatedField<bool> {
+    fn add_packed_from<I: Input>(&mut self, input: &mut CodedReader<I>) -> read::Result<()> {
+        // bools are almost always encoded as single byte varints, so decode the run a chunk at a time
+        // instead of reading each value through the reader. the run's length comes from the input, so
+        // like other packed fields, the reservation is bounded until the values are actually read
+        let limit = input.read_limit()?;
+        self.reserve(cmp::min(limit.remaining() as usize, io::MAX_PACKED_RESERVE));
+        limit.then(|input| {
+            let mut buf = [0u8; 1024];
+            let mut value = false;
+            let mut len = 0;
+            while let Some(remaining) = input.bytes_remaining_in_limit().filter(|&r| r != 0) {
+                let chunk = &mut buf[..cmp::min(remaining as usize, 1024)];
+                input.read_raw(chunk)?;
+                if len == 0 && chunk.iter().all(|&b| b <= 1) {
+                    self.extend(chunk.iter().map(|&b| b != 0));
+                    continue;
+                }
+
+                let start = input.position() - chunk.len() as u64;
+                for (&b, i) in chunk.iter().zip(1..) {
+                    value |= b & 0x7F != 0;
+                    len += 1;
+                    if b & 0x80 == 0 {
+                        self.push(value);
+                        value = false;
+                        len = 0;
+                    } else if len == 10 {
+                        return Err(read::Error::new(read::ErrorKind::MalformedVarint, start + i));
+                    }
+                }
+            }
+            if len != 0 {
+                return Err(input.eof());
+            }
+            Ok(())
+        })
+    }
+    fn write_packed_to<O: Output>(&self, output: &mut CodedWriter<O>, _len: Length) -> write::Result {
+        output.write_fixed_slice::<raw::Bool>(self)
+    }
+}
+
 trait ValuesSize<T> {
     fn calculate_size(&self, builder: LengthBuilder) -> Option<LengthBuilder>;
 }
@@ -303,4 +629,358 @@ impl<V> ValuesSize<V> for RepeatedField<V::Inner>
             }
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    mod repeated_field_ext {
+        use crate::collections::{RepeatedField, RepeatedFieldExt};
+
+        #[test]
+        fn push_unique_returns_insertion() {
+            let mut field = RepeatedField::new();
+
+            assert!(field.push_unique(1));
+            assert!(field.push_unique(2));
+            assert!(!field.push_unique(1));
+            assert!(!field.push_unique(2));
+            assert!(field.push_unique(3));
+
+            assert_eq!(field, [1, 2, 3]);
+        }
+
+        #[test]
+        fn dedup_preserves_order() {
+            let mut field: RepeatedField<_> = vec!["c", "a", "c", "b", "a", "a", "d"];
+            field.dedup_retain_order();
+
+            assert_eq!(field, ["c", "a", "b", "d"]);
+        }
+
+        #[test]
+        fn dedup_empty() {
+            let mut field = RepeatedField::<u32>::new();
+            field.dedup_retain_order();
+
+            assert!(field.is_empty());
+        }
+
+        #[test]
+        fn dedup_no_duplicates() {
+            let mut field: RepeatedField<_> = vec![5, 4, 3, 2, 1];
+            field.dedup_retain_order();
+
+            assert_eq!(field, [5, 4, 3, 2, 1]);
+        }
+    }
+    mod packed {
+        use crate::collections::{RepeatedField, RepeatedValue};
+        use crate::io::CodedReader;
+        use crate::raw::{Fixed32, Packed, Uint64};
+
+        #[test]
+        fn fixed_size_reserves_once() {
+            // length prefix of 40,000 bytes
+            let mut input = vec![0xC0, 0xB8, 0x02];
+            input.extend((0..10_000u32).flat_map(|i| i.to_le_bytes().to_vec()));
+
+            let mut field = RepeatedField::<u32>::new();
+            RepeatedValue::<Packed<Fixed32>>::add_entries_from(&mut field, &mut CodedReader::with_slice(&input)).unwrap();
+
+            assert_eq!(field, (0..10_000).collect::<Vec<_>>());
+            assert_eq!(field.capacity(), 10_000);
+        }
+
+        #[test]
+        fn varint_reserve_is_lower_bound() {
+            // ten 10 byte varints
+            let mut input = vec![100];
+            for _ in 0..10 {
+                input.extend_from_slice(&[255, 255, 255, 255, 255, 255, 255, 255, 255, 1]);
+            }
+
+            let mut field = RepeatedField::<u64>::new();
+            RepeatedValue::<Packed<Uint64>>::add_entries_from(&mut field, &mut CodedReader::with_slice(&input)).unwrap();
+
+            assert_eq!(field, [u64::max_value(); 10]);
+            assert_eq!(field.capacity(), 10);
+        }
+    }
+    mod bits {
+        use crate::collections::{bools_to_bits, bits_to_bools};
+
+        #[test]
+        fn round_trip() {
+            let values = (0..100).map(|i| i % 3 == 0).collect::<Vec<_>>();
+            let bits = bools_to_bits(&values);
+
+            assert_eq!(bits.len(), 13);
+            assert_eq!(bits_to_bools(&bits, values.len()), values);
+        }
+
+        #[test]
+        fn empty() {
+            assert!(bools_to_bits(&[]).is_empty());
+            assert!(bits_to_bools(&[], 0).is_empty());
+        }
+
+        #[test]
+        #[should_panic(expected = "the bitset is too short")]
+        fn too_short() {
+            bits_to_bools(&[0], 9);
+        }
+    }
+    mod packed_bool {
+        use crate::collections::{RepeatedField, RepeatedValue};
+        use crate::io::{read, CodedReader};
+        use crate::raw::{Bool, Packed};
+
+        fn read(input: &[u8]) -> read::Result<RepeatedField<bool>> {
+            let mut field = RepeatedField::new();
+            RepeatedValue::<Packed<Bool>>::add_entries_from(&mut field, &mut CodedReader::with_slice(input)).map(|_| field)
+        }
+
+        #[test]
+        fn single_byte_values() {
+            assert_eq!(read(&[4, 1, 0, 0, 1]).unwrap(), [true, false, false, true]);
+        }
+
+        #[test]
+        fn multi_byte_values() {
+            // non-canonical encodings of false and true, followed by a 10 byte true
+            let input = [14, 0x80, 0x00, 0x81, 0x00, 255, 255, 255, 255, 255, 255, 255, 255, 255, 1];
+            assert_eq!(read(&input).unwrap(), [false, true, true]);
+        }
+
+        #[test]
+        fn values_across_chunks() {
+            // a two byte true that starts at the end of the first chunk read
+            let mut input = vec![0x82, 0x08];
+            input.extend(std::iter::repeat(1).take(1023));
+            input.extend(&[0x80, 0x01, 0]);
+            let mut expected = vec![true; 1023];
+            expected.extend(&[true, false]);
+
+            assert_eq!(read(&input).unwrap(), expected);
+            let mut field = RepeatedField::new();
+            RepeatedValue::<Packed<Bool>>::add_entries_from(&mut field, &mut CodedReader::with_stream(&input[..])).unwrap();
+            assert_eq!(field, expected);
+        }
+
+        #[test]
+        fn huge_length_in_stream() {
+            // a 2 GiB packed field with only a few bytes of values
+            let input = [0xFF, 0xFF, 0xFF, 0xFF, 0x07, 1, 0, 1];
+            let mut field = RepeatedField::new();
+            let result = RepeatedValue::<Packed<Bool>>::add_entries_from(&mut field, &mut CodedReader::with_stream(&input[..]));
+
+            assert!(matches!(result.map_err(read::Error::into_kind), Err(read::ErrorKind::IoError(_))));
+            assert!(field.capacity() <= crate::io::MAX_PACKED_RESERVE);
+        }
+
+        #[test]
+        fn malformed_values() {
+            assert!(matches!(read(&[11, 128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 0]).map_err(read::Error::into_kind), Err(read::ErrorKind::MalformedVarint)));
+            assert!(matches!(read(&[2, 1, 128]).map_err(read::Error::into_kind), Err(read::ErrorKind::IoError(_))));
+            assert!(matches!(read(&[2, 1]).map_err(read::Error::into_kind), Err(read::ErrorKind::IoError(_))));
+        }
+    }
+    mod map_size {
+        use crate::collections::MapField;
+        use crate::io::{FieldNumber, Length, LengthBuilder, CodedWriter};
+        use crate::raw::{Int32, String};
+
+        const FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+
+        #[test]
+        fn entry_tags_are_counted_once() {
+            let map: MapField<_, _> = vec![(1, "a".to_string()), (2, "b".to_string())].into_iter().collect();
+
+            // each entry is a tag, a length, a key tag and key, and a value tag, length and value: 7 bytes
+            let len = LengthBuilder::new().add_values::<_, (Int32, String)>(&map, FIELD).unwrap().build();
+            assert_eq!(len, Length::new(14).unwrap());
+
+            let mut output = vec![0; len.get() as usize];
+            let mut writer = CodedWriter::with_slice(&mut output);
+            writer.write_values::<_, (Int32, String)>(&map, FIELD).unwrap();
+            assert!(writer.into_inner().is_empty());
+        }
+    }
+    mod map_merge {
+        use crate::Mergable;
+        use crate::collections::{MapField, merge_map_in_place};
+        use crate::doctest::timestamp::Timestamp;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Scalar(i32);
+
+        #[test]
+        fn scalar_values_are_replaced() {
+            let mut map: MapField<_, _> = vec![(1, Scalar(1)), (2, Scalar(2))].into_iter().collect();
+            let other: MapField<_, _> = vec![(2, Scalar(20)), (3, Scalar(30))].into_iter().collect();
+            map.merge(&other);
+
+            let expected: MapField<_, _> = vec![(1, Scalar(1)), (2, Scalar(20)), (3, Scalar(30))].into_iter().collect();
+            assert_eq!(map, expected);
+        }
+
+        fn timestamps() -> (MapField<i32, Timestamp>, MapField<i32, Timestamp>) {
+            let mut first = Timestamp::new();
+            *first.seconds_mut() = 5;
+            let mut second = Timestamp::new();
+            *second.nanos_mut() = 10;
+
+            (vec![(1, first)].into_iter().collect(), vec![(1, second), (2, Timestamp::new())].into_iter().collect())
+        }
+
+        #[test]
+        fn message_values_are_replaced() {
+            let (mut map, other) = timestamps();
+            map.merge(&other);
+
+            assert_eq!(map, other);
+        }
+
+        #[test]
+        fn message_values_are_merged_in_place() {
+            let (mut map, other) = timestamps();
+            merge_map_in_place(&mut map, &other);
+
+            assert_eq!((map[&1].seconds(), map[&1].nanos()), (&5, &10));
+            assert_eq!(map[&2], Timestamp::new());
+        }
+    }
+    mod map_diff {
+        use crate::collections::{MapField, map_diff};
+
+        #[test]
+        fn equal_maps_are_empty() {
+            let map: MapField<_, _> = (0..10).map(|i| (i, i * 2)).collect();
+
+            assert!(map_diff(&map, &map.clone()).is_empty());
+            assert!(map_diff(&MapField::<u32, u32>::new(), &MapField::new()).is_empty());
+        }
+
+        #[test]
+        fn keys_are_sorted() {
+            let old: MapField<_, _> = (0..50).map(|i| (i, i)).collect();
+            let new: MapField<_, _> = (25..75).map(|i| (i, if i % 5 == 0 { -i } else { i })).collect();
+
+            let diff = map_diff(&old, &new);
+
+            assert_eq!(diff.added, (50..75).collect::<Vec<_>>().iter().collect::<Vec<_>>());
+            assert_eq!(diff.removed, (0..25).collect::<Vec<_>>().iter().collect::<Vec<_>>());
+            assert_eq!(
+                diff.changed.iter().map(|&(&k, &o, &n)| (k, o, n)).collect::<Vec<_>>(),
+                [(25, 25, -25), (30, 30, -30), (35, 35, -35), (40, 40, -40), (45, 45, -45)]);
+        }
+    }
+    mod btree_map {
+        use crate::Mergable;
+        use crate::io::{FieldNumber, LengthBuilder, CodedReader, CodedWriter};
+        use crate::raw::{Int32, String};
+        use std::collections::BTreeMap;
+
+        const FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+
+        fn map() -> BTreeMap<i32, std::string::String> {
+            vec![(3, "c"), (1, "a"), (2, "b")].into_iter().map(|(k, v)| (k, v.to_string())).collect()
+        }
+
+        #[test]
+        fn write_sorted() {
+            let map = map();
+            let len = LengthBuilder::new().add_values::<_, (Int32, String)>(&map, FIELD).unwrap().build();
+            let mut output = vec![0; len.get() as usize];
+            CodedWriter::with_slice(&mut output).write_values::<_, (Int32, String)>(&map, FIELD).unwrap();
+
+            assert_eq!(output, [
+                10, 5, 8, 1, 18, 1, 97,
+                10, 5, 8, 2, 18, 1, 98,
+                10, 5, 8, 3, 18, 1, 99,
+            ]);
+        }
+
+        #[test]
+        fn read_entries() {
+            let input = [10, 5, 8, 3, 18, 1, 99, 10, 5, 18, 1, 97, 8, 1, 10, 5, 8, 2, 18, 1, 98];
+            let mut reader = CodedReader::with_slice(&input);
+            let mut map = BTreeMap::new();
+            while reader.read_tag().unwrap().is_some() {
+                reader.add_entries_to::<_, (Int32, String)>(&mut map).unwrap();
+            }
+
+            assert_eq!(map, self::map());
+        }
+
+        #[test]
+        fn merge_replaces_values() {
+            let mut map = map();
+            let other = vec![(2, "x"), (4, "d")].into_iter().map(|(k, v)| (k, v.to_string())).collect();
+            map.merge(&other);
+
+            assert_eq!(map.into_iter().collect::<Vec<_>>(), [
+                (1, "a".to_string()),
+                (2, "x".to_string()),
+                (3, "c".to_string()),
+                (4, "d".to_string()),
+            ]);
+        }
+
+        #[test]
+        fn merge_replaces_non_mergable_values() {
+            #[derive(Clone, Debug, PartialEq)]
+            struct Plain(i32);
+
+            let mut map: BTreeMap<_, _> = vec![(1, Plain(1)), (2, Plain(2))].into_iter().collect();
+            let other = vec![(2, Plain(20)), (3, Plain(3))].into_iter().collect();
+            map.merge(&other);
+
+            assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, Plain(1)), (2, Plain(20)), (3, Plain(3))]);
+        }
+    }
+    mod boxed_map {
+        use crate::{Mergable, Message};
+        use crate::collections::merge_map_in_place;
+        use crate::doctest::node::Node;
+        use crate::io::CodedWriter;
+
+        fn node(value: i32, children: Vec<(&str, Node)>) -> Node {
+            Node {
+                value,
+                children: children.into_iter().map(|(k, v)| (k.to_string(), Box::new(v))).collect(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn round_trip_recursive() {
+            let tree = node(1, vec![
+                ("a", node(2, vec![("c", node(4, vec![]))])),
+                ("b", node(3, vec![])),
+            ]);
+
+            let mut output = vec![0; tree.calculate_size().unwrap().get() as usize];
+            tree.write_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+
+            assert_eq!(Node::parse_merged_from_slice(&output).unwrap(), tree);
+        }
+
+        #[test]
+        fn merge_replaces_boxed_values() {
+            let mut tree = node(1, vec![("a", node(2, vec![]))]);
+            let other = node(0, vec![("a", node(0, vec![("b", node(3, vec![]))]))]);
+            tree.merge(&other);
+
+            assert_eq!(tree, node(1, vec![("a", node(0, vec![("b", node(3, vec![]))]))]));
+        }
+
+        #[test]
+        fn merge_in_place_merges_boxed_values() {
+            let mut tree = node(1, vec![("a", node(2, vec![]))]);
+            let other = node(0, vec![("a", node(0, vec![("b", node(3, vec![]))]))]);
+            merge_map_in_place(&mut tree.children, &other.children);
+
+            assert_eq!(tree, node(1, vec![("a", node(2, vec![("b", node(3, vec![]))]))]));
+        }
+    }
+}
@@ -7,11 +7,13 @@
 
 use crate::{internal::Sealed, Mergable};
 use crate::io::{read, write, FieldNumber, WireType, Tag, LengthBuilder, CodedReader, CodedWriter, Input, Output};
+use crate::io::read::UnknownFieldHandling;
 use crate::raw;
 use std::collections::{HashMap, hash_map};
 use std::fmt::{self, Formatter, Debug};
 use std::iter::FusedIterator;
 use std::ops::RangeBounds;
+use std::slice;
 use std::vec;
 use super::{FieldSet, TryRead};
 
@@ -47,8 +49,11 @@ impl Mergable for UnknownFieldSet {
 impl FieldSet for UnknownFieldSet {
     #[inline]
     fn try_add_field_from<'a, T: Input>(&mut self, input: &'a mut CodedReader<T>) -> read::Result<TryRead<'a, T>> {
-        if input.unknown_field_handling().skip() || input.last_tag().map(Tag::wire_type) == Some(WireType::EndGroup) {
+        let last_tag = input.last_tag();
+        if input.unknown_field_handling().skip() || last_tag.map(Tag::wire_type) == Some(WireType::EndGroup) {
             Ok(TryRead::Yielded(input))
+        } else if let (UnknownFieldHandling::Error, Some(tag)) = (input.unknown_field_handling(), last_tag) {
+            Err(input.error(read::ErrorKind::UnknownField { number: tag.field() }))
         } else {
             self.add_field_from(input)?;
             Ok(TryRead::Consumed)
@@ -125,7 +130,7 @@ impl FieldSet for UnknownFieldSet {
     fn is_initialized(&self) -> bool { true }
 }
 impl UnknownFieldSet {
-    fn add_field_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+    pub(crate) fn add_field_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
         if let Some(last_tag) = input.last_tag() {
             match last_tag.wire_type() {
                 WireType::Varint => self.push_value(last_tag.field(), UnknownField::Varint(input.read_varint64()?)),
@@ -133,18 +138,11 @@ impl UnknownFieldSet {
                 WireType::LengthDelimited => self.push_value(last_tag.field(), UnknownField::LengthDelimited(input.read_length_delimited()?)),
                 WireType::StartGroup => {
                     let mut group = UnknownFieldSet::new();
-                    let end_tag = Tag::new(last_tag.field(), WireType::EndGroup);
-                    while let Some(tag) = input.read_tag()? {
-                        if tag != end_tag {
-                            input.recurse(|input| group.add_field_from(input))?;
-                        } else {
-                            break;
-                        }
-                    }
+                    input.recurse(|input| input.read_group_fields(|input, _| group.add_field_from(input)))?;
                     self.push_value(last_tag.field(), UnknownField::Group(group));
                 },
                 WireType::Bit32 => self.push_value(last_tag.field(), UnknownField::Bit32(input.read_bit32()?)),
-                WireType::EndGroup => return Err(read::Error::InvalidTag(last_tag.get()))
+                WireType::EndGroup => return Err(input.unexpected_end_group(last_tag))
             }
         }
         Ok(())
@@ -186,6 +184,32 @@ impl UnknownFieldSet {
     pub fn fields(&self) -> Iter {
         Iter(self.inner.iter())
     }
+    /// Returns an iterator over every value in the set along with its field number.
+    /// 
+    /// Unlike [`fields`](#method.fields), this yields values in a stable order: fields are yielded
+    /// in ascending field number order, and the values of each field in the order they were added.
+    /// This is also the iterator used when iterating over a reference to the set.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::UnknownFieldSet;
+    /// use protrust::collections::unknown_fields::UnknownField;
+    /// use protrust::io::FieldNumber;
+    /// 
+    /// let mut set = UnknownFieldSet::new();
+    /// set.push_value(FieldNumber::new(2).unwrap(), UnknownField::Varint(1));
+    /// set.push_value(FieldNumber::new(1).unwrap(), UnknownField::Bit32(2));
+    /// set.push_value(FieldNumber::new(2).unwrap(), UnknownField::Varint(3));
+    /// 
+    /// let entries = set.entries().map(|(n, v)| (n.get(), v.clone())).collect::<Vec<_>>();
+    /// assert_eq!(entries, [(1, UnknownField::Bit32(2)), (2, UnknownField::Varint(1)), (2, UnknownField::Varint(3))]);
+    /// ```
+    pub fn entries(&self) -> Entries {
+        let mut fields = self.inner.iter().map(|(&n, v)| (n, v.iter())).collect::<Vec<_>>();
+        fields.sort_unstable_by_key(|(n, _)| *n);
+        Entries { fields: fields.into_iter(), current: None }
+    }
     /// Returns a mutable iterator of all the fields in the set
     pub fn fields_mut(&mut self) -> IterMut {
         IterMut(self.inner.iter_mut())
@@ -212,6 +236,15 @@ impl UnknownFieldSet {
     }
 }
 
+impl<'a> IntoIterator for &'a UnknownFieldSet {
+    type Item = (FieldNumber, &'a UnknownField);
+    type IntoIter = Entries<'a>;
+
+    fn into_iter(self) -> Entries<'a> {
+        self.entries()
+    }
+}
+
 /// An iterator over the fields of an unknown field set.
 #[derive(Clone, Debug)]
 pub struct Iter<'a>(hash_map::Iter<'a, FieldNumber, Vec<UnknownField>>);
@@ -320,6 +353,29 @@ impl ExactSizeIterator for Drain<'_> {
 }
 impl FusedIterator for Drain<'_> { }
 
+/// An iterator over the values of an unknown field set and their field numbers, in ascending field number order.
+#[derive(Clone, Debug)]
+pub struct Entries<'a> {
+    fields: vec::IntoIter<(FieldNumber, slice::Iter<'a, UnknownField>)>,
+    current: Option<(FieldNumber, slice::Iter<'a, UnknownField>)>,
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = (FieldNumber, &'a UnknownField);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((num, values)) = &mut self.current {
+                if let Some(value) = values.next() {
+                    return Some((*num, value));
+                }
+            }
+            self.current = Some(self.fields.next()?);
+        }
+    }
+}
+impl FusedIterator for Entries<'_> { }
+
 /// A draining iterator that returns the unknown fields for a single field.
 /// 
 /// This `struct` is created by the [`drain_field`] method on [`UnknownFieldSet`].
@@ -374,5 +430,65 @@ impl FusedIterator for FieldDrain<'_> { }
 
 #[cfg(test)]
 mod test {
-    
+    use crate::collections::FieldSet;
+    use crate::collections::unknown_fields::{UnknownFieldSet, UnknownField};
+    use crate::io::{read, FieldNumber, CodedReader};
+
+    fn read_set(input: &[u8]) -> read::Result<UnknownFieldSet> {
+        let mut reader = CodedReader::with_slice(input);
+        let mut set = UnknownFieldSet::new();
+        while reader.read_tag()?.is_some() {
+            set.try_add_field_from(&mut reader)?.or_skip()?;
+        }
+        Ok(set)
+    }
+
+    #[test]
+    fn entries_are_ordered() {
+        let set = read_set(&[24, 1, 8, 2, 29, 3, 0, 0, 0, 8, 4, 18, 0]).unwrap();
+        let entries = (&set).into_iter().map(|(n, v)| (n.get(), v.clone())).collect::<Vec<_>>();
+
+        assert_eq!(entries, [
+            (1, UnknownField::Varint(2)),
+            (1, UnknownField::Varint(4)),
+            (2, UnknownField::LengthDelimited(Box::new([]))),
+            (3, UnknownField::Varint(1)),
+            (3, UnknownField::Bit32(3)),
+        ]);
+        assert_eq!(UnknownFieldSet::new().entries().next(), None);
+    }
+
+    #[test]
+    fn read_nested_group() {
+        let set = read_set(&[11, 19, 8, 1, 20, 16, 2, 12]).expect("input is a valid group");
+
+        let mut inner = UnknownFieldSet::new();
+        inner.push_value(FieldNumber::new(1).unwrap(), UnknownField::Varint(1));
+        let mut outer = UnknownFieldSet::new();
+        outer.push_value(FieldNumber::new(2).unwrap(), UnknownField::Group(inner));
+        outer.push_value(FieldNumber::new(2).unwrap(), UnknownField::Varint(2));
+
+        assert_eq!(set.values(FieldNumber::new(1).unwrap()), &[UnknownField::Group(outer)]);
+    }
+
+    #[test]
+    fn read_crossed_group() {
+        let result = read_set(&[11, 19, 12, 20]);
+
+        assert!(matches!(result.as_ref().map_err(read::Error::kind), Err(read::ErrorKind::GroupMismatch { start, end }) if start.get() == 2 && end.get() == 1), "got {:?}", result);
+    }
+
+    #[test]
+    fn read_unterminated_group() {
+        let result = read_set(&[11, 8, 1]);
+
+        assert!(matches!(result.as_ref().map_err(read::Error::kind), Err(read::ErrorKind::IoError(_))), "got {:?}", result);
+    }
+
+    #[test]
+    fn read_stray_end_group() {
+        let result = read_set(&[8, 1, 12]);
+
+        assert!(matches!(result.as_ref().map_err(read::Error::kind), Err(read::ErrorKind::InvalidTag(12))), "got {:?}", result);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,3 @@
+//! Compatibility shims for code migrating from other protobuf libraries.
+
+pub mod prost;
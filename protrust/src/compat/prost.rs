@@ -0,0 +1,112 @@
+//! An extension trait providing [`prost`](https://docs.rs/prost)-like methods for messages.
+//!
+//! Importing [`ProstMessage`] lets call sites written against `prost::Message` keep their
+//! `encode`, `encode_to_vec`, `decode`, and `encoded_len` calls while the message types are replaced with
+//! protrust messages. The methods take slices and vectors instead of prost's `Buf` and `BufMut` traits,
+//! and return protrust's read and write errors.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! # use protrust::doctest::timestamp::Timestamp;
+//! use protrust::compat::prost::ProstMessage;
+//!
+//! let mut timestamp = Timestamp::new();
+//! *timestamp.seconds_mut() = 5;
+//!
+//! let encoded = timestamp.encode_to_vec();
+//! assert_eq!(encoded.len(), timestamp.encoded_len());
+//!
+//! let decoded = Timestamp::decode(&encoded)?;
+//! assert_eq!(decoded, timestamp);
+//! # Ok::<(), protrust::io::read::Error>(())
+//! ```
+//!
+//! [`ProstMessage`]: trait.ProstMessage.html
+
+use crate::Message;
+use crate::io::{read, write, CodedWriter};
+
+/// Provides prost-like encoding and decoding methods for all messages.
+pub trait ProstMessage: Message {
+    /// Encodes the message, appending it to the buffer.
+    ///
+    /// Returns `ValueTooLarge` if the message's size overflows an `i32`.
+    /// Nothing is appended to the buffer if an error is returned.
+    fn encode(&self, buf: &mut Vec<u8>) -> write::Result;
+    /// Encodes the message into a new `Vec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message's size overflows an `i32`.
+    fn encode_to_vec(&self) -> Vec<u8>;
+    /// Decodes a new message from the buffer, reading fields until the end of the buffer.
+    fn decode(buf: &[u8]) -> read::Result<Self>;
+    /// Returns the encoded length of the message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the message's size overflows an `i32`.
+    fn encoded_len(&self) -> usize;
+}
+
+impl<M: Message> ProstMessage for M {
+    fn encode(&self, buf: &mut Vec<u8>) -> write::Result {
+        let len = self.calculate_size().ok_or(write::Error::ValueTooLarge)?.get() as usize;
+        let start = buf.len();
+        buf.resize(start + len, 0);
+        let result = self.write_to(&mut CodedWriter::with_slice(&mut buf[start..]));
+        if result.is_err() {
+            buf.truncate(start);
+        }
+        result
+    }
+    fn encode_to_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode_into(&mut buf).expect("message size overflowed an i32");
+        buf
+    }
+    fn decode(buf: &[u8]) -> read::Result<Self> {
+        Self::parse_merged_from_slice(buf)
+    }
+    fn encoded_len(&self) -> usize {
+        self.calculate_size().expect("message size overflowed an i32").get() as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::doctest::timestamp::Timestamp;
+    use super::ProstMessage;
+
+    #[test]
+    fn encode_appends() {
+        let mut timestamp = Timestamp::new();
+        *timestamp.seconds_mut() = 5;
+        *timestamp.nanos_mut() = 100;
+
+        let mut buf = vec![0xFF];
+        timestamp.encode(&mut buf).unwrap();
+        assert_eq!(buf, [0xFF, 8, 5, 16, 100]);
+        assert_eq!(timestamp.encoded_len(), 4);
+        assert_eq!(timestamp.encode_to_vec(), [8, 5, 16, 100]);
+
+        let mut buf = Vec::with_capacity(16);
+        buf.extend_from_slice(&[1, 2]);
+        let ptr = buf.as_ptr();
+        timestamp.encode(&mut buf).unwrap();
+        timestamp.encode(&mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 8, 5, 16, 100, 8, 5, 16, 100]);
+        assert_eq!(buf.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn decode_roundtrips() {
+        let mut timestamp = Timestamp::new();
+        *timestamp.seconds_mut() = 10;
+
+        let decoded = Timestamp::decode(&timestamp.encode_to_vec()).unwrap();
+        assert_eq!(decoded, timestamp);
+        assert!(Timestamp::decode(&[8]).is_err());
+    }
+}
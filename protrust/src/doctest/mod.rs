@@ -1 +1,3 @@
+pub mod node;
+pub mod required;
 pub mod timestamp;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use crate::{UnknownFieldSet, Mergable, Message};
+use crate::collections::MapField;
+use crate::io::{read, write, CodedReader, Input, CodedWriter, Output, FieldNumber, Tag, WireType, Length, LengthBuilder};
+use crate::raw as r;
+
+type Children = (r::String, r::Boxed<r::Message<Node>>);
+
+/// A recursive message, with a group field and a map field of itself:
+/// 
+/// ```text
+/// message Node {
+///   int32 value = 1;
+///   group Nested = 2 { ... }
+///   map<string, Node> children = 3;
+/// }
+/// ```
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Node {
+    pub value: i32,
+    pub nested: Option<Box<Node>>,
+    pub children: MapField<String, Box<Node>>,
+    pub unknown_fields: UnknownFieldSet,
+}
+
+impl Node {
+    pub fn new() -> Node {
+        Default::default()
+    }
+
+    pub const VALUE_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+    const VALUE_TAG: u32 = Tag::new(Self::VALUE_NUMBER, WireType::Varint).get();
+
+    pub const NESTED_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+    const NESTED_TAG: u32 = Tag::new(Self::NESTED_NUMBER, WireType::StartGroup).get();
+
+    pub const CHILDREN_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(3) };
+    const CHILDREN_TAG: u32 = Tag::new(Self::CHILDREN_NUMBER, WireType::LengthDelimited).get();
+}
+
+impl Mergable for Node {
+    fn merge(&mut self, other: &Self) {
+        if other.value != 0 {
+            self.value = other.value;
+        }
+        if let Some(nested) = &other.nested {
+            self.nested.get_or_insert_with(Default::default).merge(nested);
+        }
+        self.children.merge(&other.children);
+        self.unknown_fields.merge(&other.unknown_fields);
+    }
+}
+
+impl Message for Node {
+    fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+        while let Some(field) = input.read_field()? {
+            match field.tag() {
+                Self::VALUE_TAG => field.merge_value::<r::Int32>(Self::VALUE_NUMBER, &mut self.value)?,
+                Self::NESTED_TAG => field.merge_value::<r::Group<Node>>(Self::NESTED_NUMBER, self.nested.get_or_insert_with(Default::default))?,
+                Self::CHILDREN_TAG => field.add_entries_to::<_, Children>(Self::CHILDREN_NUMBER, &mut self.children)?,
+                _ => field.check_and_try_add_field_to(&mut self.unknown_fields)?.or_skip()?,
+            }
+        }
+        Ok(())
+    }
+    fn calculate_size(&self) -> Option<Length> {
+        let mut builder = LengthBuilder::new();
+        if self.value != 0 {
+            builder = builder.add_field::<r::Int32>(Self::VALUE_NUMBER, &self.value)?;
+        }
+        if let Some(nested) = &self.nested {
+            builder = builder.add_field::<r::Group<Node>>(Self::NESTED_NUMBER, nested)?;
+        }
+        builder = builder.add_values::<_, Children>(&self.children, Self::CHILDREN_NUMBER)?;
+        builder = builder.add_fields(&self.unknown_fields)?;
+
+        Some(builder.build())
+    }
+    fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
+        if self.value != 0 {
+            output.write_field::<r::Int32>(Self::VALUE_NUMBER, &self.value)?;
+        }
+        if let Some(nested) = &self.nested {
+            output.write_field::<r::Group<Node>>(Self::NESTED_NUMBER, nested)?;
+        }
+        output.write_values::<_, Children>(&self.children, Self::CHILDREN_NUMBER)?;
+        output.write_fields(&self.unknown_fields)?;
+        Ok(())
+    }
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn unknown_fields(&self) -> &UnknownFieldSet {
+        &self.unknown_fields
+    }
+    fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet {
+        &mut self.unknown_fields
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{UnknownFieldSet, Mergable, Message};
+use crate::io::{read, write, CodedReader, Input, CodedWriter, Output, FieldNumber, Tag, WireType, Length, LengthBuilder};
+use crate::raw as r;
+
+/// A message with required fields and an optional field of itself:
+/// 
+/// ```text
+/// message Required {
+///   required int32 a = 1;
+///   required int32 b = 2;
+///   required int32 c = 3;
+///   optional Required child = 4;
+/// }
+/// ```
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Required {
+    a: Option<i32>,
+    b: Option<i32>,
+    c: Option<i32>,
+    child: Option<Box<Required>>,
+    unknown_fields: UnknownFieldSet,
+}
+
+impl Required {
+    pub fn new() -> Required {
+        Default::default()
+    }
+
+    pub const A_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+    const A_TAG: u32 = Tag::new(Self::A_NUMBER, WireType::Varint).get();
+    pub fn a(&self) -> Option<&i32> {
+        self.a.as_ref()
+    }
+    pub fn a_mut(&mut self) -> &mut i32 {
+        self.a.get_or_insert(0)
+    }
+
+    pub const B_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+    const B_TAG: u32 = Tag::new(Self::B_NUMBER, WireType::Varint).get();
+    pub fn b(&self) -> Option<&i32> {
+        self.b.as_ref()
+    }
+    pub fn b_mut(&mut self) -> &mut i32 {
+        self.b.get_or_insert(0)
+    }
+
+    pub const C_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(3) };
+    const C_TAG: u32 = Tag::new(Self::C_NUMBER, WireType::Varint).get();
+    pub fn c(&self) -> Option<&i32> {
+        self.c.as_ref()
+    }
+    pub fn c_mut(&mut self) -> &mut i32 {
+        self.c.get_or_insert(0)
+    }
+
+    pub const CHILD_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(4) };
+    const CHILD_TAG: u32 = Tag::new(Self::CHILD_NUMBER, WireType::LengthDelimited).get();
+    pub fn child(&self) -> Option<&Required> {
+        self.child.as_deref()
+    }
+    pub fn child_mut(&mut self) -> &mut Required {
+        self.child.get_or_insert_with(Default::default)
+    }
+}
+
+impl Mergable for Required {
+    fn merge(&mut self, other: &Self) {
+        if other.a.is_some() {
+            self.a = other.a;
+        }
+        if other.b.is_some() {
+            self.b = other.b;
+        }
+        if other.c.is_some() {
+            self.c = other.c;
+        }
+        if let Some(child) = &other.child {
+            self.child_mut().merge(child);
+        }
+        self.unknown_fields.merge(&other.unknown_fields);
+    }
+}
+
+impl Message for Required {
+    fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+        while let Some(field) = input.read_field()? {
+            match field.tag() {
+                Self::A_TAG => field.merge_value::<r::Int32>(Self::A_NUMBER, self.a_mut())?,
+                Self::B_TAG => field.merge_value::<r::Int32>(Self::B_NUMBER, self.b_mut())?,
+                Self::C_TAG => field.merge_value::<r::Int32>(Self::C_NUMBER, self.c_mut())?,
+                Self::CHILD_TAG => field.merge_value::<r::Message<Required>>(Self::CHILD_NUMBER, self.child_mut())?,
+                _ => field.check_and_try_add_field_to(&mut self.unknown_fields)?.or_skip()?,
+            }
+        }
+        Ok(())
+    }
+    fn calculate_size(&self) -> Option<Length> {
+        let mut builder = LengthBuilder::new();
+        if let Some(a) = &self.a {
+            builder = builder.add_field::<r::Int32>(Self::A_NUMBER, a)?;
+        }
+        if let Some(b) = &self.b {
+            builder = builder.add_field::<r::Int32>(Self::B_NUMBER, b)?;
+        }
+        if let Some(c) = &self.c {
+            builder = builder.add_field::<r::Int32>(Self::C_NUMBER, c)?;
+        }
+        if let Some(child) = &self.child {
+            builder = builder.add_field::<r::Message<Required>>(Self::CHILD_NUMBER, child)?;
+        }
+        builder = builder.add_fields(&self.unknown_fields)?;
+
+        Some(builder.build())
+    }
+    fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
+        if let Some(a) = &self.a {
+            output.write_field::<r::Int32>(Self::A_NUMBER, a)?;
+        }
+        if let Some(b) = &self.b {
+            output.write_field::<r::Int32>(Self::B_NUMBER, b)?;
+        }
+        if let Some(c) = &self.c {
+            output.write_field::<r::Int32>(Self::C_NUMBER, c)?;
+        }
+        if let Some(child) = &self.child {
+            output.write_field::<r::Message<Required>>(Self::CHILD_NUMBER, child)?;
+        }
+        output.write_fields(&self.unknown_fields)?;
+        Ok(())
+    }
+    fn is_initialized(&self) -> bool {
+        self.a.is_some() && self.b.is_some() && self.c.is_some() &&
+        self.child.as_ref().map_or(true, |c| c.is_initialized())
+    }
+    fn missing_fields(&self, path: &str, missing: &mut Vec<String>) {
+        let join = |name: &str| if path.is_empty() { name.to_string() } else { format!("{}.{}", path, name) };
+        let fields = [("a", self.a.is_none()), ("b", self.b.is_none()), ("c", self.c.is_none())];
+        for &(name, unset) in &fields {
+            if unset {
+                missing.push(join(name));
+            }
+        }
+        if let Some(child) = &self.child {
+            child.missing_fields(&join("child"), missing);
+        }
+    }
+
+    fn unknown_fields(&self) -> &UnknownFieldSet {
+        &self.unknown_fields
+    }
+    fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet {
+        &mut self.unknown_fields
+    }
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.a == other.a &&
+        self.b == other.b &&
+        self.c == other.c &&
+        match (&self.child, &other.child) {
+            (Some(a), Some(b)) => a.semantic_eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{UnknownFieldSet, Mergable, Message};
-use crate::io::{read, write, CodedReader, Input, CodedWriter, Output, FieldNumber, Tag, LengthBuilder};
+use crate::io::{read, write, CodedReader, Input, CodedWriter, Output, FieldNumber, Tag, WireType, Length, LengthBuilder};
 use crate::raw as r;
 
 #[derive(Default, Clone, Debug, PartialEq)]
@@ -10,7 +10,12 @@ pub struct Timestamp {
 }
 
 impl Timestamp {
+    pub fn new() -> Timestamp {
+        Default::default()
+    }
+
     pub const SECONDS_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+    const SECONDS_TAG: u32 = Tag::new(Self::SECONDS_NUMBER, WireType::Varint).get();
     pub fn seconds(&self) -> &i64 {
         &self.seconds
     }
@@ -19,6 +24,7 @@ impl Timestamp {
     }
 
     pub const NANOS_NUMBER: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+    const NANOS_TAG: u32 = Tag::new(Self::NANOS_NUMBER, WireType::Varint).get();
     pub fn nanos(&self) -> &i32 {
         &self.nanos
     }
@@ -43,14 +49,15 @@ impl Message for Timestamp {
     fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
         while let Some(field) = input.read_field()? {
             match field.tag() {
-                8 => field.read_value(unsafe { Tag::new_unchecked(8) }, |input| input.merge_value::<r::Int64>(&mut self.seconds))?,
-                16 => field.read_value(unsafe { Tag::new_unchecked(16) }, |input| input.merge_value::<r::Int32>(&mut self.nanos))?,
-                _ => field.check_and_read_value(|input| input.try_add_field_to(&mut self.unknown_fields)?.or_skip())?,
+                Self::SECONDS_TAG => field.merge_value::<r::Int64>(Self::SECONDS_NUMBER, &mut self.seconds)?,
+                Self::NANOS_TAG => field.merge_value::<r::Int32>(Self::NANOS_NUMBER, &mut self.nanos)?,
+                _ => field.check_and_try_add_field_to(&mut self.unknown_fields)?.or_skip()?,
             }
         }
         Ok(())
     }
-    fn calculate_size(&self, mut builder: LengthBuilder) -> Option<LengthBuilder> {
+    fn calculate_size(&self) -> Option<Length> {
+        let mut builder = LengthBuilder::new();
         if self.seconds != 0 {
             builder =
                 builder.add_field::<r::Int64>(Self::SECONDS_NUMBER, self.seconds())?;
@@ -62,7 +69,7 @@ impl Message for Timestamp {
         builder =
             builder.add_fields(&self.unknown_fields)?;
 
-        Some(builder)
+        Some(builder.build())
     }
     fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
         if self.seconds != 0 {
@@ -84,4 +91,8 @@ impl Message for Timestamp {
     fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet {
         &mut self.unknown_fields
     }
+    fn semantic_eq(&self, other: &Self) -> bool {
+        self.seconds == other.seconds &&
+        self.nanos == other.nanos
+    }
 }
\ No newline at end of file
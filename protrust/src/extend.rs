@@ -1,10 +1,12 @@
 //! Types and traits for working with proto2 extensions
 
-use crate::Mergable;
+use crate::UnknownFieldSet;
+use crate::collections::unknown_fields::UnknownField;
 use crate::collections::{RepeatedField, FieldSet, TryRead};
 use crate::internal::Sealed;
-use crate::io::{read::{self, Input}, write::{self, Output}, FieldNumber, WireType, Tag, LengthBuilder, CodedReader, CodedWriter};
-use crate::raw::{ValueType, Value, Packable, Packed};
+use crate::io::{read::{self, Input, UnknownFieldHandling}, write::{self, Output}, raw_varint32_size, FieldNumber, WireType, Tag, Length, LengthBuilder, CodedReader, CodedWriter};
+use crate::io::{MESSAGE_SET_ITEM_START, MESSAGE_SET_ITEM_END, MESSAGE_SET_TYPE_ID, MESSAGE_SET_MESSAGE};
+use crate::raw::{self, ValueType, Value, Packable, Packed};
 use std::any::TypeId;
 use std::borrow::{Borrow, Cow, ToOwned};
 use std::collections::{HashMap, hash_map};
@@ -13,10 +15,11 @@ use std::marker::PhantomData;
 use std::mem;
 
 mod internal {
-    use crate::{Mergable, merge};
+    use crate::merge;
+    use crate::internal::MergeValue;
     use crate::collections::{RepeatedField, RepeatedValue};
-    use crate::io::{read, write, FieldNumber, WireType, Tag, LengthBuilder, CodedReader, CodedWriter};
-    use crate::raw::{ValueType, Value, Packable, Packed};
+    use crate::io::{read, write, FieldNumber, WireType, Tag, LengthBuilder, CodedReader, CodedWriter, MESSAGE_SET_MESSAGE};
+    use crate::raw::{self, ValueType, Value, Packable, Packed};
     use std::any::{Any, TypeId};
     use std::fmt::{self, Debug, Formatter};
     use super::ExtendableMessage;
@@ -52,6 +55,32 @@ mod internal {
         fn calculate_size(&self, builder: LengthBuilder) -> Option<LengthBuilder>;
         fn write_to(&self, output: &mut CodedWriter<write::Any>) -> write::Result;
         fn is_initialized(&self) -> bool;
+        /// Returns whether this is a singular message extension, the only kind of extension that can extend a MessageSet
+        fn is_message(&self) -> bool { false }
+        /// Writes this extension as a MessageSet item, returning an `InvalidMessageSetItem` error if it isn't a singular message extension
+        fn write_message_set_item_to(&self, _: &mut CodedWriter<write::Any>) -> write::Result {
+            Err(write::Error::InvalidMessageSetItem(self.field_number()))
+        }
+    }
+
+    /// Specialized over message values to find and write singular message extensions
+    trait MessageValue: Value {
+        fn is_message() -> bool;
+        fn write_message_set_item(num: FieldNumber, value: &Self::Inner, output: &mut CodedWriter<write::Any>) -> write::Result;
+    }
+
+    impl<V: Value> MessageValue for V {
+        default fn is_message() -> bool { false }
+        default fn write_message_set_item(num: FieldNumber, _: &V::Inner, _: &mut CodedWriter<write::Any>) -> write::Result {
+            Err(write::Error::InvalidMessageSetItem(num))
+        }
+    }
+
+    impl<M: crate::Message> MessageValue for raw::Message<M> {
+        fn is_message() -> bool { true }
+        fn write_message_set_item(num: FieldNumber, value: &M, output: &mut CodedWriter<write::Any>) -> write::Result {
+            output.write_message_set_item_with(num, |output| output.write_field::<Self>(MESSAGE_SET_MESSAGE.field(), value))
+        }
     }
 
     pub struct ExtensionValue<V: ValueType> {
@@ -74,7 +103,7 @@ mod internal {
     impl<V> AnyExtension for ExtensionValue<V>
         where
             V: Value + 'static,
-            V::Inner: Clone + Mergable + PartialEq + Debug + Send + Sync
+            V::Inner: Clone + PartialEq + Debug + Send + Sync
     {
         fn clone_into_box(&self) -> Box<dyn AnyExtension> {
             Box::new(
@@ -88,7 +117,7 @@ mod internal {
             assert_eq!(TypeId::of::<Self>(), other.type_id());
 
             let other: &Self = unsafe { &*(other as *const dyn AnyExtension as *const Self) };
-            merge(&mut self.value, &other.value);
+            self.value.merge_value(&other.value);
         }
         fn eq(&self, other: &dyn AnyExtension) -> bool {
             assert_eq!(TypeId::of::<Self>(), other.type_id());
@@ -114,6 +143,12 @@ mod internal {
         fn is_initialized(&self) -> bool {
             V::is_initialized(&self.value)
         }
+        fn is_message(&self) -> bool {
+            <V as MessageValue>::is_message()
+        }
+        fn write_message_set_item_to(&self, output: &mut CodedWriter<write::Any>) -> write::Result {
+            <V as MessageValue>::write_message_set_item(self.num, &self.value, output)
+        }
     }
 
     impl<V> Debug for ExtensionValue<V>
@@ -263,6 +298,35 @@ pub trait ExtendableMessage: Sized {
     fn extensions(&self) -> &ExtensionSet<Self>;
     /// Returns a mutable unique reference to the extension set in this message
     fn extensions_mut(&mut self) -> &mut ExtensionSet<Self>;
+
+    /// Gets the value of the specified extension if it's set. If the extension is not set, this returns None.
+    fn get_extension<U: ExtensionType<Extended = Self>>(&self, extension: &U) -> Option<&U::Value>
+        where Self: 'static
+    {
+        self.extensions().value(extension)
+    }
+    /// Gets the value of the specified extension or the extension's default value if it's not set.
+    /// If the extension is not set and has no default value, this returns None.
+    fn get_extension_or_default<'a, 'e: 'a, V, D>(&'a self, extension: &'e Extension<Self, V, D>) -> Option<&'a D>
+        where
+            Self: 'static,
+            V: Value + 'static,
+            V::Inner: Borrow<D> + Clone + PartialEq + Debug + Send + Sync,
+            D: ?Sized + ToOwned<Owned = V::Inner> + Sync + 'static
+    {
+        self.extensions().value_or_default(extension)
+    }
+    /// Sets the value of the specified extension, returning the extension's old value if it was set.
+    ///
+    /// # Errors
+    ///
+    /// If the extension isn't in the registry used by this message's extension set, the value isn't set
+    /// and is returned as an error.
+    fn set_extension<U: ExtensionType<Extended = Self>>(&mut self, extension: &U, value: U::Value) -> Result<Option<U::Value>, U::Value>
+        where Self: 'static
+    {
+        self.extensions_mut().set_value(extension, value)
+    }
 }
 
 /// An extension identifier for accessing an extension value from an ExtensionSet
@@ -280,7 +344,7 @@ impl<T, V, D> ExtensionIdentifier for Extension<T, V, D>
     where
         T: ExtendableMessage + 'static,
         V: Value + 'static,
-        V::Inner: Mergable + Borrow<D> + PartialEq + Clone + Debug + Send + Sync,
+        V::Inner: Borrow<D> + PartialEq + Clone + Debug + Send + Sync,
         D: ?Sized + ToOwned<Owned = V::Inner> + Sync + 'static
 {
     fn field_number(&self) -> FieldNumber {
@@ -303,7 +367,7 @@ impl<T, V, D> ExtensionType for Extension<T, V, D>
     where
         T: ExtendableMessage + 'static,
         V: Value + 'static,
-        V::Inner: Mergable + Borrow<D> + PartialEq + Clone + Debug + Send + Sync,
+        V::Inner: Borrow<D> + PartialEq + Clone + Debug + Send + Sync,
         D: ?Sized + ToOwned<Owned = V::Inner> + Sync + 'static
 {
     type Entry = internal::ExtensionValue<V>;
@@ -364,6 +428,16 @@ pub struct RepeatedExtension<T, V: ValueType> {
     num: FieldNumber
 }
 
+#[doc(hidden)]
+impl<T, V: ValueType> RepeatedExtension<T, V> {
+    pub const fn new(num: FieldNumber) -> Self {
+        Self {
+            t: PhantomData,
+            num
+        }
+    }
+}
+
 impl<T, V> ExtensionIdentifier for RepeatedExtension<T, V>
     where
         T: ExtendableMessage + 'static,
@@ -461,11 +535,56 @@ impl<T, V> ExtensionType for RepeatedExtension<T, Packed<V>>
 
 
 /// A registry used to contain all the extensions from a generated code module
+/// 
+/// Extendable messages only parse extension fields found in the registry they use,
+/// and other extension fields are read as unknown fields. A registry is given to a [`CodedReader`]
+/// with [`set_registry`] or [`Builder::registry`], and messages read by the reader use the reader's registry.
+/// 
+/// Messages keep a reference to their registry, so registries are borrowed for the `'static` lifetime.
+/// A registry built at runtime can be stored in a lazily initialized static, or leaked with `Box::leak`
+/// if it's only built once. Registries can't be changed after they're borrowed, so all extensions should
+/// be registered before the registry is given to a reader.
+/// 
+/// Top level messages aren't created by the reader, so the registry should be set on the message's
+/// extension set with [`replace_registry`] before it's merged.
+/// 
+/// [`CodedReader`]: ../io/read/struct.CodedReader.html
+/// [`set_registry`]: ../io/read/struct.CodedReader.html#method.set_registry
+/// [`Builder::registry`]: ../io/read/struct.Builder.html#method.registry
+/// [`replace_registry`]: struct.ExtensionSet.html#method.replace_registry
+#[derive(Default)]
 pub struct ExtensionRegistry {
     by_num: HashMap<(TypeId, FieldNumber), &'static dyn ExtensionIdentifier>
 }
 
+fn insert_identifier(
+    by_num: &mut HashMap<(TypeId, FieldNumber), &'static dyn ExtensionIdentifier>,
+    id: &'static dyn ExtensionIdentifier) -> Result<(), ExtensionConflict>
+{
+    let num = id.field_number();
+    match by_num.entry((id.message_type(), num)) {
+        hash_map::Entry::Occupied(_) => Err(ExtensionConflict(num)),
+        hash_map::Entry::Vacant(entry) => {
+            entry.insert(id);
+            Ok(())
+        }
+    }
+}
+
 impl ExtensionRegistry {
+    /// Creates a new registry without any extensions
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Adds an extension to this registry.
+    /// 
+    /// # Errors
+    /// 
+    /// If the registry already contains an extension to the same message with the same field number,
+    /// the extension isn't added and this returns an error.
+    pub fn register(&mut self, id: &'static dyn ExtensionIdentifier) -> Result<(), ExtensionConflict> {
+        insert_identifier(&mut self.by_num, id)
+    }
     /// Returns whether an extension registry contains the extension field
     pub fn contains<T: ?Sized + ExtensionIdentifier>(&self, id: &T) -> bool {
         self.by_num
@@ -507,12 +626,7 @@ impl RegistryBuilder {
     /// Adds an extension identifier to this registry
     #[inline]
     pub fn add_identifier(mut self, id: &'static dyn ExtensionIdentifier) -> Result<Self, ExtensionConflict> {
-        let num = id.field_number();
-        let typ = id.message_type();
-        match self.by_num.insert((typ, num), id) {
-            Some(_) => Err(ExtensionConflict(num)),
-            None => Ok(self)
-        }
+        insert_identifier(&mut self.by_num, id).map(|_| self)
     }
     /// Returns the extension registry
     #[inline]
@@ -561,6 +675,26 @@ impl<T: ExtendableMessage + 'static> ExtensionSet<T> {
         mem::replace(&mut self.registry, new)
     }
 
+    /// Returns the number of extension fields with values in this set
+    pub fn len(&self) -> usize {
+        self.by_num.len()
+    }
+    /// Returns whether no extension fields in this set have values
+    pub fn is_empty(&self) -> bool {
+        self.by_num.is_empty()
+    }
+    /// Returns whether an extension field with the specified field number has a value in this set
+    pub fn contains(&self, num: FieldNumber) -> bool {
+        self.by_num.contains_key(&num)
+    }
+    /// Returns an iterator over the field numbers of all extension fields with values in this set.
+    /// Field numbers are yielded in ascending order.
+    pub fn field_numbers(&self) -> impl Iterator<Item = FieldNumber> {
+        let mut nums: Vec<_> = self.by_num.keys().copied().collect();
+        nums.sort_unstable();
+        nums.into_iter()
+    }
+
     /// Returns whether the specified extension is contained in the registry used by this set
     /// and if the field has a set value.
     pub fn has_extension<U: ?Sized + ExtensionIdentifier>(&self, extension: &U) -> bool {
@@ -586,12 +720,28 @@ impl<T: ExtendableMessage + 'static> ExtensionSet<T> {
     pub fn value_or_default<'a, 'e: 'a, V, D>(&'a self, extension: &'e Extension<T, V, D>) -> Option<&'a D>
         where
             V: Value + 'static,
-            V::Inner: Borrow<D> + Mergable + Clone + PartialEq + Debug + Send + Sync,
+            V::Inner: Borrow<D> + Clone + PartialEq + Debug + Send + Sync,
             D: ?Sized + ToOwned<Owned = V::Inner> + Sync + 'static
     {
         self.value(extension).map(|v| v.borrow()).or_else(|| extension.default.as_ref().map(|v| v.borrow()))
     }
 
+    /// Sets the value of the specified extension, returning the extension's old value if it was set.
+    ///
+    /// # Errors
+    ///
+    /// If the extension isn't in the registry used by this set, the value isn't set and is returned as an error.
+    pub fn set_value<U: ExtensionType<Extended = T>>(&mut self, extension: &U, value: U::Value) -> Result<Option<U::Value>, U::Value> {
+        match self.field(extension) {
+            Some(Field::Occupied(mut field)) => Ok(Some(field.insert(value))),
+            Some(Field::Vacant(field)) => {
+                field.insert(value);
+                Ok(None)
+            },
+            None => Err(value),
+        }
+    }
+
     /// Returns a Field which can be used to modify an extension value
     pub fn field<'a, 'e, U: 'e + ExtensionType<Extended = T>>(&'a mut self, extension: &'e U) -> Option<Field<'a, 'e, U>> {
         if self.registry_contains(extension) {
@@ -605,6 +755,130 @@ impl<T: ExtendableMessage + 'static> ExtensionSet<T> {
     }
 }
 
+/// Support for the legacy proto2 MessageSet wire format, used by messages with the `message_set_wire_format` option.
+/// 
+/// In a MessageSet, each extension is written as a group item (field 1) containing the extension's
+/// field number as a `type_id` (field 2) and the extension's encoded message as `message` (field 3).
+/// Only singular message extensions can extend a MessageSet.
+impl<T: ExtendableMessage + 'static> ExtensionSet<T> {
+    /// Merges a MessageSet item from the input into the set, returning whether the item's extension was in the set's registry.
+    /// 
+    /// This should be called after reading the item's start group tag (field 1). Items for
+    /// extensions that aren't in the registry are added to the unknown field set as a group, so they're
+    /// written back out unchanged, unless the reader is set to skip unknown fields. If the reader is set to
+    /// error on unknown fields, an `UnknownField` error is returned with the item's type id.
+    pub fn merge_message_set_item_from<U: Input>(&mut self, input: &mut CodedReader<U>, unknown_fields: &mut UnknownFieldSet) -> read::Result<bool> {
+        let mut type_id = None;
+        let mut buffered = None;
+        let mut known = false;
+        let mut item = UnknownFieldSet::new();
+        input.recurse(|input| input.read_group_fields(|input, tag| {
+            match (tag, type_id) {
+                (MESSAGE_SET_TYPE_ID, _) => {
+                    let id = input.read_varint32()?;
+                    type_id = Some(id);
+                    item.push_value(tag.field(), UnknownField::Varint(id.into()));
+                },
+                // the type id is normally written first, which lets the message be read straight from the input
+                (MESSAGE_SET_MESSAGE, Some(id)) => {
+                    let num = FieldNumber::new(id).ok_or_else(|| input.error(read::ErrorKind::InvalidTag(id)))?;
+                    input.set_last_tag(Some(Tag::new(num, WireType::LengthDelimited)));
+                    match self.try_add_field_from(input)? {
+                        TryRead::Consumed => known = true,
+                        TryRead::Yielded(input) => {
+                            let message = input.read_length_delimited()?;
+                            item.push_value(tag.field(), UnknownField::LengthDelimited(message));
+                        },
+                    }
+                },
+                (MESSAGE_SET_MESSAGE, None) => buffered = Some((input.position(), input.read_length_delimited::<Vec<u8>>()?)),
+                _ => item.add_field_from(input)?,
+            }
+            Ok(())
+        }))?;
+
+        if let (Some(id), Some((offset, message))) = (type_id, buffered) {
+            let num = FieldNumber::new(id).ok_or_else(|| input.error(read::ErrorKind::InvalidTag(id)))?;
+            let mut field = CodedWriter::with_capacity(message.len() + 10, Vec::new());
+            let field = 
+                field.write_varint32(Tag::new(num, WireType::LengthDelimited).get())
+                    .and_then(|()| field.write_length_delimited(&message))
+                    .and_then(|()| field.finish())
+                    .map_err(|e| input.error(read::ErrorKind::IoError(e.into())))?;
+
+            let mut reader = CodedReader::with_slice(&field);
+            reader.set_registry(input.registry());
+            reader.read_tag()?;
+            let tag_len = reader.position();
+            known = match self.try_add_field_from(&mut reader) {
+                Ok(TryRead::Consumed) => true,
+                Ok(TryRead::Yielded(_)) => {
+                    item.push_value(MESSAGE_SET_MESSAGE.field(), UnknownField::LengthDelimited(message.into_boxed_slice()));
+                    false
+                },
+                // the message was read from the input after its length prefix without the tag written before it here
+                Err(e) => {
+                    let at = offset + e.offset() - tag_len;
+                    return Err(read::Error::new(e.into_kind(), at));
+                },
+            };
+        }
+
+        if !known {
+            match input.unknown_field_handling() {
+                UnknownFieldHandling::Store => unknown_fields.push_value(MESSAGE_SET_ITEM_START.field(), UnknownField::Group(item)),
+                UnknownFieldHandling::Skip => { },
+                UnknownFieldHandling::Error => {
+                    // report the item's extension field, or the item itself if it doesn't have a valid type id
+                    let number = type_id.and_then(FieldNumber::new).unwrap_or_else(|| MESSAGE_SET_ITEM_START.field());
+                    return Err(input.error(read::ErrorKind::UnknownField { number }));
+                },
+            }
+        }
+
+        Ok(known)
+    }
+    /// Calculates the size of the set's extensions written as MessageSet items.
+    /// 
+    /// This returns `None` if the size overflows or if the set contains an extension
+    /// that isn't a singular message extension, which can't be written as a MessageSet item.
+    pub fn calculate_message_set_size(&self, builder: LengthBuilder) -> Option<LengthBuilder> {
+        self.by_num.values().try_fold(builder, |builder, field| {
+            if !field.is_message() {
+                return None;
+            }
+            let num = field.field_number();
+            let size = field.calculate_size(LengthBuilder::new())?.build().get();
+            // a message extension is written as its tag followed by the length prefixed message
+            let tag_size = raw_varint32_size(Tag::new(num, WireType::LengthDelimited).get()).get();
+            builder
+                .add_tag(MESSAGE_SET_ITEM_START)?
+                .add_tag(MESSAGE_SET_TYPE_ID)?
+                .add_value::<raw::Uint32>(&num.get())?
+                .add_tag(MESSAGE_SET_MESSAGE)?
+                .add_bytes(Length::new(size - tag_size)?)?
+                .add_tag(MESSAGE_SET_ITEM_END)
+        })
+    }
+    /// Writes the set's extensions to the output as MessageSet items.
+    /// 
+    /// # Errors
+    /// 
+    /// If the set contains an extension that isn't a singular message extension, this returns an
+    /// `InvalidMessageSetItem` error before writing anything to the output.
+    pub fn write_message_set_to<U: Output>(&self, output: &mut CodedWriter<U>) -> write::Result {
+        if let Some(field) = self.by_num.values().find(|field| !field.is_message()) {
+            return Err(write::Error::InvalidMessageSetItem(field.field_number()));
+        }
+
+        let mut output = output.as_any();
+        for field in self.by_num.values() {
+            field.write_message_set_item_to(&mut output)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T: ExtendableMessage + 'static> Sealed for ExtensionSet<T> { }
 impl<T: ExtendableMessage + 'static> FieldSet for ExtensionSet<T> {
     fn try_add_field_from<'a, U: Input>(&mut self, input: &'a mut CodedReader<U>) -> read::Result<TryRead<'a, U>> {
@@ -780,4 +1054,289 @@ impl<T: ExtendableMessage> Default for ExtensionSet<T> {
             by_num: Default::default()
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::extend::{ExtendableMessage, ExtensionSet, ExtensionRegistry, RegistryBuilder, Extension, RepeatedExtension};
+    use std::sync::Once;
+    use crate::doctest::timestamp::Timestamp;
+    use crate::collections::unknown_fields::UnknownField;
+    use crate::io::{read::{self, UnknownFieldHandling}, write, FieldNumber, Length, LengthBuilder, CodedReader, CodedWriter};
+    use crate::raw::{self, Int32, String};
+    use crate::UnknownFieldSet;
+
+    #[derive(Default)]
+    struct TestMessage {
+        extensions: ExtensionSet<TestMessage>,
+    }
+
+    impl ExtendableMessage for TestMessage {
+        fn extensions(&self) -> &ExtensionSet<Self> {
+            &self.extensions
+        }
+        fn extensions_mut(&mut self) -> &mut ExtensionSet<Self> {
+            &mut self.extensions
+        }
+    }
+
+    static INT_EXT: Extension<TestMessage, Int32> = Extension::with_no_default(unsafe { FieldNumber::new_unchecked(100) });
+    static STRING_EXT: Extension<TestMessage, String, str> = Extension::with_static_default(unsafe { FieldNumber::new_unchecked(20) }, "foo");
+    static REPEATED_EXT: RepeatedExtension<TestMessage, Int32> = RepeatedExtension::new(unsafe { FieldNumber::new_unchecked(5) });
+
+    static MESSAGE_EXT: Extension<TestMessage, raw::Message<Timestamp>> = Extension::with_no_default(unsafe { FieldNumber::new_unchecked(1000) });
+
+    static UNREGISTERED_EXT: Extension<TestMessage, Int32> = Extension::with_owned_default(unsafe { FieldNumber::new_unchecked(101) }, 10);
+
+    fn registry() -> &'static ExtensionRegistry {
+        static INIT: Once = Once::new();
+        static mut REGISTRY: Option<ExtensionRegistry> = None;
+
+        unsafe {
+            INIT.call_once(|| {
+                REGISTRY = Some(
+                    RegistryBuilder::new()
+                        .add_identifier(&INT_EXT).ok().unwrap()
+                        .add_identifier(&STRING_EXT).ok().unwrap()
+                        .add_identifier(&REPEATED_EXT).ok().unwrap()
+                        .add_identifier(&MESSAGE_EXT).ok().unwrap()
+                        .build());
+            });
+            REGISTRY.as_ref().unwrap()
+        }
+    }
+
+    fn new_message() -> TestMessage {
+        TestMessage { extensions: new_set() }
+    }
+
+    fn new_set() -> ExtensionSet<TestMessage> {
+        let mut set = ExtensionSet::new();
+        set.replace_registry(Some(registry()));
+        set
+    }
+
+    #[test]
+    fn empty_set_has_no_fields() {
+        let set = new_set();
+
+        assert!(set.is_empty());
+        assert_eq!(set.len(), 0);
+        assert_eq!(set.field_numbers().next(), None);
+        assert!(!set.contains(FieldNumber::new(100).unwrap()));
+    }
+
+    #[test]
+    fn field_numbers_are_ascending() {
+        let mut set = new_set();
+        set.field(&INT_EXT).unwrap().or_insert(5);
+        set.field(&STRING_EXT).unwrap().or_insert("bar".to_string());
+        set.field(&REPEATED_EXT).unwrap().or_insert(vec![1, 2]);
+
+        assert!(!set.is_empty());
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.field_numbers().map(FieldNumber::get).collect::<Vec<_>>(), [5, 20, 100]);
+        assert!(set.contains(FieldNumber::new(20).unwrap()));
+        assert!(!set.contains(FieldNumber::new(21).unwrap()));
+    }
+
+    #[test]
+    fn removed_fields_are_not_listed() {
+        let mut set = new_set();
+        set.field(&INT_EXT).unwrap().or_insert(5);
+        set.field(&STRING_EXT).unwrap().or_insert("bar".to_string());
+
+        if let Some(crate::extend::Field::Occupied(field)) = set.field(&INT_EXT) {
+            field.remove();
+        }
+
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.field_numbers().map(FieldNumber::get).collect::<Vec<_>>(), [20]);
+    }
+
+    #[test]
+    fn get_unset_extension() {
+        let message = new_message();
+
+        assert_eq!(message.get_extension(&INT_EXT), None);
+        assert_eq!(message.get_extension_or_default(&INT_EXT), None);
+        assert_eq!(message.get_extension(&STRING_EXT), None);
+        assert_eq!(message.get_extension_or_default(&STRING_EXT), Some("foo"));
+        assert!(message.extensions().is_empty());
+    }
+
+    #[test]
+    fn set_replaces_value() {
+        let mut message = new_message();
+
+        assert_eq!(message.set_extension(&INT_EXT, 5), Ok(None));
+        assert_eq!(message.get_extension(&INT_EXT), Some(&5));
+        assert_eq!(message.get_extension_or_default(&INT_EXT), Some(&5));
+
+        assert_eq!(message.set_extension(&INT_EXT, 10), Ok(Some(5)));
+        assert_eq!(message.get_extension(&INT_EXT), Some(&10));
+
+        assert_eq!(message.set_extension(&STRING_EXT, "bar".to_string()), Ok(None));
+        assert_eq!(message.get_extension_or_default(&STRING_EXT), Some("bar"));
+
+        assert_eq!(message.set_extension(&REPEATED_EXT, vec![1, 2]), Ok(None));
+        assert_eq!(message.get_extension(&REPEATED_EXT), Some(&vec![1, 2]));
+
+        assert_eq!(message.extensions().len(), 3);
+    }
+
+    fn runtime_registry() -> &'static ExtensionRegistry {
+        static INIT: Once = Once::new();
+        static mut REGISTRY: Option<ExtensionRegistry> = None;
+
+        unsafe {
+            INIT.call_once(|| {
+                let mut registry = ExtensionRegistry::new();
+                registry.register(&INT_EXT).ok().unwrap();
+                registry.register(&REPEATED_EXT).ok().unwrap();
+                assert!(registry.register(&INT_EXT).is_err());
+                REGISTRY = Some(registry);
+            });
+            REGISTRY.as_ref().unwrap()
+        }
+    }
+
+    #[test]
+    fn registered_extensions_are_read() {
+        // field 100 = 5, field 5 = 1, field 20 = "a"
+        let input = [0xA0, 0x06, 5, 40, 1, 0xA2, 0x01, 1, 97];
+        let mut reader = CodedReader::with_slice(&input);
+        reader.set_registry(Some(runtime_registry()));
+
+        let mut unknown_fields = UnknownFieldSet::new();
+        let mut set = ExtensionSet::<TestMessage>::new();
+        set.replace_registry(reader.registry());
+        while let Some(field) = reader.read_field().unwrap() {
+            field.check_and_try_add_field_to(&mut set).unwrap()
+                .or_try(&mut unknown_fields).unwrap()
+                .or_skip().unwrap();
+        }
+
+        assert_eq!(set.value(&INT_EXT), Some(&5));
+        assert_eq!(set.value(&REPEATED_EXT), Some(&vec![1]));
+        assert_eq!(set.len(), 2);
+        // the string extension isn't in the registry, so it's read as an unknown field
+        assert_eq!(unknown_fields.values(FieldNumber::new(20).unwrap()).len(), 1);
+        assert_eq!(unknown_fields.field_len(), 1);
+    }
+
+    fn read_message_set(input: &[u8]) -> (ExtensionSet<TestMessage>, Vec<bool>) {
+        let (set, known, _) = read_message_set_with_unknown(input);
+        (set, known)
+    }
+
+    fn read_message_set_with_unknown(input: &[u8]) -> (ExtensionSet<TestMessage>, Vec<bool>, UnknownFieldSet) {
+        let mut set = new_set();
+        let mut unknown_fields = UnknownFieldSet::new();
+        let mut known = Vec::new();
+        let mut reader = CodedReader::with_slice(input);
+        while let Some(field) = reader.read_field().unwrap() {
+            assert_eq!(field.tag(), 11);
+            known.push(field.check_and_then(|r| set.merge_message_set_item_from(r, &mut unknown_fields)).unwrap());
+        }
+        (set, known, unknown_fields)
+    }
+
+    fn timestamp(seconds: i64) -> Timestamp {
+        let mut t = Timestamp::new();
+        *t.seconds_mut() = seconds;
+        t
+    }
+
+    #[test]
+    fn message_set_items_are_read() {
+        // type_id 1000 with seconds = 5, then an item for type_id 2000 which isn't registered
+        let input = [11, 16, 0xE8, 0x07, 26, 2, 8, 5, 12, 11, 16, 0xD0, 0x0F, 26, 0, 12];
+        let (set, known) = read_message_set(&input);
+
+        assert_eq!(known, [true, false]);
+        assert_eq!(set.value(&MESSAGE_EXT), Some(&timestamp(5)));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn unknown_message_set_items_are_kept() {
+        // an item for type_id 2000 which isn't registered, written with the type id first and with the message first
+        for input in &[&[11, 16, 0xD0, 0x0F, 26, 2, 8, 5, 12][..], &[11, 26, 2, 8, 5, 16, 0xD0, 0x0F, 12][..]] {
+            let (set, known, unknown_fields) = read_message_set_with_unknown(input);
+            assert_eq!(known, [false]);
+            assert!(set.is_empty());
+
+            let len = Length::of_fields(&unknown_fields).unwrap();
+            let mut output = vec![0; len.get() as usize];
+            CodedWriter::with_slice(&mut output).write_fields(&unknown_fields).unwrap();
+            assert_eq!(read_message_set_with_unknown(&output).2, unknown_fields);
+
+            let item = match unknown_fields.values(FieldNumber::new(1).unwrap()) {
+                [UnknownField::Group(item)] => item,
+                values => panic!("unexpected values: {:?}", values),
+            };
+            assert_eq!(item.values(FieldNumber::new(2).unwrap()), [UnknownField::Varint(2000)]);
+            assert_eq!(item.values(FieldNumber::new(3).unwrap()), [UnknownField::LengthDelimited(Box::new([8, 5]))]);
+        }
+    }
+
+    #[test]
+    fn unknown_message_set_items_error() {
+        let input = [11, 16, 0xD0, 0x0F, 26, 2, 8, 5, 12];
+        let mut set = new_set();
+        let mut unknown_fields = UnknownFieldSet::new();
+        let mut reader = read::Builder::new().unknown_fields(UnknownFieldHandling::Error).with_slice(&input);
+        let field = reader.read_field().unwrap().unwrap();
+        let err = field.check_and_then(|r| set.merge_message_set_item_from(r, &mut unknown_fields)).unwrap_err();
+
+        assert!(matches!(err.kind(), read::ErrorKind::UnknownField { number } if number.get() == 2000));
+        assert!(unknown_fields.is_empty());
+    }
+
+    #[test]
+    fn non_message_extensions_are_not_message_set_items() {
+        let mut set = new_set();
+        set.set_value(&MESSAGE_EXT, timestamp(5)).unwrap();
+        set.set_value(&INT_EXT, 5).unwrap();
+
+        assert!(set.calculate_message_set_size(LengthBuilder::new()).is_none());
+
+        let mut output = [0; 32];
+        let mut writer = CodedWriter::with_slice(&mut output);
+        let err = set.write_message_set_to(&mut writer).unwrap_err();
+        assert!(matches!(err, write::Error::InvalidMessageSetItem(num) if num.get() == 100));
+        assert_eq!(writer.remaining(), 32);
+    }
+
+    #[test]
+    fn message_set_item_with_message_first() {
+        let input = [11, 26, 2, 8, 5, 16, 0xE8, 0x07, 12];
+        let (set, known) = read_message_set(&input);
+
+        assert_eq!(known, [true]);
+        assert_eq!(set.value(&MESSAGE_EXT), Some(&timestamp(5)));
+    }
+
+    #[test]
+    fn message_set_round_trip() {
+        let mut set = new_set();
+        set.set_value(&MESSAGE_EXT, timestamp(5)).unwrap();
+
+        let size = set.calculate_message_set_size(LengthBuilder::new()).unwrap().build();
+        let mut output = vec![0; size.get() as usize];
+        set.write_message_set_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+
+        assert_eq!(output, [11, 16, 0xE8, 0x07, 26, 2, 8, 5, 12]);
+        assert_eq!(read_message_set(&output).0.value(&MESSAGE_EXT), Some(&timestamp(5)));
+    }
+
+    #[test]
+    fn set_unregistered_extension() {
+        let mut message = new_message();
+
+        assert_eq!(message.set_extension(&UNREGISTERED_EXT, 5), Err(5));
+        assert_eq!(message.get_extension(&UNREGISTERED_EXT), None);
+        assert!(message.extensions().is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Functions for combining the paths of a `google.protobuf.FieldMask`.
+//!
+//! A field mask is a list of paths, where each path is a series of field names separated by a `.`.
+//! A path covers itself and every path nested under it, so the mask `["a"]` selects the same fields as `["a", "a.b"]`.
+//!
+//! These functions take and return the paths of a mask, so they can be used with any `FieldMask` type.
+//! The returned paths are always normalized.
+
+use std::cmp::Ordering;
+
+/// Returns true if the path is the same as or nested under the other path
+fn is_covered_by(path: &str, by: &str) -> bool {
+    path.starts_with(by) && (path.len() == by.len() || path.as_bytes()[by.len()] == b'.')
+}
+
+/// Compares paths field name by field name, which orders a path directly before the paths nested under it
+fn cmp_paths(a: &str, b: &str) -> Ordering {
+    a.split('.').cmp(b.split('.'))
+}
+
+/// Normalizes the paths of a field mask, sorting them and removing duplicate paths
+/// and paths covered by another path in the mask.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::field_mask;
+///
+/// let paths = ["b", "a.b", "a", "b"].iter().map(|&s| s.to_string()).collect::<Vec<_>>();
+/// assert_eq!(field_mask::normalize(&paths), ["a", "b"]);
+/// ```
+pub fn normalize(paths: &[String]) -> Vec<String> {
+    let mut sorted = paths.iter().map(String::as_str).collect::<Vec<_>>();
+    sorted.sort_unstable_by(|a, b| cmp_paths(a, b));
+
+    let mut normalized: Vec<String> = Vec::with_capacity(sorted.len());
+    for path in sorted {
+        // since paths are sorted, any path covering this one is the last path added
+        match normalized.last() {
+            Some(last) if is_covered_by(path, last) => { },
+            _ => normalized.push(path.to_string()),
+        }
+    }
+    normalized
+}
+
+/// Returns the normalized union of two field masks, which covers every path covered by either mask.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::field_mask;
+///
+/// let a = vec!["a.b".to_string(), "c".to_string()];
+/// let b = vec!["a".to_string(), "d.e".to_string()];
+/// assert_eq!(field_mask::union(&a, &b), ["a", "c", "d.e"]);
+/// ```
+pub fn union(a: &[String], b: &[String]) -> Vec<String> {
+    let mut paths = Vec::with_capacity(a.len() + b.len());
+    paths.extend_from_slice(a);
+    paths.extend_from_slice(b);
+    normalize(&paths)
+}
+
+/// Returns the normalized intersection of two field masks, which covers the paths covered by both masks.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::field_mask;
+///
+/// let a = vec!["a".to_string(), "c.d".to_string()];
+/// let b = vec!["a.b".to_string(), "c".to_string(), "e".to_string()];
+/// assert_eq!(field_mask::intersect(&a, &b), ["a.b", "c.d"]);
+/// ```
+pub fn intersect(a: &[String], b: &[String]) -> Vec<String> {
+    let a = normalize(a);
+    let b = normalize(b);
+
+    let mut paths = Vec::new();
+    for x in &a {
+        for y in &b {
+            if is_covered_by(x, y) {
+                paths.push(x.clone());
+            } else if is_covered_by(y, x) {
+                paths.push(y.clone());
+            }
+        }
+    }
+    normalize(&paths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{normalize, union, intersect};
+
+    fn paths(p: &[&str]) -> Vec<String> {
+        p.iter().map(|&s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_removes_covered_paths() {
+        assert_eq!(normalize(&paths(&["a.b", "a"])), ["a"]);
+        assert_eq!(normalize(&paths(&["a.b.c", "a.b", "d"])), ["a.b", "d"]);
+        assert_eq!(normalize(&paths(&["a", "a"])), ["a"]);
+        assert!(normalize(&[]).is_empty());
+    }
+
+    #[test]
+    fn normalize_compares_whole_field_names() {
+        // "ab" and "a-b" aren't nested under "a", and while "a-b" sorts between "a" and "a.b" as a string,
+        // it doesn't keep "a.b" from being removed
+        assert_eq!(normalize(&paths(&["ab", "a", "a.b"])), ["a", "ab"]);
+        assert_eq!(normalize(&paths(&["a.b", "a-b", "a"])), ["a", "a-b"]);
+        assert_eq!(normalize(&paths(&["a.bc", "a.b"])), ["a.b", "a.bc"]);
+    }
+
+    #[test]
+    fn union_overlapping_masks() {
+        assert_eq!(union(&paths(&["a.b"]), &paths(&["a"])), ["a"]);
+        assert_eq!(union(&paths(&["a.b", "c"]), &paths(&["a.c", "c.d"])), ["a.b", "a.c", "c"]);
+        assert_eq!(union(&paths(&["a"]), &[]), ["a"]);
+    }
+
+    #[test]
+    fn intersect_overlapping_masks() {
+        assert_eq!(intersect(&paths(&["a"]), &paths(&["a.b", "a.c"])), ["a.b", "a.c"]);
+        assert_eq!(intersect(&paths(&["a.b", "a"]), &paths(&["a"])), ["a"]);
+        assert_eq!(intersect(&paths(&["a.b.c", "d"]), &paths(&["a.b", "e"])), ["a.b.c"]);
+        assert!(intersect(&paths(&["a"]), &paths(&["ab", "b"])).is_empty());
+        assert!(intersect(&paths(&["a"]), &[]).is_empty());
+    }
+}
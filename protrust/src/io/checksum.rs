@@ -0,0 +1,243 @@
+//! Defines streams that checksum the bytes passing through them, for framing encoded messages with a checksum trailer.
+//!
+//! [`ChecksummedWriter`] tees every byte written to an inner [`Write`] through a [`Checksum`],
+//! and [`ChecksummedReader`] does the same for bytes read from an inner [`Read`].
+//! Both can be used as the stream of a [`CodedWriter`] or [`CodedReader`].
+//!
+//! A [`CodedReader`] over a stream may read ahead of the value it's parsing to fill its buffer,
+//! so when checksumming a frame inside a larger stream the reader should be limited to the frame's payload
+//! (for example with [`Read::take`]) before it's wrapped.
+//!
+//! # Examples
+//!
+//! ```
+//! use protrust::io::{CodedReader, CodedWriter};
+//! use protrust::io::checksum::{ChecksummedReader, ChecksummedWriter};
+//!
+//! let mut writer = ChecksummedWriter::new(Vec::new());
+//! let mut output = CodedWriter::with_stream(&mut writer);
+//! output.write_varint32(150)?;
+//! output.flush()?;
+//! drop(output);
+//!
+//! let crc = writer.checksum();
+//! let payload = writer.into_inner();
+//! assert_eq!(payload, [150, 1]);
+//!
+//! let mut reader = ChecksummedReader::new(&payload[..]);
+//! let mut input = CodedReader::with_stream(&mut reader);
+//! assert_eq!(input.read_varint32()?, 150);
+//! drop(input);
+//!
+//! reader.verify(crc)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`ChecksummedWriter`]: struct.ChecksummedWriter.html
+//! [`ChecksummedReader`]: struct.ChecksummedReader.html
+//! [`Checksum`]: trait.Checksum.html
+//! [`CodedWriter`]: ../write/struct.CodedWriter.html
+//! [`CodedReader`]: ../read/struct.CodedReader.html
+//! [`Write`]: https://doc.rust-lang.org/nightly/std/io/trait.Write.html
+//! [`Read`]: https://doc.rust-lang.org/nightly/std/io/trait.Read.html
+//! [`Read::take`]: https://doc.rust-lang.org/nightly/std/io/trait.Read.html#method.take
+
+use std::io::{self, Read, Write, ErrorKind};
+
+/// A checksum algorithm that can be updated with bytes as they pass through a stream.
+pub trait Checksum {
+    /// Updates the checksum with the specified bytes.
+    fn update(&mut self, bytes: &[u8]);
+    /// Returns the checksum of all the bytes passed to `update` so far.
+    fn finish(&self) -> u32;
+}
+
+/// The CRC-32 (IEEE) checksum, as used by zlib, PNG, and most record formats.
+///
+/// # Examples
+///
+/// ```
+/// use protrust::io::checksum::{Checksum, Crc32};
+///
+/// let mut crc = Crc32::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), 0xCBF4_3926);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Crc32 {
+    state: u32,
+}
+
+impl Crc32 {
+    const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+    /// Creates a new checksum over no bytes.
+    pub fn new() -> Crc32 {
+        Crc32 { state: 0 }
+    }
+}
+
+impl Checksum for Crc32 {
+    fn update(&mut self, bytes: &[u8]) {
+        let mut crc = !self.state;
+        for &b in bytes {
+            crc ^= u32::from(b);
+            for _ in 0..8 {
+                crc = (crc >> 1) ^ (Self::POLYNOMIAL & (crc & 1).wrapping_neg());
+            }
+        }
+        self.state = !crc;
+    }
+    fn finish(&self) -> u32 {
+        self.state
+    }
+}
+
+/// A writer that updates a checksum with every byte written to the inner writer.
+#[derive(Debug)]
+pub struct ChecksummedWriter<W, C = Crc32> {
+    inner: W,
+    checksum: C,
+}
+
+impl<W: Write> ChecksummedWriter<W> {
+    /// Creates a new writer computing a CRC-32 checksum over the bytes written to the inner writer.
+    pub fn new(inner: W) -> Self {
+        Self::with_checksum(inner, Crc32::new())
+    }
+}
+
+impl<W: Write, C: Checksum> ChecksummedWriter<W, C> {
+    /// Creates a new writer computing the specified checksum over the bytes written to the inner writer.
+    pub fn with_checksum(inner: W, checksum: C) -> Self {
+        ChecksummedWriter { inner, checksum }
+    }
+    /// Returns the checksum of all the bytes written so far.
+    pub fn checksum(&self) -> u32 {
+        self.checksum.finish()
+    }
+    /// Gets a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Returns ownership of the inner writer, discarding the checksum.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, C: Checksum> Write for ChecksummedWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.checksum.update(&buf[..written]);
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A reader that updates a checksum with every byte read from the inner reader.
+#[derive(Debug)]
+pub struct ChecksummedReader<R, C = Crc32> {
+    inner: R,
+    checksum: C,
+}
+
+impl<R: Read> ChecksummedReader<R> {
+    /// Creates a new reader computing a CRC-32 checksum over the bytes read from the inner reader.
+    pub fn new(inner: R) -> Self {
+        Self::with_checksum(inner, Crc32::new())
+    }
+}
+
+impl<R: Read, C: Checksum> ChecksummedReader<R, C> {
+    /// Creates a new reader computing the specified checksum over the bytes read from the inner reader.
+    pub fn with_checksum(inner: R, checksum: C) -> Self {
+        ChecksummedReader { inner, checksum }
+    }
+    /// Returns the checksum of all the bytes read so far.
+    pub fn checksum(&self) -> u32 {
+        self.checksum.finish()
+    }
+    /// Checks the bytes read so far match the expected checksum,
+    /// returning an [`InvalidData`](https://doc.rust-lang.org/nightly/std/io/enum.ErrorKind.html#variant.InvalidData)
+    /// error if they don't.
+    pub fn verify(&self, expected: u32) -> io::Result<()> {
+        let actual = self.checksum();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(io::Error::new(ErrorKind::InvalidData, format!("checksum mismatch: expected {:#010x}, got {:#010x}", expected, actual)))
+        }
+    }
+    /// Gets a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns ownership of the inner reader, discarding the checksum.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, C: Checksum> Read for ChecksummedReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.checksum.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{ErrorKind, Read, Write};
+    use super::{Checksum, Crc32, ChecksummedReader, ChecksummedWriter};
+
+    #[test]
+    fn crc32_known_values() {
+        let crc = |b: &[u8]| {
+            let mut c = Crc32::new();
+            c.update(b);
+            c.finish()
+        };
+
+        assert_eq!(crc(b""), 0);
+        assert_eq!(crc(b"a"), 0xE8B7_BE43);
+        assert_eq!(crc(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+
+    /// A writer that only accepts one byte per write
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.extend(buf.first());
+            Ok(buf.len().min(1))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writer_checksums_written_bytes() {
+        let mut writer = ChecksummedWriter::new(Trickle(Vec::new()));
+        writer.write_all(b"123456789").unwrap();
+
+        assert_eq!(writer.checksum(), 0xCBF4_3926);
+        assert_eq!(writer.into_inner().0, b"123456789");
+    }
+
+    #[test]
+    fn reader_verifies_checksum() {
+        let mut reader = ChecksummedReader::new(&b"123456789"[..]);
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+
+        assert!(reader.verify(0xCBF4_3926).is_ok());
+        assert_eq!(reader.verify(0).unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,167 @@
+//! Helpers for reading and writing messages with gRPC's length-prefixed message framing.
+//!
+//! gRPC prefixes every message with a 5 byte header: a 1 byte compressed flag followed by
+//! the length of the message as a 4 byte big-endian integer. These helpers only handle the framing,
+//! not the HTTP/2 transport around it, so they can be used when bridging messages to and from a gRPC transport.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! # use protrust::doctest::timestamp::Timestamp;
+//! use protrust::io::grpc::{read_grpc_frame, write_grpc_frame};
+//!
+//! let mut timestamp = Timestamp::new();
+//! *timestamp.seconds_mut() = 5;
+//!
+//! let mut framed = Vec::new();
+//! write_grpc_frame(&mut framed, &timestamp, false)?;
+//! assert_eq!(framed, [0, 0, 0, 0, 2, 8, 5]);
+//!
+//! let mut input = &framed[..];
+//! assert_eq!(read_grpc_frame::<Timestamp, _>(&mut input)?, Some(timestamp));
+//! assert_eq!(read_grpc_frame::<Timestamp, _>(&mut input)?, None);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::Message;
+use crate::io::read;
+use crate::io::write;
+use crate::io::CodedWriter;
+use std::cmp;
+use std::io::{self, ErrorKind, Read, Write};
+
+/// The length of the header written before each message.
+pub const HEADER_LEN: usize = 5;
+
+/// Writes a message to the writer prefixed with a gRPC frame header.
+///
+/// The compressed flag is written to the header as-is. No compression is applied to the message,
+/// so a compressed frame should only be written if the payload is compressed by the transport.
+///
+/// # Errors
+///
+/// If the message's size overflows an `i32`, this returns an `InvalidInput` error.
+/// Errors from the writer are returned as-is. Like [`write_to_writer`](../../trait.Message.html#method.write_to_writer),
+/// output still buffered when an error is returned is discarded, so the header isn't written without its message.
+pub fn write_grpc_frame<M: Message, W: Write>(w: &mut W, m: &M, compressed: bool) -> io::Result<()> {
+    let len = m.calculate_size().ok_or(write::Error::ValueTooLarge)?.get() as u32;
+    let mut writer = CodedWriter::with_capacity(cmp::min(HEADER_LEN + len as usize, super::DEFAULT_BUF_SIZE), w);
+    // the header is buffered with the message so a failed write doesn't leave a header without its message.
+    // fixed32 values are written little-endian, so the length's bytes are swapped to write it big-endian
+    let result = writer.write_varint32(compressed as u32)
+        .and_then(|_| writer.write_bit32(u32::from_le_bytes(len.to_be_bytes())))
+        .and_then(|_| m.write_to(&mut writer));
+    match result {
+        Ok(()) => {
+            writer.finish()?;
+            Ok(())
+        },
+        Err(e) => {
+            writer.into_inner();
+            Err(e.into())
+        }
+    }
+}
+
+/// Reads a message prefixed with a gRPC frame header from the reader.
+///
+/// Returns `None` if the reader is at the end of its input before the start of a frame.
+///
+/// # Errors
+///
+/// If the input ends part way through a frame, this returns an `UnexpectedEof` IO error.
+/// Since no compression is supported, frames with the compressed flag set return an `InvalidData` IO error.
+/// Errors from parsing the message are returned as-is. Error offsets are relative to the start of the frame.
+pub fn read_grpc_frame<M: Message, R: Read>(r: &mut R) -> read::Result<Option<M>> {
+    let io_error = |e, offset: usize| read::Error::new(read::ErrorKind::IoError(e), offset as u64);
+    let mut header = [0; HEADER_LEN];
+    let mut filled = 0;
+    while filled < HEADER_LEN {
+        match r.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io_error(io::Error::from(ErrorKind::UnexpectedEof), filled)),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => { },
+            Err(e) => return Err(io_error(e, filled)),
+        }
+    }
+
+    match header[0] {
+        0 => { },
+        1 => return Err(io_error(io::Error::new(ErrorKind::InvalidData, "compressed gRPC frames are not supported"), 0)),
+        _ => return Err(io_error(io::Error::new(ErrorKind::InvalidData, "invalid gRPC frame compressed flag"), 0)),
+    }
+
+    let mut len = [0; 4];
+    len.copy_from_slice(&header[1..]);
+    let len = u32::from_be_bytes(len);
+
+    let mut payload = Vec::with_capacity(std::cmp::min(len as usize, super::DEFAULT_BUF_SIZE));
+    r.take(u64::from(len)).read_to_end(&mut payload).map_err(|e| io_error(e, HEADER_LEN + payload.len()))?;
+    if payload.len() as u64 != u64::from(len) {
+        return Err(io_error(io::Error::from(ErrorKind::UnexpectedEof), HEADER_LEN + payload.len()));
+    }
+
+    M::parse_merged_from_slice(&payload)
+        .map(Some)
+        .map_err(|e| e.offset_by(HEADER_LEN as u64))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::doctest::timestamp::Timestamp;
+    use crate::io::read::{self, Error};
+    use std::io::ErrorKind;
+    use super::{read_grpc_frame, write_grpc_frame};
+
+    fn timestamp(seconds: i64, nanos: i32) -> Timestamp {
+        let mut t = Timestamp::new();
+        *t.seconds_mut() = seconds;
+        *t.nanos_mut() = nanos;
+        t
+    }
+
+    #[test]
+    fn round_trip_frames() {
+        let first = timestamp(5, 300);
+        let second = timestamp(0, 0);
+
+        let mut framed = Vec::new();
+        write_grpc_frame(&mut framed, &first, false).unwrap();
+        write_grpc_frame(&mut framed, &second, false).unwrap();
+
+        assert_eq!(framed, [0, 0, 0, 0, 5, 8, 5, 16, 172, 2, 0, 0, 0, 0, 0]);
+
+        let mut input = &framed[..];
+        assert_eq!(read_grpc_frame::<Timestamp, _>(&mut input).unwrap(), Some(first));
+        assert_eq!(read_grpc_frame::<Timestamp, _>(&mut input).unwrap(), Some(second));
+        assert_eq!(read_grpc_frame::<Timestamp, _>(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn compressed_flag_is_written() {
+        let mut framed = Vec::new();
+        write_grpc_frame(&mut framed, &timestamp(1, 0), true).unwrap();
+
+        assert_eq!(framed, [1, 0, 0, 0, 2, 8, 1]);
+    }
+
+    #[test]
+    fn compressed_frames_are_rejected() {
+        let input = [1, 0, 0, 0, 2, 8, 1];
+        match read_grpc_frame::<Timestamp, _>(&mut &input[..]).map_err(Error::into_kind) {
+            Err(read::ErrorKind::IoError(e)) => assert_eq!(e.kind(), ErrorKind::InvalidData),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn truncated_frames_are_errors() {
+        for input in &[&[0, 0, 0][..], &[0, 0, 0, 0, 2, 8][..]] {
+            match read_grpc_frame::<Timestamp, _>(&mut &input[..]).map_err(Error::into_kind) {
+                Err(read::ErrorKind::IoError(e)) => assert_eq!(e.kind(), ErrorKind::UnexpectedEof),
+                r => panic!("unexpected result: {:?}", r),
+            }
+        }
+    }
+}
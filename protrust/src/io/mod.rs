@@ -1,17 +1,23 @@
 //! Contains types and traits for reading and writing protobuf coded data.
 
+pub mod checksum;
+#[cfg(feature = "grpc-framing")]
+pub mod grpc;
 pub mod read;
 pub mod write;
 
 pub use read::{Input, CodedReader};
 pub use write::{Output, CodedWriter};
 
+use crate::Message;
 use crate::collections::{RepeatedValue, FieldSet};
 use crate::raw::Value;
 use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 use std::num::NonZeroU32;
+use std::ops::{Add, AddAssign};
+use std::sync::atomic::{AtomicI32, Ordering as AtomicOrdering};
 
 mod internal {
     pub trait Array: AsRef<[u8]> + AsMut<[u8]> {
@@ -31,7 +37,16 @@ mod internal {
     fva!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10);
 }
 
-const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+pub(crate) const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+/// The most bytes worth of values reserved up front when reading a packed field,
+/// so a large length prefix can't make a stream reader allocate memory before its values are read.
+pub(crate) const MAX_PACKED_RESERVE: usize = 1 << 20;
+
+// The tags of an item in the legacy proto2 MessageSet wire format
+pub(crate) const MESSAGE_SET_ITEM_START: Tag = Tag::new(unsafe { FieldNumber::new_unchecked(1) }, WireType::StartGroup);
+pub(crate) const MESSAGE_SET_ITEM_END: Tag = Tag::new(unsafe { FieldNumber::new_unchecked(1) }, WireType::EndGroup);
+pub(crate) const MESSAGE_SET_TYPE_ID: Tag = Tag::new(unsafe { FieldNumber::new_unchecked(2) }, WireType::Varint);
+pub(crate) const MESSAGE_SET_MESSAGE: Tag = Tag::new(unsafe { FieldNumber::new_unchecked(3) }, WireType::LengthDelimited);
 
 /// The wire type of a protobuf value.
 ///
@@ -67,6 +82,67 @@ impl WireType {
         (self as u8 == WireType::Bit64 as u8) ||
         (self as u8 == WireType::Bit32 as u8)
     }
+    /// Gets whether a wire type is length delimited.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::WireType;
+    /// 
+    /// assert!(WireType::LengthDelimited.is_length_delimited());
+    /// assert!(!WireType::Varint.is_length_delimited());
+    /// ```
+    pub const fn is_length_delimited(self) -> bool {
+        self as u8 == WireType::LengthDelimited as u8
+    }
+    /// Gets whether a wire type is a fixed size value, either Bit32 or Bit64.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::WireType;
+    /// 
+    /// assert!(WireType::Bit32.is_fixed());
+    /// assert!(WireType::Bit64.is_fixed());
+    /// assert!(!WireType::Varint.is_fixed());
+    /// ```
+    pub const fn is_fixed(self) -> bool {
+        (self as u8 == WireType::Bit64 as u8) ||
+        (self as u8 == WireType::Bit32 as u8)
+    }
+    /// Gets whether a wire type marks the start or end of a group.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::WireType;
+    /// 
+    /// assert!(WireType::StartGroup.is_group_marker());
+    /// assert!(WireType::EndGroup.is_group_marker());
+    /// assert!(!WireType::LengthDelimited.is_group_marker());
+    /// ```
+    pub const fn is_group_marker(self) -> bool {
+        (self as u8 == WireType::StartGroup as u8) ||
+        (self as u8 == WireType::EndGroup as u8)
+    }
+    /// Gets the size in bytes of values with a fixed size wire type, or `None` for other wire types.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::WireType;
+    /// 
+    /// assert_eq!(WireType::Bit32.fixed_size(), Some(4));
+    /// assert_eq!(WireType::Bit64.fixed_size(), Some(8));
+    /// assert_eq!(WireType::Varint.fixed_size(), None);
+    /// ```
+    pub const fn fixed_size(self) -> Option<usize> {
+        match self {
+            WireType::Bit32 => Some(4),
+            WireType::Bit64 => Some(8),
+            _ => None,
+        }
+    }
 }
 
 impl TryFrom<u8> for WireType {
@@ -331,6 +407,9 @@ impl Display for Length {
 }
 
 impl Length {
+    /// A length of zero bytes.
+    pub const ZERO: Length = Length(0);
+
     /// Returns the value as a [`i32`](https://doc.rust-lang.org/nightly/std/primitive.i32.html).
     /// 
     /// # Examples
@@ -386,6 +465,60 @@ impl Length {
         Length(x)
     }
 
+    /// Makes a new length from the specified [`usize`], returning [`None`] if the value is greater than [`i32::MAX`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::Length;
+    /// 
+    /// assert_eq!(Length::from_usize(5), Length::new(5));
+    /// assert_eq!(Length::from_usize(i32::max_value() as usize + 1), None);
+    /// ```
+    /// 
+    /// [`usize`]: https://doc.rust-lang.org/nightly/std/primitive.usize.html
+    /// [`None`]: https://doc.rust-lang.org/nightly/std/option/enum.Option.html#variant.None
+    /// [`i32::MAX`]: https://doc.rust-lang.org/nightly/std/primitive.i32.html#method.max_value
+    pub fn from_usize(x: usize) -> Option<Length> {
+        i32::try_from(x).ok().map(Length)
+    }
+
+    /// Adds two lengths, returning [`None`] if the result overflows an [`i32`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::Length;
+    /// 
+    /// let max = Length::new(i32::max_value()).unwrap();
+    /// assert_eq!(Length::ZERO.checked_add(max), Some(max));
+    /// assert_eq!(max.checked_add(Length::new(1).unwrap()), None);
+    /// ```
+    /// 
+    /// [`i32`]: https://doc.rust-lang.org/nightly/std/primitive.i32.html
+    /// [`None`]: https://doc.rust-lang.org/nightly/std/option/enum.Option.html#variant.None
+    #[inline]
+    pub fn checked_add(self, rhs: Length) -> Option<Length> {
+        self.0.checked_add(rhs.0).map(Length)
+    }
+
+    /// Adds two lengths, saturating at [`i32::MAX`] instead of overflowing.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::Length;
+    /// 
+    /// let max = Length::new(i32::max_value()).unwrap();
+    /// assert_eq!(max.saturating_add(Length::new(1).unwrap()), max);
+    /// ```
+    /// 
+    /// [`i32::MAX`]: https://doc.rust-lang.org/nightly/std/primitive.i32.html#method.max_value
+    #[inline]
+    pub fn saturating_add(self, rhs: Length) -> Length {
+        Length(self.0.saturating_add(rhs.0))
+    }
+
     /// Returns the length of the value in the specified form.
     /// 
     /// # Examples
@@ -454,6 +587,45 @@ impl Length {
     pub fn of_fields<T: FieldSet>(value: &T) -> Option<Length> {
         LengthBuilder::new().add_fields::<T>(value).map(LengthBuilder::build)
     }
+
+    /// Returns the length of the message. This is the same as the message's
+    /// [`calculate_size`](../trait.Message.html#tymethod.calculate_size).
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::io::Length;
+    /// 
+    /// let mut timestamp = Timestamp::new();
+    /// *timestamp.seconds_mut() = 5;
+    /// 
+    /// assert_eq!(Length::of_message(&timestamp), Length::new(2));
+    /// ```
+    pub fn of_message<M: Message>(value: &M) -> Option<Length> {
+        value.calculate_size()
+    }
+
+    /// Returns the length of the message with a varint length prefix, like the messages
+    /// read by a [`MessageStream`](read/struct.MessageStream.html). This can be used to size a buffer for
+    /// length-prefixed framing.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::io::Length;
+    /// 
+    /// let mut timestamp = Timestamp::new();
+    /// *timestamp.seconds_mut() = 5;
+    /// 
+    /// // 1 byte length + 2 byte message
+    /// assert_eq!(Length::of_delimited_message(&timestamp), Length::new(3));
+    /// ```
+    pub fn of_delimited_message<M: Message>(value: &M) -> Option<Length> {
+        let len = value.calculate_size()?;
+        raw_varint32_size(len.get() as u32).checked_add(len)
+    }
 }
 
 impl From<Length> for i32 {
@@ -462,6 +634,32 @@ impl From<Length> for i32 {
     }
 }
 
+/// Adds two lengths.
+/// 
+/// # Panics
+/// 
+/// With debug assertions enabled this panics if the result overflows an `i32`.
+/// Otherwise the result saturates at `i32::MAX`, since a length can never be negative.
+impl Add for Length {
+    type Output = Length;
+
+    #[inline]
+    fn add(self, rhs: Length) -> Length {
+        if cfg!(debug_assertions) {
+            self.checked_add(rhs).expect("length overflowed an i32")
+        } else {
+            self.saturating_add(rhs)
+        }
+    }
+}
+
+impl AddAssign for Length {
+    #[inline]
+    fn add_assign(&mut self, rhs: Length) {
+        *self = *self + rhs;
+    }
+}
+
 /// An opaque type for building a length for writing to an output.
 /// 
 /// This exists to make creating checked lengths easier in generated code.
@@ -542,6 +740,100 @@ impl LengthBuilder {
     }
 }
 
+/// A memoized message size, which generated messages can store to avoid recalculating their size.
+/// 
+/// A message with a cached size calculates its size through [`get_or_calculate`](#method.get_or_calculate),
+/// which only walks the message's fields if the cache is empty. Any change to the message's fields
+/// changes its size, so every mutating accessor must [`invalidate`](#method.invalidate) the cache.
+/// This includes accessors that return a mutable reference to a field, since the caller can change
+/// the field after the accessor returns, and it includes the cache of every message containing the message.
+/// A cache that isn't invalidated causes the message to be written with a stale size, producing invalid output.
+/// 
+/// The cache doesn't affect a message's value: cached sizes always compare as equal, and clones copy the cached size
+/// since the clone's fields are the same.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::{CachedSize, Length};
+/// 
+/// let mut cache = CachedSize::new();
+/// let mut calculations = 0;
+/// 
+/// for _ in 0..3 {
+///     let size = cache.get_or_calculate(|| { calculations += 1; Length::new(5) });
+///     assert_eq!(size, Length::new(5));
+/// }
+/// assert_eq!(calculations, 1);
+/// 
+/// cache.invalidate();
+/// assert_eq!(cache.get(), None);
+/// ```
+pub struct CachedSize(AtomicI32);
+
+impl CachedSize {
+    const EMPTY: i32 = -1;
+
+    /// Creates a new empty cache.
+    pub const fn new() -> CachedSize {
+        CachedSize(AtomicI32::new(Self::EMPTY))
+    }
+    /// Gets the cached size, or `None` if the cache is empty.
+    #[inline]
+    pub fn get(&self) -> Option<Length> {
+        Length::new(self.0.load(AtomicOrdering::Relaxed))
+    }
+    /// Sets the cached size.
+    #[inline]
+    pub fn set(&self, len: Length) {
+        self.0.store(len.get(), AtomicOrdering::Relaxed)
+    }
+    /// Gets the cached size, calculating and caching it with the function if the cache is empty.
+    /// If the function returns `None`, the cache is left empty.
+    #[inline]
+    pub fn get_or_calculate<F: FnOnce() -> Option<Length>>(&self, f: F) -> Option<Length> {
+        match self.get() {
+            Some(len) => Some(len),
+            None => {
+                let len = f()?;
+                self.set(len);
+                Some(len)
+            }
+        }
+    }
+    /// Empties the cache, so the size is calculated again the next time it's used.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        *self.0.get_mut() = Self::EMPTY;
+    }
+}
+
+impl Default for CachedSize {
+    fn default() -> Self {
+        CachedSize::new()
+    }
+}
+
+impl Clone for CachedSize {
+    fn clone(&self) -> Self {
+        CachedSize(AtomicI32::new(self.0.load(AtomicOrdering::Relaxed)))
+    }
+}
+
+impl PartialEq for CachedSize {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for CachedSize { }
+
+impl fmt::Debug for CachedSize {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_tuple("CachedSize").field(&self.get()).finish()
+    }
+}
+
 /// A generic string of bytes.
 /// This is used by [`CodedReader`](read/struct.CodedReader.html) to read length delimited byte values
 /// into various kinds of byte collections.
@@ -574,5 +866,55 @@ pub(crate) const fn raw_varint64_size(value: u64) -> Length {
 
 #[cfg(test)]
 mod test {
-    
+    use super::{CachedSize, Length};
+    use crate::doctest::timestamp::Timestamp;
+
+    #[test]
+    fn of_delimited_message() {
+        let mut timestamp = Timestamp::new();
+        assert_eq!(Length::of_message(&timestamp), Length::new(0));
+        assert_eq!(Length::of_delimited_message(&timestamp), Length::new(1));
+
+        *timestamp.seconds_mut() = 1 << 62;
+        *timestamp.nanos_mut() = 1 << 20;
+        assert_eq!(Length::of_message(&timestamp), Length::new(14));
+        assert_eq!(Length::of_delimited_message(&timestamp), Length::new(15));
+    }
+
+    #[test]
+    fn cached_size() {
+        let mut cache = CachedSize::new();
+        assert_eq!(cache.get_or_calculate(|| None), None);
+        assert_eq!(cache.get(), None);
+
+        assert_eq!(cache.get_or_calculate(|| Length::new(0)), Length::new(0));
+        assert_eq!(cache.clone().get(), Length::new(0));
+        assert_eq!(cache.get_or_calculate(|| Length::new(1)), Length::new(0));
+
+        cache.invalidate();
+        assert_eq!(cache.get_or_calculate(|| Length::new(1)), Length::new(1));
+        assert_eq!(cache, CachedSize::new());
+    }
+
+    #[test]
+    fn length_add() {
+        let mut len = Length::ZERO + Length::new(5).unwrap();
+        len += Length::new(10).unwrap();
+
+        assert_eq!(len, Length::new(15).unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "length overflowed an i32"))]
+    fn length_add_overflow() {
+        let max = Length::new(i32::max_value()).unwrap();
+        assert_eq!(max + Length::new(1).unwrap(), max);
+    }
+
+    #[test]
+    fn length_from_usize() {
+        assert_eq!(Length::from_usize(0), Some(Length::ZERO));
+        assert_eq!(Length::from_usize(i32::max_value() as usize), Length::new(i32::max_value()));
+        assert_eq!(Length::from_usize(usize::max_value()), None);
+    }
 }
\ No newline at end of file
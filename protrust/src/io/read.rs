@@ -1,19 +1,24 @@
 //! Defines the `CodedReader`, a reader for reading values from a protobuf encoded byte stream.
 
-use crate::Message;
-use crate::collections::{RepeatedValue, FieldSet, TryRead};
+use crate::{Message, UnknownFieldSet};
+use crate::collections::{RepeatedValue, FieldSet, TryRead, KEY_FIELD, VALUE_FIELD};
+use crate::collections::unknown_fields::UnknownField;
 use crate::extend::ExtensionRegistry;
-use crate::io::{Tag, WireType, FieldNumber, Length, ByteString, DEFAULT_BUF_SIZE};
+use crate::io::{Tag, WireType, FieldNumber, Length, ByteString, DEFAULT_BUF_SIZE, MAX_PACKED_RESERVE};
 use crate::raw::{self, Value};
 use std::boxed::Box;
 use std::cmp::{self, Ordering};
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::error;
 use std::fmt::{self, Display, Formatter};
-use std::io::{self, Read, ErrorKind};
+use std::io::{self, Read};
 use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
 use std::result;
 use std::string::FromUtf8Error;
+use std::sync::Arc;
 
 /// A trait used by `CodedReader`s to efficiently skip bytes in an input.
 pub trait Skip: Read {
@@ -27,7 +32,7 @@ impl<T: ?Sized + Read> Skip for T {
         let mut sink = io::sink();
         io::copy(&mut take, &mut sink)?;
         if take.limit() != 0 {
-            Err(io::Error::from(ErrorKind::UnexpectedEof))
+            Err(io::Error::from(io::ErrorKind::UnexpectedEof))
         } else {
             Ok(())
         }
@@ -40,13 +45,87 @@ impl<T: ?Sized + Read + io::Seek> Skip for T {
     }
 }
 
+/// A [`Read`](https://doc.rust-lang.org/nightly/std/io/trait.Read.html) over a series of non-contiguous slices,
+/// reading each slice in order as if they were one continuous slice.
+/// 
+/// This can be used to read data split across multiple buffers without first concatenating them.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::{read, CodedReader};
+/// 
+/// // a varint split across two buffers
+/// let segments: &[&[u8]] = &[&[8, 150], &[1]];
+/// let mut reader = CodedReader::with_slices(segments);
+/// 
+/// assert_eq!(reader.read_varint32()?, 8);
+/// assert_eq!(reader.read_varint32()?, 150);
+/// # Ok::<(), read::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct SliceChain<'a> {
+    current: &'a [u8],
+    rest: &'a [&'a [u8]],
+}
+
+impl<'a> SliceChain<'a> {
+    /// Creates a new reader over the specified slices.
+    pub fn new(slices: &'a [&'a [u8]]) -> Self {
+        SliceChain { current: &[], rest: slices }
+    }
+
+    /// Returns the next non-empty slice, or an empty slice if there are none left.
+    fn next_slice(&mut self) -> &'a [u8] {
+        while self.current.is_empty() {
+            match self.rest.split_first() {
+                Some((first, rest)) => {
+                    self.current = first;
+                    self.rest = rest;
+                },
+                None => break,
+            }
+        }
+        self.current
+    }
+}
+
+impl Read for SliceChain<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let current = self.next_slice();
+        let amnt = cmp::min(buf.len(), current.len());
+        buf[..amnt].copy_from_slice(&current[..amnt]);
+        self.current = &current[amnt..];
+        Ok(amnt)
+    }
+}
+
+impl Skip for SliceChain<'_> {
+    fn skip_exact(&mut self, amnt: Length) -> io::Result<()> {
+        let mut remaining = amnt.get() as usize;
+        while remaining != 0 {
+            let current = self.next_slice();
+            if current.is_empty() {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+            }
+            let skipped = cmp::min(remaining, current.len());
+            self.current = &current[skipped..];
+            remaining -= skipped;
+        }
+        Ok(())
+    }
+}
+
 mod internal {
-    use crate::io::{ByteString, Tag, Length, internal::Array, read::{Result, Error}};
+    use crate::io::{ByteString, Tag, Length, internal::Array, read::{Result, Error, ErrorKind}};
     use std::cmp::{self, Ordering};
     use std::convert::TryFrom;
-    use std::io::{self, Read as _, ErrorKind};
+    use std::io::{self, Read as _};
+    use std::mem;
     use std::ops::Range;
     use std::ptr::{self, NonNull};
+    use std::str;
+    use std::string::FromUtf8Error;
     use super::Skip as Read;
 
     /// State shared between all readers. This is borrowed by Any to manage state of a specialized reader
@@ -55,6 +134,8 @@ mod internal {
         pub recursion_depth: usize,
         pub last_tag: Option<Tag>,
         pub next_end_group: Option<Tag>,
+        /// The total number of bytes taken from the underlying input, including those still in the buffer
+        pub consumed: u64,
     }
 
     /// A container for shared buffer manipulation logic.
@@ -107,6 +188,28 @@ mod internal {
 
             self.start = NonNull::new_unchecked(new_pos);
         }
+        /// Moves the buffer back by the specified amount. Assumes the
+        /// buffer started at least `amnt` bytes before its current position.
+        #[inline]
+        pub unsafe fn rewind(&mut self, amnt: usize) {
+            self.start = NonNull::new_unchecked(self.start.as_ptr().sub(amnt));
+        }
+        /// Validates the next `len` bytes as UTF-8 and, if they're valid, replaces the contents
+        /// of the string with them and advances past them. Assumes the buffer has at least `len`
+        /// bytes before its limit.
+        #[inline]
+        pub unsafe fn take_str_into(&mut self, len: usize, buf: &mut String) -> std::result::Result<(), FromUtf8Error> {
+            let bytes = &self.to_limit_as_slice()[..len];
+            match str::from_utf8(bytes) {
+                Ok(s) => {
+                    buf.clear();
+                    buf.push_str(s);
+                    self.advance(len);
+                    Ok(())
+                },
+                Err(_) => Err(String::from_utf8(bytes.to_vec()).unwrap_err())
+            }
+        }
         #[inline]
         pub fn reached_limit(&self) -> bool {
             self.start == self.limit
@@ -163,6 +266,7 @@ mod internal {
         ///  3. Limit is not negative
         #[inline]
         pub unsafe fn apply_limit(&mut self, limit: i32) {
+            debug_assert!(limit >= 0 && limit as usize <= self.to_end_len(), "limit extends beyond the end of the buffer");
             if self.end.is_none() {
                 self.end = Some(self.limit);
             }
@@ -186,9 +290,13 @@ mod internal {
         fn state(&self) -> &SharedState;
         fn state_mut(&mut self) -> &mut SharedState;
 
-        fn push_limit(&mut self, limit: i32) -> io::Result<Option<i32>>;
+        /// Pushes a new limit, returning the old limit to pass to `pop_limit`. This checks that the limit
+        /// doesn't extend beyond an existing limit or the end of a slice before applying it.
+        fn push_limit(&mut self, limit: i32) -> Result<Option<i32>>;
         fn pop_limit(&mut self, old: Option<i32>);
         fn reached_limit(&self) -> bool;
+        /// Returns the number of bytes left before the innermost limit, or None if no limit is active
+        fn remaining_limit(&self) -> Option<i32>;
 
         fn read_tag(&mut self) -> Result<Option<u32>>;
         fn read_varint32(&mut self) -> Result<u32>;
@@ -196,15 +304,39 @@ mod internal {
         fn read_bit32(&mut self) -> Result<u32>;
         fn read_bit64(&mut self) -> Result<u64>;
         fn read_length_delimited<B: ByteString>(&mut self) -> Result<B>;
+        /// Reads a length delimited value, appending it to the buffer. If the read fails,
+        /// the buffer is truncated back to its original length.
+        fn read_length_delimited_append(&mut self, buf: &mut Vec<u8>) -> Result<()>;
+        /// Reads a length delimited UTF-8 string into the buffer, reusing its allocation.
+        /// If the read fails, the buffer never holds part of the string, but may not be empty.
+        fn read_length_delimited_str_into(&mut self, buf: &mut String) -> Result<()>;
+        /// Fills the buffer with the next bytes of the input, without a length prefix
+        fn read_raw(&mut self, buf: &mut [u8]) -> Result<()>;
 
         fn skip_varint(&mut self) -> Result<()>;
         fn skip_bit32(&mut self) -> Result<()>;
         fn skip_bit64(&mut self) -> Result<()>;
         fn skip_length_delimited(&mut self) -> Result<()>;
+        /// Skips the rest of the input up to the innermost limit, or to the end of the input if there's no limit
+        fn skip_to_limit(&mut self) -> Result<()>;
 
         fn as_any(&mut self) -> Any;
 
         fn reached_end(&self) -> bool;
+        fn position(&self) -> u64;
+
+        /// Creates an error of the specified kind at the current position
+        fn error(&self, kind: ErrorKind) -> Error {
+            Error::new(kind, self.position())
+        }
+        /// Creates an error from an IO error returned by the underlying input at the current position
+        fn io_error(&self, err: io::Error) -> Error {
+            self.error(ErrorKind::IoError(err))
+        }
+        /// Creates an unexpected EOF error at the current position
+        fn eof(&self) -> Error {
+            self.io_error(io::Error::from(io::ErrorKind::UnexpectedEof))
+        }
     }
 
     pub struct BorrowedStream<'a> {
@@ -236,7 +368,7 @@ mod internal {
                 Some(BorrowedStream { remaining_limit: &mut 0, .. }) | None => {
                     if limit_len < slice.len() {
                         unsafe { self.buffer.advance(limit_len); }
-                        return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+                        return Err(self.eof());
                     }
     
                     unsafe {
@@ -258,30 +390,40 @@ mod internal {
                 },
             }
         }
-        fn read_direct(BorrowedStream { input: stream, remaining_limit: limit, .. }: &mut BorrowedStream, buf: &mut [u8]) -> Result<()> {
+        /// Reads directly from the stream. The buffer is empty when this is
+        /// called, so the position of the reader is the amount consumed.
+        fn read_direct(BorrowedStream { input: stream, remaining_limit: limit, .. }: &mut BorrowedStream, consumed: &mut u64, buf: &mut [u8]) -> Result<()> {
+            let io_error = |e, consumed: &u64| Error::new(ErrorKind::IoError(e), *consumed);
             if **limit < 0 {
-                stream.read_exact(buf).map_err(Into::into)
+                stream.read_exact(buf).map_err(|e| io_error(e, consumed))?;
+                *consumed += buf.len() as u64;
+                Ok(())
             } else {
                 let remaining_limit = **limit as usize;
                 if remaining_limit == 0 {
-                    Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                    Err(io_error(io::Error::from(io::ErrorKind::UnexpectedEof), consumed))
                 } else if remaining_limit >= buf.len() {
                     **limit = i32::wrapping_sub(**limit, buf.len() as i32);
-                    stream.read_exact(buf).map_err(Into::into)
+                    stream.read_exact(buf).map_err(|e| io_error(e, consumed))?;
+                    *consumed += buf.len() as u64;
+                    Ok(())
                 } else {
                     **limit = 0;
-                    stream.read_exact(&mut buf[..remaining_limit])?;
-                    Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                    stream.read_exact(&mut buf[..remaining_limit]).map_err(|e| io_error(e, consumed))?;
+                    *consumed += remaining_limit as u64;
+                    Err(io_error(io::Error::from(io::ErrorKind::UnexpectedEof), consumed))
                 }
             }
         }
         /// Attempts to refresh the buffer, returning a bool indicating if the data buffer was filled
         fn try_refresh(&mut self) -> Result<bool> {
+            let position = self.position();
             let BorrowedStream { input, buf, remaining_limit, reached_eof } = match &mut self.stream {
                 Some(s) => s,
-                None => return Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                None => return Err(self.eof()),
             };
-            let amnt = input.read(buf)?;
+            let amnt = input.read(buf).map_err(|e| Error::new(ErrorKind::IoError(e), position))?;
+            self.shared_state.consumed += amnt as u64;
 
             *self.buffer = Buffer::from_slice(&buf[..amnt]);
             if **remaining_limit >= 0 {
@@ -293,7 +435,7 @@ mod internal {
             Ok(refreshed)
         }
         fn refresh(&mut self) -> Result<()> {
-            self.try_refresh().and_then(|b| if b { Ok(()) } else { Err(io::Error::from(ErrorKind::UnexpectedEof).into()) })
+            self.try_refresh().and_then(|b| if b { Ok(()) } else { Err(self.eof()) })
         }
         fn read_byte(&mut self) -> Result<u8> {
             let mut buf = [0u8; 1];
@@ -308,12 +450,14 @@ mod internal {
             if self.buffer.to_limit_len() != 0 {
                 unsafe { Ok(Some(self.buffer.next_byte())) }
             } else {
+                let position = self.position();
                 match &mut self.stream {
                     Some(BorrowedStream { remaining_limit: &mut 0, .. }) | None => Ok(None),
                     Some(BorrowedStream { input, buf: [], remaining_limit, reached_eof }) => {
                         let mut buf = [0u8; 1];
-                        let result = input.read(&mut buf)?;
+                        let result = input.read(&mut buf).map_err(|e| Error::new(ErrorKind::IoError(e), position))?;
                         if result != 0 {
+                            self.shared_state.consumed += 1;
                             if **remaining_limit > 0 {
                                 **remaining_limit -= 1;
                             }
@@ -333,7 +477,7 @@ mod internal {
         }
         fn read_exact(&mut self, slice: &mut [u8]) -> Result<()> {
             if self.reached_end() {
-                return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+                return Err(self.eof());
             }
 
             let mut remaining_slice = self.read_buffer_partial(slice)?;
@@ -343,7 +487,7 @@ mod internal {
                     // the size of the buffer then we read direct from the stream
                     // and adjust our remaining limit accordingly
                     Some(stream) if remaining_slice.len() >= stream.buf.len() => {
-                        Self::read_direct(stream, remaining_slice)
+                        Self::read_direct(stream, &mut self.shared_state.consumed, remaining_slice)
                     },
                     Some(_) => {
                         loop {
@@ -355,7 +499,7 @@ mod internal {
                             }
                         }
                     },
-                    None => Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                    None => Err(self.eof())
                 }
             } else {
                 Ok(())
@@ -363,7 +507,7 @@ mod internal {
         }
         fn skip(&mut self, amnt: i32) -> Result<()> {
             if self.reached_end() {
-                return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+                return Err(self.eof());
             }
 
             let amnt_usize = amnt as usize;
@@ -374,27 +518,27 @@ mod internal {
             } else {
                 unsafe { self.buffer.advance(limit_buf_len); }
                 let remaining_amnt = amnt - limit_buf_len as i32;
+                let position = self.position();
                 match &mut self.stream {
                     Some(BorrowedStream { input, remaining_limit, .. }) => {
-                        match (**remaining_limit).cmp(&0) {
-                            Ordering::Less => {
-                                input.skip_exact(unsafe { Length::new_unchecked(remaining_amnt) }).map_err(Into::into)
-                            },
-                            Ordering::Equal => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                        let skipped = match (**remaining_limit).cmp(&0) {
+                            Ordering::Less => unsafe { Length::new_unchecked(remaining_amnt) },
+                            Ordering::Equal => return Err(self.eof()),
                             Ordering::Greater => {
                                 let remaining = **remaining_limit;
-                                let remaining_length = unsafe { Length::new_unchecked(remaining) };
                                 if remaining > remaining_amnt {
                                     **remaining_limit = 0;
-                                    input.skip_exact(remaining_length).map_err(Into::into)
                                 } else {
                                     **remaining_limit = i32::wrapping_sub(**remaining_limit, remaining_amnt as i32);
-                                    input.skip_exact(remaining_length).map_err(Into::into)
                                 }
+                                unsafe { Length::new_unchecked(remaining) }
                             }
-                        }
+                        };
+                        input.skip_exact(skipped).map_err(|e| Error::new(ErrorKind::IoError(e), position))?;
+                        self.shared_state.consumed += skipped.get() as u64;
+                        Ok(())
                     },
-                    None => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                    None => Err(self.eof()),
                 }
             }
         }
@@ -408,7 +552,7 @@ mod internal {
             self.shared_state
         }
 
-        fn push_limit(&mut self, limit: i32) -> io::Result<Option<i32>> {
+        fn push_limit(&mut self, limit: i32) -> Result<Option<i32>> {
             match &mut self.stream {
                 Some(BorrowedStream { remaining_limit, .. }) => {
                     if **remaining_limit < 0 {
@@ -417,7 +561,7 @@ mod internal {
                     } else {
                         let remaining = i32::wrapping_add(self.buffer.to_limit_len() as i32, **remaining_limit);
                         if remaining < limit {
-                            Err(ErrorKind::UnexpectedEof.into())
+                            Err(self.error(ErrorKind::LengthExceedsLimit))
                         } else {
                             **remaining_limit = unsafe { self.buffer.apply_partial_limit(limit) };
                             Ok(Some(i32::wrapping_sub(remaining, limit)))
@@ -427,7 +571,7 @@ mod internal {
                 None => {
                     if let Some(existing_limit) = self.buffer.remaining_limit() {
                         if existing_limit < limit {
-                            Err(ErrorKind::UnexpectedEof.into())
+                            Err(self.error(ErrorKind::LengthExceedsLimit))
                         } else {
                             let old = i32::wrapping_sub(existing_limit, limit);
                             unsafe { self.buffer.apply_limit(limit) };
@@ -438,7 +582,7 @@ mod internal {
                         unsafe {
                             match i32::try_from(limit_len) {
                                 Ok(end) if limit > end => {
-                                    Err(ErrorKind::UnexpectedEof.into())
+                                    Err(self.eof())
                                 },
                                 _ => {
                                     self.buffer.apply_limit(limit);
@@ -479,6 +623,12 @@ mod internal {
                 None => self.buffer.reached_limit()
             }
         }
+        fn remaining_limit(&self) -> Option<i32> {
+            match &self.stream {
+                Some(BorrowedStream { remaining_limit, .. }) => self.buffer.remaining_limit().map(|i| i + **remaining_limit),
+                None => self.buffer.remaining_limit()
+            }
+        }
 
         #[inline]
         fn read_tag(&mut self) -> Result<Option<u32>> {
@@ -502,7 +652,7 @@ mod internal {
                     return Ok(Some(result));
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         }
         fn read_varint32(&mut self) -> Result<u32> {
             let mut result = 0;
@@ -519,7 +669,7 @@ mod internal {
                     return Ok(result);
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         }
         fn read_varint64(&mut self) -> Result<u64> {
             let mut result = 0;
@@ -530,7 +680,7 @@ mod internal {
                     return Ok(result);
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         }
         fn read_bit32(&mut self) -> Result<u32> {
             let mut result = [0u8; 4];
@@ -545,7 +695,7 @@ mod internal {
         fn read_length_delimited<B: ByteString>(&mut self) -> Result<B> {
             let len = 
                 self.read_varint32()
-                    .and_then(|v| Length::new(v as i32).ok_or(Error::NegativeSize))?
+                    .and_then(|v| Length::new(v as i32).ok_or_else(|| self.error(ErrorKind::NegativeSize)))?
                     .get() as usize;
             let mut string = B::new(len);
             if len != 0 {
@@ -553,6 +703,38 @@ mod internal {
             }
             Ok(string)
         }
+        fn read_length_delimited_append(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+            let start = buf.len();
+            let len = 
+                self.read_varint32()
+                    .and_then(|v| Length::new(v as i32).ok_or_else(|| self.error(ErrorKind::NegativeSize)))?
+                    .get() as usize;
+            buf.resize(start + len, 0);
+            self.read_exact(&mut buf[start..]).map_err(|e| { buf.truncate(start); e })
+        }
+        fn read_length_delimited_str_into(&mut self, buf: &mut String) -> Result<()> {
+            let len = 
+                self.read_varint32()
+                    .and_then(|v| Length::new(v as i32).ok_or_else(|| self.error(ErrorKind::NegativeSize)))?
+                    .get() as usize;
+            if len <= self.buffer.to_limit_len() {
+                unsafe { self.buffer.take_str_into(len, buf) }.map_err(|e| self.error(ErrorKind::InvalidString(e)))
+            } else {
+                let mut bytes = mem::take(buf).into_bytes();
+                bytes.clear();
+                bytes.resize(len, 0);
+                self.read_exact(&mut bytes)?;
+                *buf = String::from_utf8(bytes).map_err(|e| self.error(ErrorKind::InvalidString(e)))?;
+                Ok(())
+            }
+        }
+        fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+            if buf.is_empty() {
+                Ok(())
+            } else {
+                self.read_exact(buf)
+            }
+        }
 
         fn skip_varint(&mut self) -> Result<()> {
             for _ in 0..10 {
@@ -561,7 +743,7 @@ mod internal {
                     return Ok(());
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         }
         fn skip_bit32(&mut self) -> Result<()> {
             self.skip(4)
@@ -572,7 +754,7 @@ mod internal {
         fn skip_length_delimited(&mut self) -> Result<()> {
             let len = 
                 self.read_varint32()
-                    .and_then(|v| Length::new(v as i32).ok_or(Error::NegativeSize))?
+                    .and_then(|v| Length::new(v as i32).ok_or_else(|| self.error(ErrorKind::NegativeSize)))?
                     .get();
             if len != 0 {
                 self.skip(len)
@@ -580,6 +762,23 @@ mod internal {
                 Ok(())
             }
         }
+        fn skip_to_limit(&mut self) -> Result<()> {
+            let remaining = self.remaining_limit();
+            match (&mut self.stream, remaining) {
+                (None, _) | (_, Some(0)) => {
+                    unsafe { self.buffer.advance(self.buffer.to_limit_len()); }
+                    Ok(())
+                },
+                (Some(_), Some(remaining)) => self.skip(remaining),
+                (Some(BorrowedStream { input, reached_eof, .. }), None) => {
+                    unsafe { self.buffer.advance(self.buffer.to_limit_len()); }
+                    let position = self.shared_state.consumed;
+                    self.shared_state.consumed += io::copy(input, &mut io::sink()).map_err(|e| Error::new(ErrorKind::IoError(e), position))?;
+                    **reached_eof = true;
+                    Ok(())
+                }
+            }
+        }
 
         fn as_any(&mut self) -> Any {
             Any {
@@ -599,6 +798,9 @@ mod internal {
                 None => self.buffer.reached_end()
             }
         }
+        fn position(&self) -> u64 {
+            self.shared_state.consumed - self.buffer.to_end_len() as u64
+        }
     }
 
     unsafe impl Send for Any<'_> { }
@@ -610,8 +812,46 @@ use internal::{Reader, Buffer, SharedState};
 pub use internal::Any;
 
 /// The error type for [`CodedReader`](struct.CodedReader.html)
+/// 
+/// An error is made of the [`ErrorKind`](enum.ErrorKind.html) describing what went wrong and the
+/// offset in the input where the reader was when it went wrong.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: u64,
+}
+
+impl Error {
+    /// Creates a new error of the specified kind at an offset in the input.
+    pub fn new(kind: ErrorKind, offset: u64) -> Self {
+        Error { kind, offset }
+    }
+    /// Gets the kind of error that occured.
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+    /// Gets the offset in the input where the error occured.
+    /// 
+    /// This is the position of the reader when the error was returned,
+    /// so some or all of the value being read may have been consumed.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    /// Consumes the error, returning its kind.
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+    /// Moves the error's offset forward by the specified amount, used when
+    /// the input read was part of a larger input starting at that offset.
+    pub(crate) fn offset_by(mut self, amnt: u64) -> Self {
+        self.offset += amnt;
+        self
+    }
+}
+
+/// The kind of error returned by a [`CodedReader`](struct.CodedReader.html)
 #[derive(Debug)]
-pub enum Error {
+pub enum ErrorKind {
     /// The input contained a malformed variable length integer
     MalformedVarint,
     /// The input contained a length delimited value which reported it had a negative size
@@ -625,38 +865,83 @@ pub enum Error {
     IoError(io::Error),
     /// The input contained an invalid UTF8 string
     InvalidString(FromUtf8Error),
+    /// The input contained an end group tag that didn't match the start group tag of the group it was in
+    GroupMismatch {
+        /// The field number of the start group tag that opened the group
+        start: FieldNumber,
+        /// The field number of the end group tag found in the group
+        end: FieldNumber,
+    },
+    /// The input contained a field that wasn't allowed by the filter passed to
+    /// [`read_field_filtered`](struct.CodedReader.html#method.read_field_filtered)
+    UnexpectedField {
+        /// The field number of the rejected field
+        number: FieldNumber,
+    },
+    /// The input contained an unknown field while reading with
+    /// [`UnknownFieldHandling::Error`](enum.UnknownFieldHandling.html#variant.Error)
+    UnknownField {
+        /// The field number of the unknown field
+        number: FieldNumber,
+    },
+    /// The input contained a value outside the range allowed by a [`Bounded`](../../raw/struct.Bounded.html) value
+    OutOfRange,
+    /// The input contained a length delimited value, like a message or packed field, whose length extends
+    /// past the end of the length delimited value containing it
+    LengthExceedsLimit,
+    /// The input contained a length prefixed message longer than the maximum length allowed by a
+    /// [`PartialParser`](struct.PartialParser.html)
+    MessageTooLarge {
+        /// The length of the message given by its prefix
+        len: usize,
+        /// The maximum length allowed
+        max: usize,
+    },
 }
 
-impl From<io::Error> for Error {
-    fn from(value: io::Error) -> Error {
-        Error::IoError(value)
+/// Converts the error into an [`io::Error`](https://doc.rust-lang.org/nightly/std/io/struct.Error.html),
+/// allowing reads to be used with `?` in functions returning `io::Result`.
+/// 
+/// Underlying IO errors are unwrapped, and all other errors become `InvalidData` errors.
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e.kind {
+            ErrorKind::IoError(e) => e,
+            _ => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
     }
 }
 
-impl From<FromUtf8Error> for Error {
-    fn from(value: FromUtf8Error) -> Error {
-        Error::InvalidString(value)
+impl Display for ErrorKind {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::MalformedVarint => write!(fmt, "the input contained an invalid variable length integer"),
+            ErrorKind::NegativeSize => write!(fmt, "the input contained a length delimited value which reported it had a negative size"),
+            ErrorKind::RecursionLimitExceeded => write!(fmt, "the input contained a nested data structure that exceeded the recursion limit"),
+            ErrorKind::InvalidTag(val) => write!(fmt, "the input contained an tag that was either invalid or was unexpected at this point in the input: {}", val),
+            ErrorKind::IoError(err) => write!(fmt, "an error occured in the underlying input: {}", err),
+            ErrorKind::InvalidString(_) => write!(fmt, "the input contained an invalid UTF8 string"),
+            ErrorKind::GroupMismatch { start, end } => write!(fmt, "the input contained an end group tag for field {} in a group started by field {}", end, start),
+            ErrorKind::UnexpectedField { number } => write!(fmt, "the input contained field {} which was not allowed", number),
+            ErrorKind::UnknownField { number } => write!(fmt, "the input contained unknown field {}", number),
+            ErrorKind::OutOfRange => write!(fmt, "the input contained a value outside of its allowed range"),
+            ErrorKind::LengthExceedsLimit => write!(fmt, "the input contained a length delimited value that extended past the end of the value containing it"),
+            ErrorKind::MessageTooLarge { len, max } => write!(fmt, "the input contained a message of {} bytes, longer than the maximum of {} bytes", len, max),
+        }
     }
 }
 
 impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        match self {
-            Error::MalformedVarint => write!(fmt, "the input contained an invalid variable length integer"),
-            Error::NegativeSize => write!(fmt, "the input contained a length delimited value which reported it had a negative size"),
-            Error::RecursionLimitExceeded => write!(fmt, "the input contained a nested data structure that exceeded the recursion limit"),
-            Error::InvalidTag(val) => write!(fmt, "the input contained an tag that was either invalid or was unexpected at this point in the input: {}", val),
-            Error::IoError(err) => write!(fmt, "an error occured in the underlying input: {}", err),
-            Error::InvalidString(_) => write!(fmt, "the input contained an invalid UTF8 string")
-        }
+        write!(fmt, "{} at offset {}", self.kind, self.offset)
     }
 }
 
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        match self {
-            Error::IoError(ref e) => Some(e),
-            Error::InvalidString(ref e) => Some(e),
+        match &self.kind {
+            ErrorKind::IoError(ref e) => Some(e),
+            ErrorKind::InvalidString(ref e) => Some(e),
             _ => None,
         }
     }
@@ -685,7 +970,7 @@ impl<'a> Slice<'a> {
         Self {
             a: PhantomData,
             buffer: Buffer::from_slice(value),
-            state: Default::default(),
+            state: SharedState { consumed: value.len() as u64, ..Default::default() },
         }
     }
 }
@@ -698,18 +983,18 @@ impl Reader for Slice<'_> {
         &mut self.state
     }
 
-    fn push_limit(&mut self, limit: i32) -> io::Result<Option<i32>> {
+    fn push_limit(&mut self, limit: i32) -> Result<Option<i32>> {
         let old = match self.buffer.remaining_limit() {
             Some(remaining) => {
                 if remaining < limit { // err out if the new limit goes beyond our current limit
-                    return Err(ErrorKind::UnexpectedEof.into());
+                    return Err(self.error(ErrorKind::LengthExceedsLimit));
                 }
 
                 Some(remaining - limit)
             },
             None => {
                 if self.buffer.to_end_len() < limit as usize {
-                    return Err(ErrorKind::UnexpectedEof.into());
+                    return Err(self.eof());
                 }
 
                 None
@@ -730,6 +1015,9 @@ impl Reader for Slice<'_> {
     fn reached_limit(&self) -> bool {
         self.buffer.reached_limit()
     }
+    fn remaining_limit(&self) -> Option<i32> {
+        self.buffer.remaining_limit()
+    }
 
     fn read_tag(&mut self) -> Result<Option<u32>> {
         if !self.reached_limit() {
@@ -761,7 +1049,7 @@ impl Reader for Slice<'_> {
                     return Ok(result);
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         } else if let Some::<&[u8; 5]>(arr) = self.buffer.try_limited_as_array() {
             for (i, &b) in arr.iter().enumerate() {
                 result |= ((b & 0x7f) as u32) << (7 * i);
@@ -777,7 +1065,7 @@ impl Reader for Slice<'_> {
                     return Ok(result);
                 }
             }
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         } else {
             let slice = unsafe { self.buffer.to_limit_as_slice() };
             for (i, &b) in slice.iter().enumerate() {
@@ -787,7 +1075,7 @@ impl Reader for Slice<'_> {
                     return Ok(result);
                 }
             }
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         }
     }
     fn read_varint64(&mut self) -> Result<u64> {
@@ -801,7 +1089,7 @@ impl Reader for Slice<'_> {
                     return Ok(result);
                 }
             }
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         } else {
             for (i, &b) in slice.iter().enumerate().take(10) {
                 result |= ((b & 0x7f) as u64) << (7 * i);
@@ -810,12 +1098,12 @@ impl Reader for Slice<'_> {
                     return Ok(result);
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         }
     }
     fn read_bit32(&mut self) -> Result<u32> {
         self.buffer.try_limited_as_array()
-            .ok_or(io::Error::from(ErrorKind::UnexpectedEof).into())
+            .ok_or_else(|| self.eof())
             .copied()
             .map(|arr| {
                 unsafe { self.buffer.advance(4); } // since we already got the array, we know we have at least 4 bytes
@@ -824,7 +1112,7 @@ impl Reader for Slice<'_> {
     }
     fn read_bit64(&mut self) -> Result<u64> {
         self.buffer.try_limited_as_array()
-            .ok_or(io::Error::from(ErrorKind::UnexpectedEof).into())
+            .ok_or_else(|| self.eof())
             .copied()
             .map(|arr| {
                 unsafe { self.buffer.advance(8); } // since we already got the array, we know we have at least 8 bytes
@@ -834,9 +1122,9 @@ impl Reader for Slice<'_> {
     fn read_length_delimited<B: ByteString>(&mut self) -> Result<B> {
         let len = self.read_varint32()? as i32;
         match len {
-            len if len < 0 => Err(Error::NegativeSize),
+            len if len < 0 => Err(self.error(ErrorKind::NegativeSize)),
             0 => Ok(ByteString::new(0)),
-            len if len as usize > self.buffer.to_limit_len() => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+            len if len as usize > self.buffer.to_limit_len() => Err(self.eof()),
             len => {
                 let len = len as usize;
                 let mut bytes = B::new(len);
@@ -848,6 +1136,41 @@ impl Reader for Slice<'_> {
             }
         }
     }
+    fn read_length_delimited_append(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        let len = self.read_varint32()? as i32;
+        match len {
+            len if len < 0 => Err(self.error(ErrorKind::NegativeSize)),
+            len if len as usize > self.buffer.to_limit_len() => Err(self.eof()),
+            len => {
+                let len = len as usize;
+                unsafe { // we've checked that we have enough data to copy in the branch above
+                    buf.extend_from_slice(&self.buffer.to_limit_as_slice()[..len]);
+                    self.buffer.advance(len);
+                }
+                Ok(())
+            }
+        }
+    }
+    fn read_length_delimited_str_into(&mut self, buf: &mut String) -> Result<()> {
+        let len = self.read_varint32()? as i32;
+        match len {
+            len if len < 0 => Err(self.error(ErrorKind::NegativeSize)),
+            len if len as usize > self.buffer.to_limit_len() => Err(self.eof()),
+            len => {
+                // we've checked that we have enough data to validate in the branch above
+                unsafe { self.buffer.take_str_into(len as usize, buf) }
+                    .map_err(|e| self.error(ErrorKind::InvalidString(e)))
+            }
+        }
+    }
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+        if buf.len() > self.buffer.to_limit_len() {
+            Err(self.eof())
+        } else {
+            unsafe { self.buffer.copy_nonoverlapping(buf); }
+            Ok(())
+        }
+    }
 
     fn skip_varint(&mut self) -> Result<()> {
         if let Some::<&[u8; 10]>(arr) = self.buffer.try_limited_as_array() {
@@ -857,7 +1180,7 @@ impl Reader for Slice<'_> {
                     return Ok(());
                 }
             }
-            Err(Error::MalformedVarint)
+            Err(self.error(ErrorKind::MalformedVarint))
         } else {
             for (&b, i) in unsafe { self.buffer.to_limit_as_slice() }.iter().zip(1..) {
                 if b < 0x80 {
@@ -865,7 +1188,7 @@ impl Reader for Slice<'_> {
                     return Ok(());
                 }
             }
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         }
     }
     fn skip_bit32(&mut self) -> Result<()> {
@@ -873,7 +1196,7 @@ impl Reader for Slice<'_> {
             unsafe { self.buffer.advance(4); }
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         }
     }
     fn skip_bit64(&mut self) -> Result<()> {
@@ -881,23 +1204,27 @@ impl Reader for Slice<'_> {
             unsafe { self.buffer.advance(8); }
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+            Err(self.eof())
         }
     }
     fn skip_length_delimited(&mut self) -> Result<()> {
         let len = self.read_varint32()? as i32;
         if len < 0 {
-            Err(Error::NegativeSize)
+            Err(self.error(ErrorKind::NegativeSize))
         } else {
             let len = len as usize;
             if self.buffer.to_limit_len() >= len {
                 unsafe { self.buffer.advance(len); }
                 Ok(())
             } else {
-                Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                Err(self.eof())
             }
         }
     }
+    fn skip_to_limit(&mut self) -> Result<()> {
+        unsafe { self.buffer.advance(self.buffer.to_limit_len()); }
+        Ok(())
+    }
 
     fn as_any(&mut self) -> Any {
         Any {
@@ -910,6 +1237,9 @@ impl Reader for Slice<'_> {
     fn reached_end(&self) -> bool {
         self.buffer.reached_end()
     }
+    fn position(&self) -> u64 {
+        self.state.consumed - self.buffer.to_end_len() as u64
+    }
 }
 
 unsafe impl Send for Slice<'_> { }
@@ -944,11 +1274,9 @@ impl<T: Read + Skip> Stream<T> {
     fn into_inner(self) -> T {
         self.input
     }
-    fn remaining_limit(&self) -> Option<i32> {
-        self.buffer.remaining_limit().map(|i| i + self.remaining_limit)
-    }
     fn try_refresh(&mut self) -> Result<bool> {
-        let amnt = self.input.read(&mut self.buf)?;
+        let amnt = self.input.read(&mut self.buf).map_err(|e| self.io_error(e))?;
+        self.state.consumed += amnt as u64;
 
         self.buffer = Buffer::from_slice(&self.buf[..amnt]);
         if self.remaining_limit >= 0 {
@@ -960,7 +1288,7 @@ impl<T: Read + Skip> Stream<T> {
         Ok(refreshed)
     }
     fn refresh(&mut self) -> Result<()> {
-        self.try_refresh().and_then(|b| b.then_some(()).ok_or(io::Error::from(ErrorKind::UnexpectedEof).into()))
+        self.try_refresh().and_then(|b| b.then_some(()).ok_or_else(|| self.eof()))
     }
     fn read_buffer_partial<'a>(&mut self, slice: &'a mut [u8]) -> Result<&'a mut [u8]> {
         // check if we reached the end of the buffer
@@ -973,7 +1301,7 @@ impl<T: Read + Skip> Stream<T> {
         if self.remaining_limit == 0 {
             if limit_len < slice.len() {
                 unsafe { self.buffer.advance(limit_len); }
-                return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+                return Err(self.eof());
             }
 
             unsafe {
@@ -995,24 +1323,29 @@ impl<T: Read + Skip> Stream<T> {
     }
     fn read_direct(&mut self, buf: &mut [u8]) -> Result<()> {
         if self.remaining_limit < 0 {
-            self.input.read_exact(buf).map_err(Into::into)
+            self.input.read_exact(buf).map_err(|e| self.io_error(e))?;
+            self.state.consumed += buf.len() as u64;
+            Ok(())
         } else {
             let remaining_limit = self.remaining_limit as usize;
             if remaining_limit == 0 {
-                Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                Err(self.eof())
             } else if remaining_limit >= buf.len() {
                 self.remaining_limit = i32::wrapping_sub(self.remaining_limit, buf.len() as i32);
-                self.input.read_exact(buf).map_err(Into::into)
+                self.input.read_exact(buf).map_err(|e| self.io_error(e))?;
+                self.state.consumed += buf.len() as u64;
+                Ok(())
             } else {
                 self.remaining_limit = 0;
-                self.input.read_exact(&mut buf[..remaining_limit])?;
-                Err(io::Error::from(ErrorKind::UnexpectedEof).into())
+                self.input.read_exact(&mut buf[..remaining_limit]).map_err(|e| self.io_error(e))?;
+                self.state.consumed += remaining_limit as u64;
+                Err(self.eof())
             }
         }
     }
     fn read_exact(&mut self, slice: &mut [u8]) -> Result<()> {
         if self.reached_end() {
-            return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+            return Err(self.eof());
         }
 
         let mut remaining_slice = self.read_buffer_partial(slice)?;
@@ -1039,7 +1372,7 @@ impl<T: Read + Skip> Stream<T> {
     fn skip(&mut self, amnt: i32) -> Result<()> {
         let amnt_usize = amnt as usize;
         if self.reached_end() {
-            return Err(io::Error::from(ErrorKind::UnexpectedEof).into());
+            return Err(self.eof());
         }
 
         let limit_buf_len = self.buffer.to_limit_len();
@@ -1049,23 +1382,22 @@ impl<T: Read + Skip> Stream<T> {
         } else {
             unsafe { self.buffer.advance(limit_buf_len); }
             let remaining_amnt = amnt - limit_buf_len as i32;
-            match self.remaining_limit.cmp(&0) {
-                Ordering::Less => {
-                    self.input.skip_exact(unsafe { Length::new_unchecked(remaining_amnt) }).map_err(Into::into)
-                },
-                Ordering::Equal => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+            let skipped = match self.remaining_limit.cmp(&0) {
+                Ordering::Less => unsafe { Length::new_unchecked(remaining_amnt) },
+                Ordering::Equal => return Err(self.eof()),
                 Ordering::Greater => {
                     let remaining_limit = self.remaining_limit;
-                    let remaining_length = unsafe { Length::new_unchecked(remaining_limit) };
                     if remaining_limit > remaining_amnt {
                         self.remaining_limit = 0;
-                        self.input.skip_exact(remaining_length).map_err(Into::into)
                     } else {
                         self.remaining_limit = i32::wrapping_sub(self.remaining_limit, remaining_amnt as i32);
-                        self.input.skip_exact(remaining_length).map_err(Into::into)
                     }
+                    unsafe { Length::new_unchecked(remaining_limit) }
                 }
-            }
+            };
+            self.input.skip_exact(skipped).map_err(|e| self.io_error(e))?;
+            self.state.consumed += skipped.get() as u64;
+            Ok(())
         }
     }
     /// Attempts to refresh the buffer and return the next byte.
@@ -1088,8 +1420,9 @@ impl<T: Read + Skip> Stream<T> {
                 .map_err(Into::into)
         } else {
             let mut buf = [0u8; 1];
-            let result = self.input.read(&mut buf)?;
+            let result = self.input.read(&mut buf).map_err(|e| self.io_error(e))?;
             if result != 0 {
+                self.state.consumed += 1;
                 if self.remaining_limit > 0 {
                     self.remaining_limit -= 1;
                 }
@@ -1114,13 +1447,13 @@ impl<T: Read> Reader for Stream<T> {
         &mut self.state
     }
 
-    fn push_limit(&mut self, limit: i32) -> io::Result<Option<i32>> {
+    fn push_limit(&mut self, limit: i32) -> Result<Option<i32>> {
         let old = match self.remaining_limit() {
             Some(remaining) => {
                 // if we have some existing limit, check ahead of time to
                 // make sure we don't extend behind the existing limit
                 if remaining < limit {
-                    return Err(ErrorKind::UnexpectedEof.into())
+                    return Err(self.error(ErrorKind::LengthExceedsLimit))
                 }
 
                 Some(remaining - limit)
@@ -1144,6 +1477,9 @@ impl<T: Read> Reader for Stream<T> {
     fn reached_limit(&self) -> bool {
         self.buffer.reached_limit() && self.remaining_limit == 0
     }
+    fn remaining_limit(&self) -> Option<i32> {
+        self.buffer.remaining_limit().map(|i| i + self.remaining_limit)
+    }
 
     #[inline]
     fn read_tag(&mut self) -> Result<Option<u32>> {
@@ -1167,7 +1503,7 @@ impl<T: Read> Reader for Stream<T> {
                 return Ok(Some(result));
             }
         }
-        Err(Error::MalformedVarint)
+        Err(self.error(ErrorKind::MalformedVarint))
     }
     fn read_varint32(&mut self) -> Result<u32> {
         let mut result = 0;
@@ -1184,7 +1520,7 @@ impl<T: Read> Reader for Stream<T> {
                 return Ok(result);
             }
         }
-        Err(Error::MalformedVarint)
+        Err(self.error(ErrorKind::MalformedVarint))
     }
     fn read_varint64(&mut self) -> Result<u64> {
         let mut result = 0;
@@ -1195,7 +1531,7 @@ impl<T: Read> Reader for Stream<T> {
                 return Ok(result);
             }
         }
-        Err(Error::MalformedVarint)
+        Err(self.error(ErrorKind::MalformedVarint))
     }
     fn read_bit32(&mut self) -> Result<u32> {
         let mut value = [0u8; 4];
@@ -1210,7 +1546,7 @@ impl<T: Read> Reader for Stream<T> {
     fn read_length_delimited<B: ByteString>(&mut self) -> Result<B> {
         let len = self.read_varint32()? as i32;
         if len < 0 {
-            Err(Error::NegativeSize)
+            Err(self.error(ErrorKind::NegativeSize))
         } else {
             let mut b = B::new(len as usize);
             if len != 0 {
@@ -1219,6 +1555,44 @@ impl<T: Read> Reader for Stream<T> {
             Ok(b)
         }
     }
+    fn read_length_delimited_append(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        let len = self.read_varint32()? as i32;
+        if len < 0 {
+            Err(self.error(ErrorKind::NegativeSize))
+        } else {
+            let start = buf.len();
+            buf.resize(start + len as usize, 0);
+            if len != 0 {
+                self.read_exact(&mut buf[start..]).map_err(|e| { buf.truncate(start); e })?;
+            }
+            Ok(())
+        }
+    }
+    fn read_length_delimited_str_into(&mut self, buf: &mut String) -> Result<()> {
+        let len = self.read_varint32()? as i32;
+        if len < 0 {
+            Err(self.error(ErrorKind::NegativeSize))
+        } else if len as usize <= self.buffer.to_limit_len() {
+            // the whole string is buffered, so it's validated in place before it's copied
+            unsafe { self.buffer.take_str_into(len as usize, buf) }
+                .map_err(|e| self.error(ErrorKind::InvalidString(e)))
+        } else {
+            // the string can't be validated in place, so it's read into the buffer's allocation first
+            let mut bytes = mem::take(buf).into_bytes();
+            bytes.clear();
+            bytes.resize(len as usize, 0);
+            self.read_exact(&mut bytes)?;
+            *buf = String::from_utf8(bytes).map_err(|e| self.error(ErrorKind::InvalidString(e)))?;
+            Ok(())
+        }
+    }
+    fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+        if buf.is_empty() {
+            Ok(())
+        } else {
+            self.read_exact(buf)
+        }
+    }
 
     fn skip_varint(&mut self) -> Result<()> {
         for _ in 0..10 {
@@ -1227,7 +1601,7 @@ impl<T: Read> Reader for Stream<T> {
                 return Ok(());
             }
         }
-        Err(Error::MalformedVarint)
+        Err(self.error(ErrorKind::MalformedVarint))
     }
     fn skip_bit32(&mut self) -> Result<()> {
         self.skip(4)
@@ -1238,11 +1612,23 @@ impl<T: Read> Reader for Stream<T> {
     fn skip_length_delimited(&mut self) -> Result<()> {
         let len = self.read_varint32()? as i32;
         match len.cmp(&0) {
-            Ordering::Less => Err(Error::NegativeSize),
+            Ordering::Less => Err(self.error(ErrorKind::NegativeSize)),
             Ordering::Equal => Ok(()),
             Ordering::Greater => self.skip(len)
         }
     }
+    fn skip_to_limit(&mut self) -> Result<()> {
+        match self.remaining_limit() {
+            Some(0) => Ok(()),
+            Some(remaining) => self.skip(remaining),
+            None => {
+                unsafe { self.buffer.advance(self.buffer.to_limit_len()); }
+                self.state.consumed += io::copy(&mut self.input, &mut io::sink()).map_err(|e| self.io_error(e))?;
+                self.reached_eof = true;
+                Ok(())
+            }
+        }
+    }
 
     fn as_any(&mut self) -> Any {
         Any {
@@ -1260,18 +1646,32 @@ impl<T: Read> Reader for Stream<T> {
     fn reached_end(&self) -> bool {
         self.buffer.reached_end() && self.reached_eof
     }
+    fn position(&self) -> u64 {
+        self.state.consumed - self.buffer.to_end_len() as u64
+    }
 }
 
 unsafe impl<T: Send> Send for Stream<T> { }
 unsafe impl<T: Sync> Sync for Stream<T> { }
 
 /// Handling options for unknown fields
+/// 
+/// Unknown fields are stored by default so messages from newer peers can be written back out unchanged.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum UnknownFieldHandling {
     /// Stores unknown fields in a message's `UnknownFieldSet`
     Store,
     /// Skips unknown fields when they're encounted
+    /// 
+    /// This leaves a message's `UnknownFieldSet` empty, saving the memory used to store the fields,
+    /// which is useful when messages are only read and never written again. Since the fields are dropped,
+    /// writing the message won't reproduce the input it was read from.
     Skip,
+    /// Returns an [`UnknownField`](enum.ErrorKind.html#variant.UnknownField) error when an unknown field is encountered.
+    /// 
+    /// This can be used to catch schema drift in protocols where both sides are expected to use the same schema.
+    /// Unrecognized values of closed enums are still stored as unknown fields.
+    Error,
 }
 
 impl Default for UnknownFieldHandling {
@@ -1288,11 +1688,27 @@ impl UnknownFieldHandling {
     }
 }
 
+/// Handling options for enum values that aren't defined by the enum
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EnumStrictness {
+    /// Stores unrecognized values in the enum like any other value. This is the behavior of proto3 enums.
+    Open,
+    /// Treats unrecognized values as unknown fields. This is the behavior of proto2 enums.
+    Closed,
+}
+
+impl Default for EnumStrictness {
+    fn default() -> Self {
+        EnumStrictness::Open
+    }
+}
+
 #[derive(Clone, Debug)]
 struct ReaderOptions {
     unknown_fields: UnknownFieldHandling,
     registry: Option<&'static ExtensionRegistry>,
     recursion_limit: usize,
+    field_remap: Option<Arc<FieldRemap>>,
 }
 
 impl Default for ReaderOptions {
@@ -1301,6 +1717,7 @@ impl Default for ReaderOptions {
             unknown_fields: UnknownFieldHandling::Store,
             registry: None,
             recursion_limit: 100,
+            field_remap: None,
         }
     }
 }
@@ -1335,6 +1752,12 @@ impl Builder {
         self.options.recursion_limit = limit;
         self
     }
+    /// Sets the field remapping applied to tags read by the reader. No remapping is used by default.
+    #[inline]
+    pub fn field_remap(mut self, remap: Option<Arc<FieldRemap>>) -> Self {
+        self.options.field_remap = remap;
+        self
+    }
     /// Constructs a [`CodedReader`](struct.CodedReader.html) using this builder and 
     /// the specified slice of bytes
     #[inline]
@@ -1361,6 +1784,155 @@ impl Builder {
     }
 }
 
+/// A set of field numbers allowed by [`read_field_filtered`](struct.CodedReader.html#method.read_field_filtered).
+/// 
+/// Field numbers are stored as sorted ranges, so large contiguous ranges don't take up more space.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::FieldNumber;
+/// use protrust::io::read::FieldFilter;
+/// 
+/// let mut filter = FieldFilter::new();
+/// filter.insert(FieldNumber::new(1).unwrap());
+/// filter.insert_range(FieldNumber::new(3).unwrap(), FieldNumber::new(5).unwrap());
+/// 
+/// assert!(filter.contains(FieldNumber::new(1).unwrap()));
+/// assert!(!filter.contains(FieldNumber::new(2).unwrap()));
+/// assert!(filter.contains(FieldNumber::new(4).unwrap()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldFilter {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl FieldFilter {
+    /// Creates a new empty filter which doesn't allow any fields.
+    #[inline]
+    pub fn new() -> FieldFilter {
+        FieldFilter { ranges: Vec::new() }
+    }
+    /// Allows the specified field number.
+    #[inline]
+    pub fn insert(&mut self, num: FieldNumber) {
+        self.insert_range(num, num)
+    }
+    /// Allows all field numbers between `start` and `end`, inclusive. If `start` is greater than `end` this does nothing.
+    pub fn insert_range(&mut self, start: FieldNumber, end: FieldNumber) {
+        let (mut start, mut end) = (start.get(), end.get());
+        if start > end {
+            return;
+        }
+
+        // find all the ranges that overlap or touch the new range and merge them into it
+        let first = self.ranges.iter().position(|&(_, e)| e.saturating_add(1) >= start).unwrap_or_else(|| self.ranges.len());
+        let last = self.ranges[first..].iter().position(|&(s, _)| s > end.saturating_add(1)).map_or(self.ranges.len(), |i| first + i);
+        if let (Some(&(s, _)), Some(&(_, e))) = (self.ranges[first..last].first(), self.ranges[first..last].last()) {
+            start = cmp::min(start, s);
+            end = cmp::max(end, e);
+        }
+        self.ranges.splice(first..last, Some((start, end)));
+    }
+    /// Returns whether the filter allows the specified field number.
+    pub fn contains(&self, num: FieldNumber) -> bool {
+        let num = num.get();
+        self.ranges
+            .binary_search_by(|&(s, e)| {
+                if e < num {
+                    Ordering::Less
+                } else if s > num {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+}
+
+impl std::iter::FromIterator<FieldNumber> for FieldFilter {
+    fn from_iter<I: IntoIterator<Item = FieldNumber>>(iter: I) -> FieldFilter {
+        let mut filter = FieldFilter::new();
+        filter.extend(iter);
+        filter
+    }
+}
+
+impl Extend<FieldNumber> for FieldFilter {
+    fn extend<I: IntoIterator<Item = FieldNumber>>(&mut self, iter: I) {
+        for num in iter {
+            self.insert(num);
+        }
+    }
+}
+
+/// A mapping from old field numbers to new field numbers, used to read data written
+/// before a field was renumbered. Set with [`CodedReader::set_field_remap`].
+/// 
+/// The field number of every tag read is remapped before it's returned, so the generated
+/// `merge_from` sees the new field number.
+/// 
+/// # Hazards
+/// 
+/// Only the field number is remapped: the wire type of the old field must match the wire type
+/// expected by the new field, or the value is treated as an unknown field. The remapping also
+/// applies to every message read by the reader, including nested messages, so it should
+/// only be used when the remapped field numbers aren't used by any other message in the input.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::sync::Arc;
+/// use protrust::io::{FieldNumber, CodedReader};
+/// use protrust::io::read::FieldRemap;
+/// 
+/// let mut remap = FieldRemap::new();
+/// remap.insert(FieldNumber::new(1).unwrap(), FieldNumber::new(5).unwrap());
+/// 
+/// // field 1 = 150
+/// let data = [8, 150, 1];
+/// let mut reader = CodedReader::with_slice(&data);
+/// reader.set_field_remap(Some(Arc::new(remap)));
+/// 
+/// let tag = reader.read_tag()?.unwrap();
+/// assert_eq!(tag.field().get(), 5);
+/// assert_eq!(reader.read_varint32()?, 150);
+/// # Ok::<(), protrust::io::read::Error>(())
+/// ```
+/// 
+/// [`CodedReader::set_field_remap`]: struct.CodedReader.html#method.set_field_remap
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldRemap {
+    map: HashMap<u32, FieldNumber>,
+}
+
+impl FieldRemap {
+    /// Creates a new empty remapping
+    #[inline]
+    pub fn new() -> FieldRemap {
+        Default::default()
+    }
+    /// Remaps the old field number to the new field number, returning the number it was previously remapped to.
+    pub fn insert(&mut self, old: FieldNumber, new: FieldNumber) -> Option<FieldNumber> {
+        self.map.insert(old.get(), new)
+    }
+    /// Gets the field number the old field number is remapped to
+    pub fn get(&self, old: FieldNumber) -> Option<FieldNumber> {
+        self.map.get(&old.get()).copied()
+    }
+    /// Returns whether no field numbers are remapped
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    fn remap_tag(&self, tag: u32) -> u32 {
+        match self.map.get(&(tag >> 3)) {
+            Some(new) => (new.get() << 3) | (tag & 0b111),
+            None => tag,
+        }
+    }
+}
+
 /// A reader used by generated code to quickly parse field values without tag
 /// wire type and field number checking.
 /// 
@@ -1407,11 +1979,30 @@ impl<'a, T: Input + 'a> FieldReader<'a, T> {
     pub fn add_entries_to<U: RepeatedValue<V>, V>(self, field: FieldNumber, value: &mut U) -> Result<()> {
         self.and_then(Tag::new(field, U::WIRE_TYPE), |input| input.add_entries_to::<U, V>(value))
     }
+    /// Merges an enum value from the input with an existing value.
+    /// 
+    /// If the enum is [`Closed`](enum.EnumStrictness.html#variant.Closed) and the value read isn't known by the enum,
+    /// the existing value is left unchanged and the value is added to the unknown fields instead.
+    /// 
+    /// This sets the last tag to be a varint tag made from the specified field number.
+    #[inline]
+    pub fn merge_enum<E: crate::Enum>(self, field: FieldNumber, value: &mut E, strictness: EnumStrictness, unknown_fields: &mut UnknownFieldSet) -> Result<()> {
+        self.and_then(Tag::new(field, WireType::Varint), |input| {
+            let raw = input.read_value::<raw::Int32>()?;
+            let read = E::from(raw);
+            if strictness == EnumStrictness::Open || read.is_known() {
+                *value = read;
+            } else if !input.unknown_field_handling().skip() {
+                unknown_fields.push_value(field, UnknownField::Varint(i64::from(raw) as u64));
+            }
+            Ok(())
+        })
+    }
 
     /// Reads the field value using the specified function, checking if the tag is valid before running the function.
     #[inline]
     pub fn check_and_then<R, F: FnOnce(&'a mut CodedReader<T>) -> Result<R>>(self, f: F) -> Result<R> {
-        let tag = Tag::try_from(self.tag).map_err(|_| Error::InvalidTag(self.tag))?;
+        let tag = Tag::try_from(self.tag).map_err(|_| self.inner.error(ErrorKind::InvalidTag(self.tag)))?;
         self.inner.set_last_tag(Some(tag));
 
         f(self.inner)
@@ -1431,6 +2022,11 @@ pub struct Limit<'a, T: Input + 'a> {
 }
 
 impl<'a, T: Input + 'a> Limit<'a, T> {
+    /// Returns the number of bytes left to read in the length delimited value.
+    pub fn remaining(&self) -> i32 {
+        self.inner.bytes_remaining_in_limit().unwrap_or(0)
+    }
+
     /// Reads a length delimited value using the specified function.
     pub fn then<R, F: FnOnce(&mut CodedReader<T>) -> Result<R>>(self, f: F) -> Result<R> {
         let result = f(self.inner)?;
@@ -1453,6 +2049,36 @@ impl<'a, T: Input + 'a> Drop for Limit<'a, T> {
     }
 }
 
+/// A guard over a reader with a limit pushed by [`CodedReader::limit_scope`](struct.CodedReader.html#method.limit_scope).
+/// 
+/// The guard dereferences to the limited reader, and pops the limit when it's dropped,
+/// including when an error is returned early with `?`.
+#[must_use]
+pub struct LimitGuard<'a, T: Input + 'a> {
+    inner: &'a mut CodedReader<T>,
+    old: Option<i32>,
+}
+
+impl<'a, T: Input + 'a> Deref for LimitGuard<'a, T> {
+    type Target = CodedReader<T>;
+
+    fn deref(&self) -> &CodedReader<T> {
+        self.inner
+    }
+}
+
+impl<'a, T: Input + 'a> DerefMut for LimitGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut CodedReader<T> {
+        self.inner
+    }
+}
+
+impl<'a, T: Input + 'a> Drop for LimitGuard<'a, T> {
+    fn drop(&mut self) {
+        self.inner.pop_limit(self.old);
+    }
+}
+
 /// A protobuf coded input reader that reads from a specified input.
 pub struct CodedReader<T: Input> {
     inner: T,
@@ -1484,29 +2110,167 @@ impl<T: Read> CodedReader<Stream<T>> {
     }
 }
 
+impl<'a> CodedReader<Stream<SliceChain<'a>>> {
+    /// Creates a new [`CodedReader`] in the default configuration over a series of
+    /// non-contiguous slices, reading them in order as one continuous input.
+    /// 
+    /// [`CodedReader`]: struct.CodedReader.html
+    pub fn with_slices(slices: &'a [&'a [u8]]) -> Self {
+        Builder::new().with_stream(SliceChain::new(slices))
+    }
+}
+
 impl<'a> CodedReader<Slice<'a>> {
     /// Creates a new [`CodedReader`] over the borrowed [`slice`]
     /// in the default configuration. This is optimized to read directly
     /// from the slice, making it faster than reading from a [`Read`] object.
     /// 
+    /// The reader only borrows the slice while it's reading: string and bytes values are always copied
+    /// out of the slice, so values read from the reader don't borrow from it. This makes it suitable
+    /// for reading from memory that's only valid for a limited time, like a memory mapped file,
+    /// which can be unmapped as soon as the reader and the slice are dropped.
+    /// 
+    /// # Examples
+    /// 
+    /// Reading from a memory mapped file, using a `Vec` in place of the mapping:
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// use protrust::raw;
+    /// 
+    /// # let map = vec![3, 97, 98, 99];
+    /// // let map = unsafe { Mmap::map(&file)? };
+    /// let value = {
+    ///     let mut reader = CodedReader::with_slice(&map[..]);
+    ///     reader.read_value::<raw::String>()?
+    /// };
+    /// drop(map);
+    /// 
+    /// assert_eq!(value, "abc");
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    /// 
     /// [`CodedReader`]: struct.CodedReader.html
     /// [`slice`]: https://doc.rust-lang.org/nightly/std/primitive.slice.html
     /// [`Read`]: streams/trait.Read.html
     pub fn with_slice(inner: &'a [u8]) -> Self {
         Builder::new().with_slice(inner)
     }
+    /// Returns a [`Builder`] with the default configuration, which can be used to
+    /// configure a reader over any input before it's constructed.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// use protrust::io::read::UnknownFieldHandling;
+    /// 
+    /// let input: &[u8] = &[8, 5];
+    /// let mut reader = 
+    ///     CodedReader::builder()
+    ///         .recursion_limit(16)
+    ///         .unknown_fields(UnknownFieldHandling::Skip)
+    ///         .with_capacity(64 * 1024, input);
+    /// 
+    /// assert_eq!(reader.read_varint32()?, 8);
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    /// 
+    /// [`Builder`]: struct.Builder.html
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
 
     /// Consumes the reader, returning the remaining slice
     pub fn into_inner(self) -> &'a [u8] {
         unsafe { self.inner.buffer.to_end_as_slice() }
     }
-}
-
-impl<T: Input> CodedReader<T> {
-    fn increment_recursion_count(&mut self) -> Result<()> {
+    /// Saves the reader's current position and last tag, which can be returned to later with [`restore`](#method.restore).
+    /// 
+    /// This is only supported for readers over slices, since streams can't be rewound.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            position: self.position(),
+            last_tag: self.last_tag(),
+        }
+    }
+    /// Moves the reader back (or forward) to the checkpoint's position, restoring the last tag read at that point.
+    /// 
+    /// This can be used to try reading the input one way and fall back to another if that fails.
+    /// Only the position and last tag are restored. Limits, groups, and the recursion depth
+    /// stay as they are, so checkpoints should be restored in the same limit and group they were made in.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// use protrust::raw;
+    /// 
+    /// let data = [3, 97, 98, 99];
+    /// let mut reader = CodedReader::with_slice(&data);
+    /// 
+    /// let checkpoint = reader.checkpoint();
+    /// assert_eq!(reader.read_value::<raw::Fixed32>()?, 0x6362_6103);
+    /// 
+    /// // read the same bytes again as a string instead
+    /// reader.restore(checkpoint)?;
+    /// assert_eq!(reader.read_value::<raw::String>()?, "abc");
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If the checkpoint's position is past the end of the reader's current limit,
+    /// this returns an `UnexpectedEof` IO error and the reader is left unchanged.
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        let position = self.position();
+        let slice = &mut self.inner;
+        if checkpoint.position <= position {
+            // the reader's slice starts `position` bytes before the current position, so this stays in the slice
+            unsafe { slice.buffer.rewind((position - checkpoint.position) as usize) };
+        } else if checkpoint.position - position <= slice.buffer.to_limit_len() as u64 {
+            unsafe { slice.buffer.advance((checkpoint.position - position) as usize) };
+        } else {
+            return Err(self.eof());
+        }
+        slice.state.last_tag = checkpoint.last_tag;
+        Ok(())
+    }
+}
+
+/// A saved position in a [`CodedReader`](struct.CodedReader.html) over a slice,
+/// made by [`checkpoint`](struct.CodedReader.html#method.checkpoint).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    position: u64,
+    last_tag: Option<Tag>,
+}
+
+impl Checkpoint {
+    /// Gets the position of the checkpoint in the input.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<T: Input> CodedReader<T> {
+    /// Creates an error of the specified kind at the reader's current position
+    pub(crate) fn error(&self, kind: ErrorKind) -> Error {
+        self.inner.error(kind)
+    }
+    /// Creates an unexpected EOF error at the reader's current position
+    pub(crate) fn eof(&self) -> Error {
+        self.inner.eof()
+    }
+    /// Fills the buffer with the next bytes of the input. This doesn't read a length prefix,
+    /// so it's used to read the contents of a value after its limit has been pushed.
+    pub(crate) fn read_raw(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.inner.read_raw(buf)
+    }
+    fn increment_recursion_count(&mut self) -> Result<()> {
         let state = self.inner.state_mut();
         if state.recursion_depth == self.options.recursion_limit {
-            Err(Error::RecursionLimitExceeded)
+            Err(self.error(ErrorKind::RecursionLimitExceeded))
         } else {
             state.recursion_depth += 1;
             Ok(())
@@ -1523,7 +2287,7 @@ impl<T: Input> CodedReader<T> {
     fn pop_group(&mut self, old: Option<Tag>) {
         self.inner.state_mut().next_end_group = old;
     }
-    fn set_last_tag(&mut self, tag: Option<Tag>) {
+    pub(crate) fn set_last_tag(&mut self, tag: Option<Tag>) {
         self.inner.state_mut().last_tag = tag;
     }
 
@@ -1531,15 +2295,58 @@ impl<T: Input> CodedReader<T> {
     pub fn unknown_field_handling(&self) -> UnknownFieldHandling {
         self.options.unknown_fields
     }
+    /// Sets handling options for unknown fields read with this reader.
+    pub fn set_unknown_field_handling(&mut self, value: UnknownFieldHandling) {
+        self.options.unknown_fields = value;
+    }
     /// Gets the registry extendable messages should be created with when
     /// reading from this reader.
     pub fn registry(&self) -> Option<&'static ExtensionRegistry> {
         self.options.registry
     }
+    /// Sets the registry extendable messages should be created with when reading from this reader.
+    /// This only affects messages created after the registry is set.
+    pub fn set_registry(&mut self, registry: Option<&'static ExtensionRegistry>) {
+        self.options.registry = registry;
+    }
+    /// Gets the field remapping applied to tags read by this reader.
+    pub fn field_remap(&self) -> Option<&FieldRemap> {
+        self.options.field_remap.as_deref()
+    }
+    /// Sets the field remapping applied to tags read by this reader, see [`FieldRemap`](struct.FieldRemap.html) for details.
+    pub fn set_field_remap(&mut self, remap: Option<Arc<FieldRemap>>) {
+        self.options.field_remap = remap;
+    }
     /// Gets the last tag read by the reader.
     pub fn last_tag(&self) -> Option<Tag> {
         self.inner.state().last_tag
     }
+    /// Gets the number of bytes read from the start of the input.
+    ///
+    /// Errors returned by the reader record this position as their [`offset`](struct.Error.html#method.offset).
+    /// Values that fail to read partway through may have already consumed some of their bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    ///
+    /// let mut reader = CodedReader::with_slice(&[8, 150, 1, 16, 128]);
+    /// assert_eq!(reader.position(), 0);
+    ///
+    /// reader.read_tag()?;
+    /// reader.read_varint32()?;
+    /// assert_eq!(reader.position(), 3);
+    ///
+    /// reader.read_tag()?;
+    /// let err = reader.read_varint32().unwrap_err();
+    /// assert_eq!(err.offset(), 4);
+    /// assert_eq!(reader.position(), 4);
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
     /// Returns a new CodedReader that can be used to temporarily 
     /// convert the reader into a non-generic reader over [`Any`] input.
     pub fn as_any(&mut self) -> CodedReader<Any> {
@@ -1553,16 +2360,142 @@ impl<T: Input> CodedReader<T> {
     /// 
     /// # Errors
     /// 
-    /// If a negative length is read, this returns a `NegativeSize` error.
+    /// If a negative length is read, this returns a `NegativeSize` error. If the length extends past the
+    /// current limit, this returns a `LengthExceedsLimit` error, and if it extends past the end of a slice input,
+    /// this returns an `UnexpectedEof` IO error.
     pub fn read_limit<'a>(&'a mut self) -> Result<Limit<'a, T>> {
         let limit = self.read_value::<raw::Int32>()?;
         if limit < 0 {
-            Err(Error::NegativeSize)
+            Err(self.error(ErrorKind::NegativeSize))
         } else {
             let old = self.inner.push_limit(limit)?;
             Ok(Limit { inner: self, old })
         }
     }
+    /// Reads a packed run of constant sized values, appending them to the vector.
+    /// 
+    /// Since every value has the same size, the number of values can be calculated from the length
+    /// of the run, so the vector's capacity is reserved up front instead of growing as values are pushed.
+    /// The reservation is bounded so a large length in malformed input can't cause a large allocation
+    /// before the values are actually read.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// use protrust::raw::Fixed32;
+    /// 
+    /// let input = [8, 1, 0, 0, 0, 2, 0, 0, 0];
+    /// let mut reader = CodedReader::with_slice(&input);
+    /// let mut values = Vec::new();
+    /// reader.read_packed_into::<Fixed32>(&mut values)?;
+    /// 
+    /// assert_eq!(values, [1, 2]);
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If a negative length is read, this returns a `NegativeSize` error.
+    pub fn read_packed_into<V: raw::ConstSized>(&mut self, out: &mut Vec<V::Inner>) -> Result<()> {
+        let len = self.read_value::<raw::Int32>()?;
+        if len < 0 {
+            return Err(self.error(ErrorKind::NegativeSize));
+        }
+        let old = self.inner.push_limit(len)?;
+        let limit = Limit { inner: self, old };
+        out.reserve(cmp::min(len as usize, MAX_PACKED_RESERVE) / V::SIZE.get() as usize);
+        limit.for_all(|input| input.read_value::<V>().map(|v| out.push(v)))
+    }
+    /// Skips the rest of the innermost active limit, or the rest of the input if no limit is active,
+    /// returning the number of bytes skipped.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// 
+    /// let input = [3, 1, 2, 3, 4, 5];
+    /// let mut reader = CodedReader::with_slice(&input);
+    /// 
+    /// reader.read_limit()?.then(|r| {
+    ///     r.read_varint32()?;
+    ///     assert_eq!(r.skip_to_end()?, 2);
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(reader.read_varint32()?, 4);
+    /// assert_eq!(reader.skip_to_end()?, 1);
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    pub fn skip_to_end(&mut self) -> Result<u64> {
+        let start = self.position();
+        self.inner.skip_to_limit()?;
+        Ok(self.position() - start)
+    }
+    /// Limits the reader to the next `len` bytes until the returned guard is dropped.
+    /// 
+    /// This is the manual form of [`read_limit`](#method.read_limit), for custom parsers that already
+    /// know the length of a value. The reader is used through the guard, and the previous limit is
+    /// restored when the guard is dropped, so the reader can be used directly again after that.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    /// 
+    /// fn read_pair(reader: &mut CodedReader<impl protrust::io::Input>) -> read::Result<(u32, u32)> {
+    ///     let mut limited = reader.limit_scope(2)?;
+    ///     let a = limited.read_varint32()?;
+    ///     let b = limited.read_varint32()?;
+    ///     assert!(limited.read_varint32().is_err());
+    ///     Ok((a, b))
+    /// }
+    /// 
+    /// let input = [1, 2, 3];
+    /// let mut reader = CodedReader::with_slice(&input);
+    /// assert_eq!(read_pair(&mut reader)?, (1, 2));
+    /// assert_eq!(reader.read_varint32()?, 3);
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If `len` is negative, this returns a `NegativeSize` error. If the limit extends past the
+    /// current limit, this returns a `LengthExceedsLimit` error, and if it extends past the end of a slice input,
+    /// this returns an `UnexpectedEof` IO error.
+    pub fn limit_scope(&mut self, len: i32) -> Result<LimitGuard<T>> {
+        if len < 0 {
+            return Err(self.error(ErrorKind::NegativeSize));
+        }
+        let old = self.inner.push_limit(len)?;
+        Ok(LimitGuard { inner: self, old })
+    }
+    /// Returns the number of bytes left before the end of the innermost active limit,
+    /// or `None` if no limit is active.
+    /// 
+    /// A limit is pushed when reading a length delimited value like a message or a packed field,
+    /// so this can be used by a message's `merge_from` to find how much of the message is left to read.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedReader;
+    /// 
+    /// let input = [2, 1, 2];
+    /// let mut reader = CodedReader::with_slice(&input);
+    /// assert_eq!(reader.bytes_remaining_in_limit(), None);
+    /// 
+    /// reader.read_limit()?.then(|r| {
+    ///     assert_eq!(r.bytes_remaining_in_limit(), Some(2));
+    ///     r.read_varint32()?;
+    ///     assert_eq!(r.bytes_remaining_in_limit(), Some(1));
+    ///     r.read_varint32()
+    /// })?;
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    pub fn bytes_remaining_in_limit(&self) -> Option<i32> {
+        self.inner.remaining_limit()
+    }
     fn pop_limit(&mut self, old: Option<i32>) {
         self.inner.pop_limit(old)
     }
@@ -1572,20 +2505,103 @@ impl<T: Input> CodedReader<T> {
 
     #[inline]
     fn read_raw_tag(&mut self) -> Result<Option<u32>> {
-        let tag = self.inner.read_tag()?;
-        let end_group = self.inner.state().next_end_group.map(Tag::get);
-        if tag == end_group {
+        let mut tag = self.inner.read_tag()?;
+        if let (Some(t), Some(remap)) = (tag, &self.options.field_remap) {
+            // remap before checking for the end of a group so remapped groups end on their remapped end tag
+            tag = Some(remap.remap_tag(t));
+        }
+        let state = self.inner.state_mut();
+        if tag.is_some() && tag == state.next_end_group.map(Tag::get) {
+            // clear the end tag to mark the group as terminated
+            state.next_end_group = None;
             Ok(None)
         } else {
             Ok(tag)
         }
     }
+    /// Runs the function in a group started by the specified field, erroring if the 
+    /// function returns before the group's end tag has been read.
+    fn in_group<R, F: FnOnce(&mut Self) -> Result<R>>(&mut self, field: FieldNumber, f: F) -> Result<R> {
+        struct Guard<'a, T: Input + 'a> {
+            inner: &'a mut CodedReader<T>,
+            last_group: Option<Tag>,
+        }
+        impl<'a, T: Input + 'a> Drop for Guard<'a, T> {
+            fn drop(&mut self) {
+                self.inner.pop_group(self.last_group);
+            }
+        }
 
-    /// Reads a field tag from the input
+        let last_group = self.push_group(field);
+        let guard = Guard { inner: self, last_group };
+        let result = f(guard.inner)?;
+        if guard.inner.inner.state().next_end_group.is_some() {
+            return Err(guard.inner.eof());
+        }
+
+        Ok(result)
+    }
+    /// Reads the fields of a group started by the last tag, calling the function for each tag in the group.
+    /// This errors if an end group tag is found for a different field or if the input ends before the group does.
+    pub(crate) fn read_group_fields<F: FnMut(&mut Self, Tag) -> Result<()>>(&mut self, mut f: F) -> Result<()> {
+        let start = match self.last_tag() {
+            Some(tag) => tag,
+            None => return Ok(()),
+        };
+        debug_assert!(start.wire_type() == WireType::StartGroup, "attempted to read group from tag that wasn't a start group tag");
+
+        self.in_group(start.field(), |input| {
+            while let Some(tag) = input.read_tag()? {
+                if tag.wire_type() == WireType::EndGroup {
+                    return Err(input.unexpected_end_group(tag));
+                }
+                f(input, tag)?;
+            }
+            Ok(())
+        })
+    }
+    /// Gets the error to return for an end group tag that doesn't end the current group.
+    pub(crate) fn unexpected_end_group(&self, tag: Tag) -> Error {
+        match self.inner.state().next_end_group {
+            Some(end) => self.error(ErrorKind::GroupMismatch { start: end.field(), end: tag.field() }),
+            None => self.error(ErrorKind::InvalidTag(tag.get())),
+        }
+    }
+
+    /// Reads a field tag from the input, returning `None` at the end of the input or the end of the current group.
+    /// 
+    /// Unlike [`read_field`](#method.read_field), which defers checking the tag for generated code
+    /// matching on raw tag values, this validates the tag, which makes it easier to use in hand written parsers.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{read, CodedReader, WireType};
+    /// 
+    /// // field 1 = 150, field 2 = "hi"
+    /// let data = [8, 150, 1, 18, 2, 104, 105];
+    /// let mut reader = CodedReader::with_slice(&data);
+    /// let (mut id, mut name) = (0, String::new());
+    /// 
+    /// while let Some(tag) = reader.read_tag()? {
+    ///     match (tag.field().get(), tag.wire_type()) {
+    ///         (1, WireType::Varint) => id = reader.read_varint32()?,
+    ///         (2, WireType::LengthDelimited) => reader.read_string_into(&mut name)?,
+    ///         _ => reader.skip()?,
+    ///     }
+    /// }
+    /// 
+    /// assert_eq!((id, name.as_str()), (150, "hi"));
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If the tag has a field number of 0 or an invalid wire type (6 or 7), this returns an `InvalidTag` error.
     pub fn read_tag(&mut self) -> Result<Option<Tag>> {
         let tag = 
             self.read_raw_tag()?
-                .map(|v| Tag::try_from(v).map_err(|_| Error::InvalidTag(v)))
+                .map(|v| Tag::try_from(v).map_err(|_| self.error(ErrorKind::InvalidTag(v))))
                 .transpose()?;
         self.set_last_tag(tag);
 
@@ -1593,10 +2609,15 @@ impl<T: Input> CodedReader<T> {
     }
     /// Reads a 32-bit varint field value. This is functionally similar to [`read_varint64`](#method.read_varint64),
     /// but is optimised for 32-bit values and will discard any top bits from 64-bit values.
+    ///
+    /// Like 64-bit varints, this reads at most 10 bytes, since negative 32-bit values are sign extended
+    /// and encoded with 10 bytes. If the 10th byte has its continuation bit set, this returns a `MalformedVarint` error.
     pub fn read_varint32(&mut self) -> Result<u32> {
         self.inner.read_varint32()
     }
     /// Reads a 64-bit varint field value.
+    ///
+    /// This reads at most 10 bytes. If the 10th byte has its continuation bit set, this returns a `MalformedVarint` error.
     pub fn read_varint64(&mut self) -> Result<u64> {
         self.inner.read_varint64()
     }
@@ -1612,52 +2633,88 @@ impl<T: Input> CodedReader<T> {
     pub fn read_length_delimited<B: ByteString>(&mut self) -> Result<B> {
         self.inner.read_length_delimited()
     }
+    /// Reads a length delimited string of bytes into the provided buffer, reusing its allocation.
+    /// The buffer is cleared before reading and is left empty if the read fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    ///
+    /// let mut reader = CodedReader::with_slice(&[3, 1, 2, 3, 2, 4, 5]);
+    /// let mut buf = Vec::new();
+    ///
+    /// reader.read_bytes_into(&mut buf)?;
+    /// assert_eq!(buf, [1, 2, 3]);
+    ///
+    /// reader.read_bytes_into(&mut buf)?;
+    /// assert_eq!(buf, [4, 5]);
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    pub fn read_bytes_into(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.clear();
+        self.inner.read_length_delimited_append(buf)
+    }
+    /// Reads a length delimited UTF-8 string into the provided buffer, reusing its allocation.
+    ///
+    /// The buffer is cleared and the string is read into its existing allocation. If the read fails,
+    /// the buffer is left empty rather than holding part of the string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    ///
+    /// let mut buf = String::from("old");
+    ///
+    /// // a truncated string, followed by an invalid one
+    /// assert!(CodedReader::with_slice(&[3, 104]).read_string_into(&mut buf).is_err());
+    /// assert!(buf.is_empty());
+    /// assert!(CodedReader::with_slice(&[1, 0xff]).read_string_into(&mut buf).is_err());
+    /// assert!(buf.is_empty());
+    ///
+    /// CodedReader::with_slice(&[2, 104, 105]).read_string_into(&mut buf)?;
+    /// assert_eq!(buf, "hi");
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the string isn't valid UTF-8, this returns an `InvalidString` error.
+    pub fn read_string_into(&mut self, buf: &mut String) -> Result<()> {
+        self.inner.read_length_delimited_str_into(buf).map_err(|e| { buf.clear(); e })
+    }
     /// Reads a group, merging it's fields into the provided message instance.
+    /// 
+    /// # Errors
+    /// 
+    /// If the input ends before the group's end tag is read, this returns an `IoError`.
     pub fn read_group<M: Message>(&mut self, value: &mut M) -> Result<()> {
-        struct Guard<'a, T: Input + 'a> {
-            inner: &'a mut CodedReader<T>,
-            last_group: Option<Tag>,
-        }
-        impl<'a, T: Input + 'a> Drop for Guard<'a, T> {
-            fn drop(&mut self) {
-                self.inner.pop_group(self.last_group);
-            }
-        }
-
         if let Some(last_tag) = self.last_tag() {
             debug_assert!(last_tag.wire_type() == WireType::StartGroup, "attempted to read group from tag that wasn't a start group tag");
-            let last_group = self.push_group(last_tag.field());
-    
-            let guard = Guard { inner: self, last_group };
-            let result = value.merge_from(guard.inner);
-            drop(guard);
-    
-            result
+            self.in_group(last_tag.field(), |input| value.merge_from(input))
         } else {
             Ok(())
         }
     }
     /// Skips the last field read from the input
+    /// 
+    /// # Errors
+    /// 
+    /// When skipping a group, if an end group tag is read for a field other than the one 
+    /// that started the group, this returns a `GroupMismatch` error. If the input ends 
+    /// before the group's end tag is read, this returns an `IoError`.
+    /// 
+    /// Skipping an end group tag that doesn't end the group currently being read returns a 
+    /// `GroupMismatch` error, or an `InvalidTag` error if no group is being read.
     pub fn skip(&mut self) -> Result<()> {
         if let Some(last_tag) = self.last_tag() {
             match last_tag.wire_type() {
                 WireType::Varint => self.inner.skip_varint()?,
                 WireType::Bit64 => self.inner.skip_bit64()?,
                 WireType::LengthDelimited => self.inner.skip_length_delimited()?,
-                WireType::StartGroup => {
-                    self.recurse(|s| {
-                        let end = Tag::new(last_tag.field(), WireType::EndGroup);
-                        loop {
-                            match s.read_tag()? {
-                                Some(tag) if tag == end => break Ok(()),
-                                Some(tag) if tag.wire_type() == WireType::EndGroup => return Err(Error::InvalidTag(tag.get())),
-                                Some(_) => s.skip()?,
-                                None => return Err(io::Error::from(ErrorKind::UnexpectedEof).into())
-                            }
-                        }
-                    })?
-                },
-                WireType::EndGroup => { },
+                WireType::StartGroup => self.recurse(|s| s.read_group_fields(|s, _| s.skip()))?,
+                WireType::EndGroup => return Err(self.unexpected_end_group(last_tag)),
                 WireType::Bit32 => self.inner.skip_bit32()?,
             }
         }
@@ -1690,7 +2747,46 @@ impl<T: Input> CodedReader<T> {
     /// many fields when the tag's underlying value already exists as a constant.
     #[inline]
     pub fn read_field<'a>(&'a mut self) -> Result<Option<FieldReader<'a, T>>> {
-        self.inner.read_tag().map(move |t| t.map(move |t| FieldReader { inner: self, tag: t }))
+        self.read_raw_tag().map(move |t| t.map(move |t| FieldReader { inner: self, tag: t }))
+    }
+    /// Reads a field value, returning an error if the field's number isn't allowed by the filter.
+    /// 
+    /// Unlike [`read_field`](#method.read_field), fields that aren't expected are rejected instead
+    /// of being left to be skipped or added to unknown fields.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{read, FieldNumber, CodedReader};
+    /// use protrust::io::read::FieldFilter;
+    /// 
+    /// let filter: FieldFilter = FieldNumber::new(1).into_iter().collect();
+    /// let data = [8, 1, 16, 2];
+    /// let mut reader = CodedReader::with_slice(&data);
+    /// 
+    /// let field = reader.read_field_filtered(&filter)?.unwrap();
+    /// assert_eq!(field.tag(), 8);
+    /// field.check_and_then(|r| r.skip())?;
+    /// 
+    /// assert!(matches!(
+    ///     reader.read_field_filtered(&filter).map_err(read::Error::into_kind),
+    ///     Err(read::ErrorKind::UnexpectedField { number }) if number.get() == 2
+    /// ));
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    #[inline]
+    pub fn read_field_filtered<'a>(&'a mut self, allowed: &FieldFilter) -> Result<Option<FieldReader<'a, T>>> {
+        match self.read_raw_tag()? {
+            Some(tag) => {
+                let number = FieldNumber::new(tag >> 3).ok_or_else(|| self.error(ErrorKind::InvalidTag(tag)))?;
+                if allowed.contains(number) {
+                    Ok(Some(FieldReader { inner: self, tag }))
+                } else {
+                    Err(self.error(ErrorKind::UnexpectedField { number }))
+                }
+            },
+            None => Ok(None),
+        }
     }
     /// Reads a new instance of the value from the reader.
     /// This is the inverse of `Value::read_new`.
@@ -1698,6 +2794,42 @@ impl<T: Input> CodedReader<T> {
     pub fn read_value<V: Value>(&mut self) -> Result<V::Inner> {
         V::read_new(self)
     }
+    /// Reads an enum value from the reader, returning `None` if the enum is
+    /// [`Closed`](enum.EnumStrictness.html#variant.Closed) and the value isn't known by the enum.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// # pub struct Color(pub i32);
+    /// # impl From<i32> for Color { fn from(x: i32) -> Self { Color(x) } }
+    /// # impl From<Color> for i32 { fn from(x: Color) -> Self { x.0 } }
+    /// impl protrust::Enum for Color {
+    ///     fn is_known(self) -> bool {
+    ///         matches!(self.0, 0..=2)
+    ///     }
+    /// }
+    /// 
+    /// use protrust::io::{read, CodedReader};
+    /// use protrust::io::read::EnumStrictness;
+    /// 
+    /// let data = [1, 5, 5];
+    /// let mut reader = CodedReader::with_slice(&data);
+    /// 
+    /// assert_eq!(reader.read_enum::<Color>(EnumStrictness::Closed)?, Some(Color(1)));
+    /// assert_eq!(reader.read_enum::<Color>(EnumStrictness::Closed)?, None);
+    /// assert_eq!(reader.read_enum::<Color>(EnumStrictness::Open)?, Some(Color(5)));
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    #[inline]
+    pub fn read_enum<E: crate::Enum>(&mut self, strictness: EnumStrictness) -> Result<Option<E>> {
+        let value = E::from(self.read_value::<raw::Int32>()?);
+        if strictness == EnumStrictness::Open || value.is_known() {
+            Ok(Some(value))
+        } else {
+            Ok(None)
+        }
+    }
     /// Merges the reader's value with the value from the reader.
     /// This is the inverse of `Value::merge_from`.
     #[inline]
@@ -1710,18 +2842,410 @@ impl<T: Input> CodedReader<T> {
     pub fn add_entries_to<U: RepeatedValue<V>, V>(&mut self, value: &mut U) -> Result<()> {
         value.add_entries_from(self)
     }
-    /// Tries to add the field value to the field set.
-    /// This is the inverse of `FieldSet::try_add_field_from`.
-    #[inline]
-    pub fn try_add_field_to<'a, U: FieldSet>(&'a mut self, value: &mut U) -> Result<TryRead<'a, T>> {
-        value.try_add_field_from(self)
+    /// Reads a single length delimited map entry, returning the key and value.
+    ///
+    /// The key and value fields can appear in any order. If a field appears more than once, the last value is used,
+    /// and if a field doesn't appear, the default value is used. Unknown fields in the entry are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    /// use protrust::raw::{Int32, String};
+    ///
+    /// let mut reader = CodedReader::with_slice(&[6, 18, 2, 104, 105, 8, 1, 2, 8, 2]);
+    ///
+    /// assert_eq!(reader.read_map_entry::<Int32, String>()?, (1, "hi".to_string()));
+    /// assert_eq!(reader.read_map_entry::<Int32, String>()?, (2, "".to_string()));
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    pub fn read_map_entry<K, V>(&mut self) -> Result<(K::Inner, V::Inner)>
+        where
+            K: Value,
+            K::Inner: Default + Sized,
+            V: Value,
+            V::Inner: Default + Sized
+    {
+        let key_tag = Tag::new(KEY_FIELD, K::WIRE_TYPE);
+        let value_tag = Tag::new(VALUE_FIELD, V::WIRE_TYPE);
+
+        let mut key = None::<K::Inner>;
+        let mut value = None::<V::Inner>;
+        self.read_limit()?.then(|input| {
+            while let Some(field) = input.read_field()? {
+                match field.tag() {
+                    k if k == key_tag.get() => field.and_then(key_tag, |input| input.read_value::<K>().map(|k| key = Some(k))),
+                    v if v == value_tag.get() => field.and_then(value_tag, |input| input.read_value::<V>().map(|v| value = Some(v))),
+                    _ => field.check_and_then(|input| input.skip()),
+                }?
+            }
+            Ok(())
+        })?;
+
+        Ok((key.unwrap_or_default(), value.unwrap_or_default()))
+    }
+    /// Tries to add the field value to the field set.
+    /// This is the inverse of `FieldSet::try_add_field_from`.
+    #[inline]
+    pub fn try_add_field_to<'a, U: FieldSet>(&'a mut self, value: &mut U) -> Result<TryRead<'a, T>> {
+        value.try_add_field_from(self)
+    }
+    /// Reads every field to the end of the input or current limit, appending the encoded fields to the buffer
+    /// without knowing the message's type.
+    /// 
+    /// Fields are always captured, no matter the reader's [`UnknownFieldHandling`](enum.UnknownFieldHandling.html).
+    /// Every field is checked as it's read, so malformed tags, truncated values and mismatched groups are errors,
+    /// and groups count towards the recursion limit. Fields are appended in the order they were read, so replaying
+    /// the buffer reproduces the input. Tags and varints are re-encoded in their shortest form, so the output is only
+    /// byte for byte identical when the input didn't use overlong varints. If the read fails, the buffer is truncated
+    /// back to its original length.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{read, CodedReader};
+    /// 
+    /// // field 2 = group { field 3 = 5 }, field 1 = "hi"
+    /// let data = [19, 24, 5, 20, 10, 2, 104, 105];
+    /// let mut reader = CodedReader::with_slice(&data);
+    /// let mut captured = Vec::new();
+    /// reader.read_all_into_unknown(&mut captured)?;
+    /// 
+    /// assert_eq!(captured, data);
+    /// # Ok::<(), read::Error>(())
+    /// ```
+    pub fn read_all_into_unknown(&mut self, buf: &mut Vec<u8>) -> Result<()> {
+        let start = buf.len();
+        let result = (|| {
+            while let Some(tag) = self.read_tag()? {
+                self.copy_field_to(tag, buf)?;
+            }
+            Ok(())
+        })();
+        if result.is_err() {
+            buf.truncate(start);
+        }
+        result
+    }
+    fn copy_field_to(&mut self, tag: Tag, buf: &mut Vec<u8>) -> Result<()> {
+        fn push_varint(buf: &mut Vec<u8>, mut value: u64) {
+            while value >= 0x80 {
+                buf.push(value as u8 | 0x80);
+                value >>= 7;
+            }
+            buf.push(value as u8);
+        }
+
+        push_varint(buf, tag.get() as u64);
+        match tag.wire_type() {
+            WireType::Varint => push_varint(buf, self.read_varint64()?),
+            WireType::Bit64 => buf.extend_from_slice(&self.read_bit64()?.to_le_bytes()),
+            WireType::LengthDelimited => {
+                let len = self.read_varint32()? as i32;
+                if len < 0 {
+                    return Err(self.error(ErrorKind::NegativeSize));
+                }
+                push_varint(buf, len as u64);
+                // read in chunks so a huge declared length can't reserve more than the input holds
+                let mut remaining = len as usize;
+                while remaining != 0 {
+                    let at = buf.len();
+                    let chunk = cmp::min(remaining, DEFAULT_BUF_SIZE);
+                    buf.resize(at + chunk, 0);
+                    self.read_raw(&mut buf[at..])?;
+                    remaining -= chunk;
+                }
+            },
+            WireType::StartGroup => {
+                self.recurse(|input| input.read_group_fields(|input, tag| input.copy_field_to(tag, buf)))?;
+                push_varint(buf, Tag::new(tag.field(), WireType::EndGroup).get() as u64);
+            },
+            WireType::EndGroup => return Err(self.unexpected_end_group(tag)),
+            WireType::Bit32 => buf.extend_from_slice(&self.read_bit32()?.to_le_bytes()),
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over a stream of length delimited messages, where each message is prefixed by its length as a varint.
+/// 
+/// This yields `None` when the stream ends cleanly between two messages. If the stream ends in the middle of a length
+/// prefix or a message, this yields an error. After yielding an error, the iterator is finished.
+/// 
+/// # Examples
+/// 
+/// ```ignore
+/// # use protrust::doctest::timestamp::Timestamp;
+/// use protrust::io::read::MessageStream;
+/// 
+/// // two timestamps: seconds = 5 and nanos = 10
+/// let data: &[u8] = &[2, 8, 5, 2, 16, 10];
+/// let messages = MessageStream::<_, Timestamp>::new(data).collect::<Result<Vec<_>, _>>()?;
+/// 
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(messages[0].seconds(), &5);
+/// assert_eq!(messages[1].nanos(), &10);
+/// # Ok::<(), protrust::io::read::Error>(())
+/// ```
+pub struct MessageStream<R: Read, M: Message> {
+    reader: CodedReader<Stream<R>>,
+    finished: bool,
+    message: PhantomData<fn() -> M>,
+}
+
+impl<R: Read, M: Message> MessageStream<R, M> {
+    /// Creates a new message stream reading from the specified [`Read`](https://doc.rust-lang.org/nightly/std/io/trait.Read.html)
+    /// with the default reader configuration.
+    pub fn new(inner: R) -> Self {
+        Self::with_reader(CodedReader::with_stream(inner))
+    }
+    /// Creates a new message stream reading from the specified reader.
+    pub fn with_reader(reader: CodedReader<Stream<R>>) -> Self {
+        MessageStream { reader, finished: false, message: PhantomData }
+    }
+    /// Returns ownership of the underlying reader.
+    pub fn into_inner(self) -> CodedReader<Stream<R>> {
+        self.reader
+    }
+
+    fn read_next(&mut self) -> Result<Option<M>> {
+        // the length prefix is read like a tag so we can tell the end of the stream apart from a truncated length
+        let len = match self.reader.inner.read_tag()? {
+            Some(len) => len as i32,
+            None => return Ok(None),
+        };
+        if len < 0 {
+            return Err(self.reader.error(ErrorKind::NegativeSize));
+        }
+
+        let old = self.reader.inner.push_limit(len)?;
+        let limit = Limit { inner: &mut self.reader, old };
+        let mut message = M::default();
+        message.merge_from(limit.inner)?;
+        if limit.inner.reached_limit() {
+            Ok(Some(message))
+        } else {
+            // the stream ended before the end of the message
+            Err(limit.inner.eof())
+        }
+    }
+}
+
+impl<R: Read, M: Message> Iterator for MessageStream<R, M> {
+    type Item = Result<M>;
+
+    fn next(&mut self) -> Option<Result<M>> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.read_next().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.finished = true;
+        }
+        result
+    }
+}
+
+/// Reads length delimited messages from the reader until the end of its input, collecting them into a `Vec`.
+/// 
+/// This is the collecting counterpart to [`MessageStream`]: messages must be prefixed by their length as a varint,
+/// and the input must end cleanly between two messages. If the input ends in the middle of a length prefix or
+/// a message, an error is returned and the messages read before it are discarded.
+/// 
+/// # Examples
+/// 
+/// ```ignore
+/// # use protrust::doctest::timestamp::Timestamp;
+/// use protrust::io::read;
+/// 
+/// // two timestamps: seconds = 5 and nanos = 10
+/// let data: &[u8] = &[2, 8, 5, 2, 16, 10];
+/// let messages: Vec<Timestamp> = read::decode_all(data)?;
+/// 
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(messages[1].nanos(), &10);
+/// # Ok::<(), protrust::io::read::Error>(())
+/// ```
+/// 
+/// [`MessageStream`]: struct.MessageStream.html
+pub fn decode_all<M: Message, R: Read>(r: R) -> Result<Vec<M>> {
+    MessageStream::new(r).collect()
+}
+
+/// Describes where a best effort merge stopped reading, returned by
+/// [`Message::merge_best_effort`](../../trait.Message.html#method.merge_best_effort).
+#[derive(Debug)]
+pub struct Truncated {
+    error: Error,
+    position: u64,
+    skipped: u64,
+}
+
+impl Truncated {
+    pub(crate) fn new(error: Error, position: u64, skipped: u64) -> Self {
+        Truncated { error, position, skipped }
+    }
+    /// Gets the error that stopped the merge.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+    /// Gets the position of the reader when the error was returned.
+    /// Some or all of the field being read at that position may have been consumed.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Gets the number of bytes that were skipped after the error.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+    /// Consumes the truncation, returning the error that stopped the merge.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+/// The result of feeding bytes to a [`PartialParser`](struct.PartialParser.html).
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseProgress<M> {
+    /// A complete message was parsed.
+    Complete(M),
+    /// More bytes are needed before the next message can be parsed.
+    NeedMore,
+}
+
+/// A parser for length delimited messages that accepts input in chunks as it becomes available,
+/// for use where a blocking [`Read`](https://doc.rust-lang.org/nightly/std/io/trait.Read.html) isn't available.
+/// 
+/// This works at the granularity of whole messages: bytes are buffered until the length prefix and
+/// all the bytes of the next message are available, and then the message is parsed in one go.
+/// It doesn't suspend in the middle of a field, so a single message is never parsed incrementally.
+/// 
+/// Each call to [`feed`](#method.feed) parses at most one message. Any bytes after that message
+/// stay buffered, so after a message is returned, `feed` should be called again with an empty
+/// slice until it returns `NeedMore`.
+/// 
+/// Since a whole message is buffered before it's parsed, the length prefix is checked against a maximum
+/// before any of the message is buffered. This defaults to 64 MiB, and can be changed with
+/// [`max_message_len`](#method.max_message_len).
+/// 
+/// # Examples
+/// 
+/// ```ignore
+/// # use protrust::doctest::timestamp::Timestamp;
+/// use protrust::io::read::{PartialParser, ParseProgress};
+/// 
+/// let mut parser = PartialParser::<Timestamp>::new();
+/// 
+/// // seconds = 5, split over two chunks, followed by the first byte of the next message
+/// assert_eq!(parser.feed(&[2, 8])?, ParseProgress::NeedMore);
+/// match parser.feed(&[5, 2])? {
+///     ParseProgress::Complete(t) => assert_eq!(t.seconds(), &5),
+///     ParseProgress::NeedMore => unreachable!(),
+/// }
+/// assert_eq!(parser.feed(&[])?, ParseProgress::NeedMore);
+/// assert_eq!(parser.buffered_len(), 1);
+/// # Ok::<(), protrust::io::read::Error>(())
+/// ```
+pub struct PartialParser<M: Message> {
+    buffer: Vec<u8>,
+    start: usize,
+    /// The offset of the start of the buffer in all the bytes fed to the parser
+    offset: u64,
+    max_message_len: usize,
+    message: PhantomData<fn() -> M>,
+}
+
+const DEFAULT_MAX_MESSAGE_LEN: usize = 64 << 20;
+
+impl<M: Message> PartialParser<M> {
+    /// Creates a new parser with no buffered bytes.
+    pub fn new() -> Self {
+        PartialParser { buffer: Vec::new(), start: 0, offset: 0, max_message_len: DEFAULT_MAX_MESSAGE_LEN, message: PhantomData }
+    }
+    /// Sets the maximum length of a message, not including its length prefix.
+    /// Longer messages are rejected with a `MessageTooLarge` error.
+    pub fn max_message_len(mut self, len: usize) -> Self {
+        self.max_message_len = len;
+        self
+    }
+    /// Returns the number of bytes buffered that haven't been parsed yet.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+    /// Appends the bytes to the buffer and tries to parse the next message.
+    /// 
+    /// # Errors
+    /// 
+    /// If the length prefix is malformed, negative, or longer than the maximum message length, this returns
+    /// an error and leaves the buffer unchanged, since the end of the message can't be found. If the message itself
+    /// fails to parse, its bytes are removed from the buffer before the error is returned, so parsing can continue
+    /// with the next message.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<ParseProgress<M>> {
+        // parsed messages are only removed once they make up most of the buffer,
+        // so each buffered byte is moved a constant number of times on average
+        if self.start > self.buffered_len() {
+            self.buffer.drain(..self.start);
+            self.offset += self.start as u64;
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+
+        let buffered = &self.buffer[self.start..];
+        let message_offset = self.offset + self.start as u64;
+        let prefix_len = match buffered.iter().take(10).position(|b| b & 0x80 == 0) {
+            Some(i) => i + 1,
+            None if buffered.len() >= 10 => return Err(Error::new(ErrorKind::MalformedVarint, message_offset + 10)),
+            None => return Ok(ParseProgress::NeedMore),
+        };
+        let body_offset = message_offset + prefix_len as u64;
+        let len = CodedReader::with_slice(&buffered[..prefix_len]).read_value::<raw::Int32>()?;
+        if len < 0 {
+            return Err(Error::new(ErrorKind::NegativeSize, body_offset));
+        }
+        let len = len as usize;
+        if len > self.max_message_len {
+            return Err(Error::new(ErrorKind::MessageTooLarge { len, max: self.max_message_len }, body_offset));
+        }
+
+        let end = prefix_len + len;
+        if buffered.len() < end {
+            return Ok(ParseProgress::NeedMore);
+        }
+
+        let result = M::parse_merged_from_slice(&buffered[prefix_len..end]);
+        self.start += end;
+        if self.start == self.buffer.len() {
+            self.buffer.clear();
+            self.offset += self.start as u64;
+            self.start = 0;
+        }
+        result
+            .map(ParseProgress::Complete)
+            .map_err(|e| e.offset_by(body_offset))
+    }
+}
+
+impl<M: Message> Default for PartialParser<M> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unreadable_literal)]
 mod test {
-    use crate::io::read::{Any, Input, Builder, CodedReader};
+    use crate::io::{FieldNumber, Tag};
+    use crate::UnknownFieldSet;
+    use crate::collections::unknown_fields::UnknownField;
+    use crate::Message;
+    use crate::io::Length;
+    use crate::io::read::{Any, Input, Builder, CodedReader, EnumStrictness, Error, ErrorKind, FieldFilter, FieldRemap, MessageStream, ParseProgress, PartialParser, SliceChain, Skip, UnknownFieldHandling};
+    use crate::doctest::timestamp::Timestamp;
+    use std::io::{self, Read};
+    use crate::raw;
     use std::borrow::BorrowMut;
+    use std::sync::Arc;
 
     pub trait ReaderInput<'a> {
         type Reader: Input + 'a;
@@ -1753,7 +3277,8 @@ mod test {
     mod actions {
         use std::fmt::Debug;
         use std::marker::PhantomData;
-        use crate::io::{Tag, ByteString, read::{self, Input, CodedReader, Error}};
+        use crate::io::{Tag, ByteString, read::{self, Input, CodedReader, Error, ErrorKind, FieldFilter}};
+        use crate::raw::{self, Value};
 
         pub trait Action<T: Input> {
             fn run(self, reader: &mut CodedReader<T>);
@@ -1823,7 +3348,35 @@ mod test {
         pub fn read_bit32<T: Input>(r: &mut CodedReader<T>) -> read::Result<u32> { r.read_bit32() }
         pub fn read_bit64<T: Input>(r: &mut CodedReader<T>) -> read::Result<u64> { r.read_bit64() }
         pub fn read_length_delimited<B: ByteString, T: Input>(r: &mut CodedReader<T>) -> read::Result<B> { r.read_length_delimited() }
+        pub fn read_value<V: Value, T: Input>(r: &mut CodedReader<T>) -> read::Result<V::Inner> where V::Inner: Sized { r.read_value::<V>() }
+        pub fn read_packed_into<V: raw::ConstSized, T: Input>(mut values: Vec<V::Inner>) -> impl FnOnce(&mut CodedReader<T>) -> read::Result<Vec<V::Inner>> {
+            move |r| r.read_packed_into::<V>(&mut values).map(|_| values)
+        }
+        pub fn read_bytes_into<T: Input>(buf: &mut Vec<u8>) -> impl FnOnce(&mut CodedReader<T>) -> read::Result<Vec<u8>> + '_ {
+            move |r| r.read_bytes_into(buf).map(|_| buf.clone())
+        }
+        pub fn read_string_into<T: Input>(buf: &mut String) -> impl FnOnce(&mut CodedReader<T>) -> read::Result<String> + '_ {
+            move |r| r.read_string_into(buf).map(|_| buf.clone())
+        }
         pub fn skip<T: Input>(r: &mut CodedReader<T>) -> read::Result<()> { r.skip() }
+        pub fn read_map_entry<T: Input>(r: &mut CodedReader<T>) -> read::Result<(i32, String)> { r.read_map_entry::<raw::Int32, raw::String>() }
+        pub fn read_field_filtered<T: Input>(filter: &FieldFilter) -> impl FnOnce(&mut CodedReader<T>) -> read::Result<Option<u32>> + '_ {
+            move |r| match r.read_field_filtered(filter)? {
+                Some(field) => {
+                    let tag = field.tag();
+                    field.check_and_then(|r| r.skip())?;
+                    Ok(Some(tag))
+                },
+                None => Ok(None),
+            }
+        }
+        pub fn position<T: Input>(position: u64) -> impl FnOnce(&mut CodedReader<T>) {
+            move |r| assert_eq!(r.position(), position)
+        }
+        pub fn skip_to_end<T: Input>(r: &mut CodedReader<T>) -> read::Result<u64> { r.skip_to_end() }
+        pub fn remaining_limit<T: Input>(remaining: Option<i32>) -> impl FnOnce(&mut CodedReader<T>) {
+            move |r| assert_eq!(r.bytes_remaining_in_limit(), remaining)
+        }
         pub fn read_limited<T: Input, R, F: FnOnce(&mut CodedReader<T>) -> read::Result<R>>(f: F) -> impl FnOnce(&mut CodedReader<T>) -> read::Result<R> {
             move |r| r.read_limit()?.then(f)
         }
@@ -1843,16 +3396,29 @@ mod test {
             move |v| assert!(matches!(&v, Ok(v) if v == &value), "expected `{:?}`, got `{:?}`", value, v)
         }
         pub fn io_error<T: Debug>(r: Result<T, Error>) {
-            assert!(matches!(r, Err(Error::IoError(_))), "expected `{:?}`, got `{:?}`", "Error::IoError(_)", r)
+            assert!(matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::IoError(_))), "expected `{:?}`, got `{:?}`", "ErrorKind::IoError(_)", r)
         }
         pub fn malformed_varint<T: Debug>(r: Result<T, Error>) {
-            assert!(matches!(r, Err(Error::MalformedVarint)), "expected `{:?}`, got `{:?}`", Err::<T, _>(Error::MalformedVarint), r)
+            assert!(matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::MalformedVarint)), "expected `{:?}`, got `{:?}`", Err::<T, _>(ErrorKind::MalformedVarint), r)
         }
         pub fn invalid_tag<T: Debug>(tag: u32) -> impl FnOnce(Result<T, Error>) {
-            move |r| assert!(matches!(r, Err(Error::InvalidTag(t)) if t == tag))
+            move |r| assert!(matches!(r.map_err(Error::into_kind), Err(ErrorKind::InvalidTag(t)) if t == tag))
+        }
+        pub fn invalid_string<T: Debug>(r: Result<T, Error>) {
+            assert!(matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::InvalidString(_))), "expected `{:?}`, got `{:?}`", "ErrorKind::InvalidString(_)", r)
         }
         pub fn negative_size<T: Debug>(r: Result<T, Error>) {
-            assert!(matches!(r, Err(Error::NegativeSize)), "expected `{:?}`, got `{:?}`", Err::<T, _>(Error::NegativeSize), r)
+            assert!(matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::NegativeSize)), "expected `{:?}`, got `{:?}`", Err::<T, _>(ErrorKind::NegativeSize), r)
+        }
+        pub fn unexpected_field<T: Debug>(number: u32) -> impl FnOnce(Result<T, Error>) {
+            move |r| assert!(
+                matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::UnexpectedField { number: n }) if n.get() == number),
+                "expected `UnexpectedField {{ number: {} }}`, got `{:?}`", number, r)
+        }
+        pub fn group_mismatch<T: Debug>(start: u32, end: u32) -> impl FnOnce(Result<T, Error>) {
+            move |r| assert!(
+                matches!(r.as_ref().map_err(Error::kind), Err(ErrorKind::GroupMismatch { start: s, end: e }) if s.get() == start && e.get() == end),
+                "expected `GroupMismatch {{ start: {}, end: {} }}`, got `{:?}`", start, end, r)
         }
     }
 
@@ -1860,6 +3426,524 @@ mod test {
 
     use a::ReadAction;
 
+    fn field_filter(ranges: &[(u32, u32)]) -> FieldFilter {
+        let mut filter = FieldFilter::new();
+        for &(start, end) in ranges {
+            filter.insert_range(FieldNumber::new(start).unwrap(), FieldNumber::new(end).unwrap());
+        }
+        filter
+    }
+
+    #[test]
+    fn field_remap_renumbers_fields() {
+        let mut remap = FieldRemap::new();
+        remap.insert(FieldNumber::new(1).unwrap(), FieldNumber::new(2).unwrap());
+
+        // seconds = 5, nanos = 7
+        let mut reader = Builder::new().field_remap(Some(Arc::new(remap))).with_slice(&[8, 5, 16, 7]);
+        let mut timestamp = Timestamp::new();
+        timestamp.merge_from(&mut reader).unwrap();
+
+        assert_eq!(timestamp.seconds(), &0);
+        assert_eq!(timestamp.nanos(), &7);
+        assert!(timestamp.unknown_fields().is_empty());
+    }
+
+    #[test]
+    fn field_remap_renumbers_groups() {
+        let mut remap = FieldRemap::new();
+        remap.insert(FieldNumber::new(3).unwrap(), FieldNumber::new(4).unwrap());
+
+        // a group in field 3 containing field 1 = 1, then field 2 = 2
+        let mut reader = CodedReader::with_slice(&[27, 8, 1, 28, 16, 2]);
+        reader.set_field_remap(Some(Arc::new(remap)));
+
+        assert_eq!(reader.read_tag().unwrap().map(Tag::get), Some(35));
+        reader.skip().unwrap();
+        assert_eq!(reader.read_tag().unwrap().map(Tag::get), Some(16));
+        assert_eq!(reader.read_varint32().unwrap(), 2);
+        assert_eq!(reader.read_tag().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_varint_error_offset() {
+        fn read<T: Input>(reader: &mut CodedReader<T>) -> Error {
+            reader.read_tag().unwrap();
+            reader.read_varint32().unwrap();
+            reader.read_tag().unwrap();
+            reader.read_varint32().unwrap_err()
+        }
+        let data = [8, 150, 1, 16, 128, 128];
+
+        // slices check the whole varint is there before reading it, streams read it until the input ends
+        let err = read(&mut CodedReader::with_slice(&data));
+        assert!(matches!(err.kind(), ErrorKind::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert_eq!(err.offset(), 4);
+        assert_eq!(err.to_string(), "an error occured in the underlying input: unexpected end of file at offset 4");
+
+        let err = read(&mut CodedReader::with_stream(&data[..]));
+        assert!(matches!(err.kind(), ErrorKind::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert_eq!(err.offset(), 6);
+    }
+
+    #[test]
+    fn invalid_tag_error_offset() {
+        // seconds = 5, followed by a tag for field 0
+        let data = [8, 5, 0];
+
+        let mut reader = CodedReader::with_slice(&data);
+        reader.read_tag().unwrap();
+        reader.read_varint32().unwrap();
+        let err = reader.read_tag().unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidTag(0)));
+        assert_eq!(err.offset(), 3);
+
+        let err = Timestamp::parse_merged_from_slice(&data).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidTag(0)));
+        assert_eq!(err.offset(), 3);
+
+        let err = Timestamp::new().merge_from(&mut CodedReader::with_stream(&data[..])).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidTag(0)));
+        assert_eq!(err.offset(), 3);
+    }
+
+    #[test]
+    fn into_io_error() {
+        let err = std::io::Error::from(Error::new(ErrorKind::MalformedVarint, 0));
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(err.into_inner().unwrap().downcast::<Error>().map(|e| e.into_kind()), Ok(ErrorKind::MalformedVarint)));
+
+        let eof = CodedReader::with_slice(&[128]).read_varint32().unwrap_err();
+        assert_eq!(std::io::Error::from(eof).kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn slice_chain_reads_across_segments() {
+        let segments: &[&[u8]] = &[&[], &[8, 5, 18, 3, 102], &[], &[111], &[111, 24], &[7]];
+        let mut reader = CodedReader::with_slices(segments);
+        let mut unknown_fields = UnknownFieldSet::new();
+        while let Some(field) = reader.read_field().unwrap() {
+            field.check_and_try_add_field_to(&mut unknown_fields).unwrap().or_skip().unwrap();
+        }
+
+        let field = |n| unknown_fields.values(FieldNumber::new(n).unwrap());
+        assert_eq!(field(1), &[UnknownField::Varint(5)]);
+        assert_eq!(field(2), &[UnknownField::LengthDelimited(b"foo".to_vec().into_boxed_slice())]);
+        assert_eq!(field(3), &[UnknownField::Varint(7)]);
+    }
+
+    #[test]
+    fn slice_chain_skips_across_segments() {
+        let segments: &[&[u8]] = &[&[1, 2, 3], &[4], &[5, 6]];
+        let mut chain = SliceChain::new(segments);
+        chain.skip_exact(Length::new(4).unwrap()).unwrap();
+
+        let mut rest = Vec::new();
+        chain.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [5, 6]);
+
+        let mut chain = SliceChain::new(segments);
+        assert_eq!(chain.skip_exact(Length::new(7).unwrap()).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn slice_chain_reads_message() {
+        let segments: &[&[u8]] = &[&[8], &[5, 16], &[100]];
+        let mut timestamp = Timestamp::new();
+        timestamp.merge_from(&mut CodedReader::with_slices(segments)).unwrap();
+
+        assert_eq!((timestamp.seconds(), timestamp.nanos()), (&5, &100));
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Closed(i32);
+
+    impl From<i32> for Closed {
+        fn from(i: i32) -> Self {
+            Closed(i)
+        }
+    }
+
+    impl From<Closed> for i32 {
+        fn from(c: Closed) -> i32 {
+            c.0
+        }
+    }
+
+    impl crate::Enum for Closed {
+        fn is_known(self) -> bool {
+            self.0 == 0 || self.0 == 1
+        }
+    }
+
+    fn merge_enums(data: &[u8], builder: Builder, strictness: EnumStrictness) -> (Closed, UnknownFieldSet) {
+        let field = FieldNumber::new(1).unwrap();
+        let mut reader = builder.with_slice(data);
+        let mut value = Closed::default();
+        let mut unknown_fields = UnknownFieldSet::new();
+        while let Some(f) = reader.read_field().unwrap() {
+            f.merge_enum(field, &mut value, strictness, &mut unknown_fields).unwrap();
+        }
+        (value, unknown_fields)
+    }
+
+    #[test]
+    fn merge_open_enum() {
+        let (value, unknown_fields) = merge_enums(&[8, 1, 8, 5], Builder::new(), EnumStrictness::Open);
+
+        assert_eq!(value, Closed(5));
+        assert!(unknown_fields.is_empty());
+    }
+
+    #[test]
+    fn merge_closed_enum() {
+        let (value, unknown_fields) = merge_enums(&[8, 1, 8, 5, 8, 255, 255, 255, 255, 255, 255, 255, 255, 255, 1], Builder::new(), EnumStrictness::Closed);
+
+        assert_eq!(value, Closed(1));
+        assert_eq!(
+            unknown_fields.values(FieldNumber::new(1).unwrap()),
+            &[UnknownField::Varint(5), UnknownField::Varint(u64::max_value())]);
+    }
+
+    #[test]
+    fn merge_closed_enum_skips_unknown() {
+        let builder = Builder::new().unknown_fields(UnknownFieldHandling::Skip);
+        let (value, unknown_fields) = merge_enums(&[8, 5], builder, EnumStrictness::Closed);
+
+        assert_eq!(value, Closed(0));
+        assert!(unknown_fields.is_empty());
+    }
+
+    #[test]
+    fn restore_checkpoint() {
+        let data = [8, 1, 16, 2];
+        let mut reader = CodedReader::with_slice(&data);
+
+        let start = reader.checkpoint();
+        assert_eq!(reader.read_tag().unwrap().map(|t| t.get()), Some(8));
+        let after_tag = reader.checkpoint();
+        assert_eq!(reader.read_varint32().unwrap(), 1);
+        assert_eq!(reader.read_tag().unwrap().map(|t| t.get()), Some(16));
+
+        reader.restore(after_tag).unwrap();
+        assert_eq!((reader.position(), reader.last_tag().map(|t| t.get())), (1, Some(8)));
+
+        reader.restore(start).unwrap();
+        assert_eq!((reader.position(), reader.last_tag()), (0, None));
+
+        reader.restore(after_tag).unwrap();
+        assert_eq!(reader.read_varint32().unwrap(), 1);
+    }
+
+    #[test]
+    fn restore_checkpoint_outside_limit() {
+        let data = [2, 8, 1, 16, 2];
+        let mut reader = CodedReader::with_slice(&data);
+        let end = {
+            let mut skipped = CodedReader::with_slice(&data);
+            skipped.skip_to_end().unwrap();
+            skipped.checkpoint()
+        };
+
+        let limit = reader.read_limit().unwrap();
+        limit.then(|reader| {
+            assert!(matches!(reader.restore(end).map_err(Error::into_kind), Err(ErrorKind::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+            assert_eq!(reader.position(), 1);
+            reader.skip_to_end().map(|_| ())
+        }).unwrap();
+
+        reader.restore(end).unwrap();
+        assert!(reader.read_tag().unwrap().is_none());
+    }
+
+    #[test]
+    fn nested_length_exceeds_limit() {
+        fn read_nested<T: Input>(reader: &mut CodedReader<T>) -> crate::io::read::Result<()> {
+            assert_eq!(reader.read_tag()?.map(|t| t.get()), Some(10));
+            reader.read_limit()?.then(|reader| {
+                assert_eq!(reader.read_tag()?.map(|t| t.get()), Some(18));
+                reader.read_limit()?.then(|reader| reader.skip_to_end().map(|_| ()))
+            })
+        }
+
+        // a 10 byte message containing a field that claims to be 1000 bytes long,
+        // followed by enough sibling data to satisfy the inner length if it wasn't checked
+        let mut data = vec![10, 10, 18, 232, 7, 1, 2, 3, 4, 5, 6, 7];
+        data.resize(1100, 8);
+
+        assert!(matches!(read_nested(&mut CodedReader::with_slice(&data)).map_err(Error::into_kind), Err(ErrorKind::LengthExceedsLimit)));
+        assert!(matches!(read_nested(&mut CodedReader::with_slice(&data).as_any()).map_err(Error::into_kind), Err(ErrorKind::LengthExceedsLimit)));
+        assert!(matches!(read_nested(&mut CodedReader::with_stream(&data[..])).map_err(Error::into_kind), Err(ErrorKind::LengthExceedsLimit)));
+        assert!(matches!(read_nested(&mut CodedReader::with_capacity(4, &data[..]).as_any()).map_err(Error::into_kind), Err(ErrorKind::LengthExceedsLimit)));
+
+        let mut reader = CodedReader::with_slice(&data[..2]);
+        assert!(matches!(reader.limit_scope(3).map(|_| ()).map_err(Error::into_kind), Err(ErrorKind::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+    }
+
+    #[test]
+    fn read_all_into_unknown_round_trips() {
+        // a FileDescriptorProto payload with its fields out of field number order:
+        // name = "a.proto", message_type = [{ name = "A" }], package = "p", message_type = [{ name = "B" }]
+        let data = [
+            10, 7, 97, 46, 112, 114, 111, 116, 111,
+            34, 3, 10, 1, 65,
+            18, 1, 112,
+            34, 3, 10, 1, 66,
+        ];
+        let mut reader = Builder::new().unknown_fields(UnknownFieldHandling::Skip).with_slice(&data);
+        let mut output = Vec::new();
+        reader.read_all_into_unknown(&mut output).unwrap();
+        assert_eq!(output, data);
+
+        let mut output = Vec::new();
+        CodedReader::with_capacity(4, &data[..]).read_all_into_unknown(&mut output).unwrap();
+        assert_eq!(output, data);
+
+        // fixed values and nested groups keep their order too
+        let data = [13, 1, 0, 0, 0, 19, 27, 33, 2, 0, 0, 0, 0, 0, 0, 0, 28, 24, 5, 20, 8, 1];
+        let mut output = vec![7];
+        CodedReader::with_slice(&data).read_all_into_unknown(&mut output).unwrap();
+        assert_eq!(output[1..], data);
+    }
+
+    #[test]
+    fn read_all_into_unknown_respects_limits() {
+        let mut reader = Builder::new().recursion_limit(1).with_slice(&[11, 19, 20, 12]);
+        assert!(matches!(reader.read_all_into_unknown(&mut Vec::new()).map_err(Error::into_kind), Err(ErrorKind::RecursionLimitExceeded)));
+
+        let mut reader = CodedReader::with_slice(&[10, 5, 1]);
+        assert!(matches!(reader.read_all_into_unknown(&mut Vec::new()).map_err(Error::into_kind), Err(ErrorKind::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+
+        let mut reader = CodedReader::with_stream(&[10, 0xff, 0xff, 0xff, 0xff, 0x07, 1][..]);
+        let mut output = vec![1, 2];
+        assert!(matches!(reader.read_all_into_unknown(&mut output).map_err(Error::into_kind), Err(ErrorKind::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert_eq!(output, [1, 2]);
+
+        let mut reader = CodedReader::with_slice(&[12]);
+        assert!(reader.read_all_into_unknown(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn unknown_fields_error() {
+        let mut reader = Builder::new().unknown_fields(UnknownFieldHandling::Error).with_slice(&[8, 5, 24, 1]);
+        let mut timestamp = Timestamp::new();
+        let err = timestamp.merge_from(&mut reader).unwrap_err();
+
+        assert!(matches!(err.kind(), ErrorKind::UnknownField { number } if number.get() == 3));
+        assert_eq!(timestamp.seconds(), &5);
+    }
+
+    #[test]
+    fn set_unknown_field_handling() {
+        let mut reader = CodedReader::with_slice(&[24, 1]);
+        reader.set_unknown_field_handling(UnknownFieldHandling::Skip);
+        assert_eq!(reader.unknown_field_handling(), UnknownFieldHandling::Skip);
+
+        let mut timestamp = Timestamp::new();
+        timestamp.merge_from(&mut reader).unwrap();
+        assert!(timestamp.unknown_fields().is_empty());
+    }
+
+    fn message_stream(data: &'static [u8]) -> MessageStream<&'static [u8], Timestamp> {
+        MessageStream::with_reader(Builder::new().with_capacity(4, data))
+    }
+
+    #[test]
+    fn message_stream_reads_all_messages() {
+        let mut stream = message_stream(&[4, 8, 5, 16, 100, 0, 2, 8, 10]);
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!((first.seconds(), first.nanos()), (&5, &100));
+        assert_eq!(stream.next().unwrap().unwrap(), Timestamp::new());
+        assert_eq!(stream.next().unwrap().unwrap().seconds(), &10);
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn message_stream_empty() {
+        assert!(message_stream(&[]).next().is_none());
+    }
+
+    #[test]
+    fn message_stream_truncated_record() {
+        // the last record's length is 4 but only a single field follows
+        let mut stream = message_stream(&[2, 8, 5, 4, 16, 100]);
+
+        assert!(stream.next().unwrap().is_ok());
+        assert!(matches!(stream.next().map(|r| r.map_err(Error::into_kind)), Some(Err(ErrorKind::IoError(ref e))) if e.kind() == io::ErrorKind::UnexpectedEof));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn message_stream_truncated_value() {
+        let mut stream = message_stream(&[2, 8, 5, 3, 8, 128]);
+
+        assert!(stream.next().unwrap().is_ok());
+        assert!(matches!(stream.next().map(|r| r.map_err(Error::into_kind)), Some(Err(ErrorKind::IoError(_)))));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn message_stream_truncated_length() {
+        let mut stream = message_stream(&[128]);
+
+        assert!(matches!(stream.next().map(|r| r.map_err(Error::into_kind)), Some(Err(ErrorKind::IoError(_)))));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn decode_all_reads_until_end() {
+        let messages: Vec<Timestamp> = super::decode_all(&[4, 8, 5, 16, 100, 0, 2, 8, 10][..]).unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!((messages[0].seconds(), messages[0].nanos()), (&5, &100));
+        assert_eq!(messages[1], Timestamp::new());
+        assert_eq!(messages[2].seconds(), &10);
+
+        assert!(super::decode_all::<Timestamp, _>(&[][..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_all_truncated() {
+        let result = super::decode_all::<Timestamp, _>(&[2, 8, 5, 4, 16, 100][..]);
+
+        assert!(matches!(result.map_err(Error::into_kind), Err(ErrorKind::IoError(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof));
+    }
+
+    fn complete<M>(progress: Result<ParseProgress<M>, Error>) -> M {
+        match progress {
+            Ok(ParseProgress::Complete(m)) => m,
+            _ => panic!("expected a complete message"),
+        }
+    }
+
+    #[test]
+    fn partial_parser_resumes_across_chunks() {
+        let mut parser = PartialParser::<Timestamp>::new();
+
+        // a length prefix split over two chunks: 130 bytes of repeated `seconds` fields
+        let mut data = vec![0x82, 0x01];
+        for _ in 0..65 {
+            data.extend_from_slice(&[8, 7]);
+        }
+
+        assert_eq!(parser.feed(&data[..1]).unwrap(), ParseProgress::NeedMore);
+        assert_eq!(parser.feed(&data[1..50]).unwrap(), ParseProgress::NeedMore);
+        assert_eq!(complete(parser.feed(&data[50..])).seconds(), &7);
+        assert_eq!(parser.buffered_len(), 0);
+    }
+
+    #[test]
+    fn partial_parser_buffers_following_messages() {
+        let mut parser = PartialParser::<Timestamp>::new();
+
+        assert_eq!(complete(parser.feed(&[2, 8, 5, 0, 2, 16])).seconds(), &5);
+        assert_eq!(complete(parser.feed(&[])), Timestamp::new());
+        assert_eq!(parser.feed(&[]).unwrap(), ParseProgress::NeedMore);
+        assert_eq!(complete(parser.feed(&[10])).nanos(), &10);
+        assert_eq!(parser.feed(&[]).unwrap(), ParseProgress::NeedMore);
+    }
+
+    #[test]
+    fn partial_parser_skips_invalid_messages() {
+        let mut parser = PartialParser::<Timestamp>::new();
+
+        // a message with a truncated varint, followed by a valid message
+        assert!(matches!(parser.feed(&[2, 8, 128, 2, 8, 1]).map_err(Error::into_kind), Err(ErrorKind::IoError(_))));
+        assert_eq!(complete(parser.feed(&[])).seconds(), &1);
+    }
+
+    #[test]
+    fn partial_parser_malformed_prefix() {
+        let mut parser = PartialParser::<Timestamp>::new();
+
+        assert_eq!(parser.feed(&[0xFF; 9]).unwrap(), ParseProgress::NeedMore);
+        assert!(matches!(parser.feed(&[0xFF]).map_err(Error::into_kind), Err(ErrorKind::MalformedVarint)));
+        assert!(matches!(PartialParser::<Timestamp>::new().feed(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]).map_err(Error::into_kind), Err(ErrorKind::NegativeSize)));
+    }
+
+    #[test]
+    fn partial_parser_max_message_len() {
+        let mut parser = PartialParser::<Timestamp>::new().max_message_len(2);
+
+        assert_eq!(complete(parser.feed(&[2, 8, 5])).seconds(), &5);
+        assert!(matches!(parser.feed(&[4, 8, 5, 16, 1]).map_err(Error::into_kind), Err(ErrorKind::MessageTooLarge { len: 4, max: 2 })));
+        assert_eq!(parser.buffered_len(), 5);
+    }
+
+    #[test]
+    fn partial_parser_compacts_parsed_messages() {
+        let mut parser = PartialParser::<Timestamp>::new();
+        let data: Vec<u8> = (1..=4).flat_map(|i| vec![2, 8, i]).collect();
+
+        assert_eq!(complete(parser.feed(&data)).seconds(), &1);
+        assert_eq!(parser.buffered_len(), 9);
+        for i in 2..=4u8 {
+            assert_eq!(complete(parser.feed(&[])).seconds(), &i64::from(i));
+            assert_eq!(parser.buffered_len(), 3 * usize::from(4 - i));
+        }
+        assert!(parser.buffer.is_empty());
+        assert_eq!(complete(parser.feed(&[2, 8, 5])).seconds(), &5);
+    }
+
+    #[test]
+    fn slice_values_outlive_input() {
+        let input = vec![3, 1, 2, 3, 2, 8, 5];
+        let (bytes, timestamp) = {
+            let mut reader = CodedReader::with_slice(&input);
+            let bytes = reader.read_value::<raw::Bytes<Vec<u8>>>().unwrap();
+            let timestamp = reader.read_value::<raw::Message<Timestamp>>().unwrap();
+            assert!(reader.into_inner().is_empty());
+            (bytes, timestamp)
+        };
+        drop(input);
+
+        assert_eq!(bytes, [1, 2, 3]);
+        assert_eq!(timestamp.seconds(), &5);
+    }
+
+    #[test]
+    fn limit_scope_restores_limit() {
+        fn read_all<T: Input>(reader: &mut CodedReader<T>, len: i32) -> Result<Vec<u32>, Error> {
+            let mut limited = reader.limit_scope(len)?;
+            let mut values = Vec::new();
+            while limited.bytes_remaining_in_limit() != Some(0) {
+                values.push(limited.read_varint32()?);
+            }
+            Ok(values)
+        }
+
+        let input = [1, 2, 3, 4, 128, 5];
+        let mut reader = CodedReader::with_slice(&input);
+        assert!(matches!(reader.limit_scope(7).map_err(Error::into_kind), Err(ErrorKind::IoError(_))));
+        assert!(matches!(reader.limit_scope(-1).map_err(Error::into_kind), Err(ErrorKind::NegativeSize)));
+
+        {
+            let mut outer = reader.limit_scope(4).unwrap();
+            assert_eq!(read_all(&mut outer, 2).unwrap(), [1, 2]);
+            assert_eq!(outer.bytes_remaining_in_limit(), Some(2));
+            assert!(outer.limit_scope(3).is_err());
+            assert_eq!(read_all(&mut outer, 2).unwrap(), [3, 4]);
+        }
+        assert_eq!(reader.bytes_remaining_in_limit(), None);
+
+        // the varint is cut off by the limit, and the limit is popped by the early return
+        assert!(read_all(&mut reader, 1).is_err());
+        assert_eq!(reader.bytes_remaining_in_limit(), None);
+    }
+
+    #[test]
+    fn field_filter_merges_ranges() {
+        let filter = field_filter(&[(10, 12), (1, 2), (4, 5), (3, 3), (13, 20), (7, 8)]);
+        assert_eq!(filter.ranges, [(1, 5), (7, 8), (10, 20)]);
+
+        let filter = field_filter(&[(1, 2), (5, 6), (9, 10), (2, 9)]);
+        assert_eq!(filter.ranges, [(1, 10)]);
+
+        assert!(!filter.contains(FieldNumber::new(11).unwrap()));
+        assert!(filter.contains(FieldNumber::new(10).unwrap()));
+    }
+
     macro_rules! test {
         ($(($ti:ident | $tia:ident $(| init: || $init:expr)?) = $x:expr => |$f:ident| $t:block),+) => {
             $(
@@ -1917,6 +4001,15 @@ mod test {
         (read_malformed_tag | read_malformed_tag_any) = [128u8; 10] => |r| {
             r.then(a::try_read_tag.with(a::malformed_varint));
         },
+        (read_tag_wire_type_6 | read_tag_wire_type_6_any) = [14] => |r| {
+            r.then(a::try_read_tag.with(a::invalid_tag(14)));
+        },
+        (read_tag_wire_type_7 | read_tag_wire_type_7_any) = [15] => |r| {
+            r.then(a::try_read_tag.with(a::invalid_tag(15)));
+        },
+        (read_tag_field_0 | read_tag_field_0_any) = [2] => |r| {
+            r.then(a::try_read_tag.with(a::invalid_tag(2)));
+        },
         (read_truncated_varint32_empty | read_truncated_varint32_empty_any) = [] => |r| {
             r.then(a::read_varint32.with(a::io_error));
         },
@@ -1929,6 +4022,9 @@ mod test {
         (read_malformed_varint32 | read_malformed_varint32_any) = [128u8; 10] => |r| {
             r.then(a::read_varint32.with(a::malformed_varint));
         },
+        (read_overlong_varint32 | read_overlong_varint32_any) = [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 1] => |r| {
+            r.then(a::read_varint32.with(a::malformed_varint));
+        },
         (read_varint32 | read_varint32_any) = [1] => |r| {
             r.then(a::read_varint32.with(a::value(1)))
              .then(a::read_tag::none());
@@ -1941,6 +4037,23 @@ mod test {
             r.then(a::read_varint32.with(a::value(0))) // discard all top bits
              .then(a::read_tag::none());
         },
+        (read_negative_int32 | read_negative_int32_any) = [8, 255, 255, 255, 255, 255, 255, 255, 255, 255, 1, 16, 1] => |r| {
+            r.then(a::read_tag::value(8))
+             .then(a::read_value::<raw::Int32, _>.with(a::value(-1)))
+             .then(a::read_tag::value(16))
+             .then(a::read_value::<raw::Int32, _>.with(a::value(1)))
+             .then(a::read_tag::none());
+        },
+        (read_min_int32 | read_min_int32_any) = [8, 128, 128, 128, 128, 248, 255, 255, 255, 255, 1, 16, 1] => |r| {
+            r.then(a::read_tag::value(8))
+             .then(a::read_value::<raw::Int32, _>.with(a::value(i32::min_value())))
+             .then(a::read_tag::value(16))
+             .then(a::read_value::<raw::Int32, _>.with(a::value(1)))
+             .then(a::read_tag::none());
+        },
+        (read_truncated_negative_int32 | read_truncated_negative_int32_any) = [255, 255, 255, 255, 255, 255, 255, 255, 255] => |r| {
+            r.then(a::read_value::<raw::Int32, _>.with(a::io_error));
+        },
         (read_truncated_varint64_empty | read_truncated_varint64_empty_any) = [] => |r| {
             r.then(a::read_varint64.with(a::io_error));
         },
@@ -1950,6 +4063,9 @@ mod test {
         (read_malformed_varint64 | read_malformed_varint64_any) = [128u8; 10] => |r| {
             r.then(a::read_varint64.with(a::malformed_varint));
         },
+        (read_overlong_varint64 | read_overlong_varint64_any) = [128, 128, 128, 128, 128, 128, 128, 128, 128, 128, 1] => |r| {
+            r.then(a::read_varint64.with(a::malformed_varint));
+        },
         (read_varint64 | read_varint64_any) = [1] => |r| {
             r.then(a::read_varint64.with(a::value(1)))
              .then(a::read_tag::none());
@@ -2056,7 +4172,226 @@ mod test {
         // throw an end tag for field 2 in the middle of the field 1 group
         (skip_group_other_field_end | skip_group_other_field_end_any) = [11, 20, 12] => |r| {
             r.then(a::read_tag::value(11))
-             .then(a::skip.with(a::invalid_tag(20)));
+             .then(a::skip.with(a::group_mismatch(1, 2)));
+        },
+        (skip_nested_group | skip_nested_group_any) = [11, 19, 8, 1, 20, 16, 2, 12] => |r| {
+            r.then(a::read_tag::value(11))
+             .then(a::skip.with(a::value(())))
+             .then(a::read_tag::none());
+        },
+        // start groups for field 1 and 2, then end them in the wrong order
+        (skip_crossed_group | skip_crossed_group_any) = [11, 19, 12, 20] => |r| {
+            r.then(a::read_tag::value(11))
+             .then(a::skip.with(a::group_mismatch(2, 1)));
+        },
+        (skip_unterminated_group | skip_unterminated_group_any) = [11, 16, 0] => |r| {
+            r.then(a::read_tag::value(11))
+             .then(a::skip.with(a::io_error));
+        },
+        (skip_unterminated_nested_group | skip_unterminated_nested_group_any) = [11, 19, 12] => |r| {
+            r.then(a::read_tag::value(11))
+             .then(a::skip.with(a::group_mismatch(2, 1)));
+        },
+        // the group's end tag is outside the length delimited value containing the group
+        (skip_group_unterminated_in_limit | skip_group_unterminated_in_limit_any) = [10, 3, 11, 16, 0, 12] => |r| {
+            r.then(a::read_tag::value(10))
+             .then(a::read_limited(|r| {
+                r.then(a::read_tag::value(11));
+                r.skip()
+              }).with(a::io_error));
+        },
+        (skip_end_group | skip_end_group_any) = [12] => |r| {
+            r.then(a::read_tag::value(12))
+             .then(a::skip.with(a::invalid_tag(12)));
+        },
+        (read_bytes_into_reused | read_bytes_into_reused_any) = [3, 1, 2, 3, 0, 12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12] => |r| {
+            let mut buf = vec![9; 5];
+            r.then(a::read_bytes_into(&mut buf).with(a::value(vec![1, 2, 3])))
+             .then(a::read_bytes_into(&mut buf).with(a::value(vec![])))
+             .then(a::read_bytes_into(&mut buf).with(a::value(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])))
+             .then(a::read_tag::none());
+        },
+        (read_bytes_into_truncated | read_bytes_into_truncated_any) = [3, 1, 2] => |r| {
+            let mut buf = vec![9; 5];
+            r.then(a::read_bytes_into(&mut buf).with(a::io_error));
+            assert!(buf.is_empty());
+        },
+        (read_bytes_into_negative | read_bytes_into_negative_any) = [255, 255, 255, 255, 15] => |r| {
+            let mut buf = vec![9; 5];
+            r.then(a::read_bytes_into(&mut buf).with(a::negative_size));
+            assert!(buf.is_empty());
+        },
+        (read_string_into_reused | read_string_into_reused_any) = [3, 102, 111, 111, 2, 104, 105] => |r| {
+            let mut buf = String::from("garbage");
+            let ptr = buf.as_ptr();
+            r.then(a::read_string_into(&mut buf).with(a::value("foo".to_string())))
+             .then(a::read_string_into(&mut buf).with(a::value("hi".to_string())))
+             .then(a::read_tag::none());
+            assert_eq!(buf.as_ptr(), ptr);
+        },
+        (read_string_into_invalid | read_string_into_invalid_any) = [2, 0xC3, 0x28] => |r| {
+            let mut buf = String::from("garbage");
+            r.then(a::read_string_into(&mut buf).with(a::invalid_string));
+            assert!(buf.is_empty());
+        },
+        (read_string_into_truncated | read_string_into_truncated_any) = [3, 102, 111] => |r| {
+            let mut buf = String::from("garbage");
+            r.then(a::read_string_into(&mut buf).with(a::io_error));
+            assert!(buf.is_empty());
+        },
+        (read_map_entry | read_map_entry_any) = [6, 8, 1, 18, 2, 104, 105, 6, 18, 2, 104, 105, 8, 1] => |r| {
+            r.then(a::read_map_entry.with(a::value((1, "hi".to_string()))))
+             .then(a::read_map_entry.with(a::value((1, "hi".to_string()))))
+             .then(a::read_tag::none());
+        },
+        (read_map_entry_missing_fields | read_map_entry_missing_fields_any) = [4, 18, 2, 104, 105, 2, 8, 1, 0] => |r| {
+            r.then(a::read_map_entry.with(a::value((0, "hi".to_string()))))
+             .then(a::read_map_entry.with(a::value((1, String::new()))))
+             .then(a::read_map_entry.with(a::value((0, String::new()))))
+             .then(a::read_tag::none());
+        },
+        (read_map_entry_repeated_and_unknown_fields | read_map_entry_repeated_and_unknown_fields_any) = [8, 8, 1, 24, 5, 8, 2, 35, 36, 16, 3] => |r| {
+            r.then(a::read_map_entry.with(a::value((2, String::new()))))
+             .then(a::read_tag::value(16));
+        },
+        (read_map_entry_truncated | read_map_entry_truncated_any) = [6, 8, 1, 18, 5, 104] => |r| {
+            r.then(a::read_map_entry.with(a::io_error));
+        },
+        (read_packed_fixed32 | read_packed_fixed32_any) = [8, 1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 4, 2, 0, 0, 0] => |r| {
+            r.then(a::read_packed_into::<raw::Fixed32, _>(vec![]).with(a::value(vec![1, u32::max_value()])))
+             .then(a::read_packed_into::<raw::Fixed32, _>(vec![7]).with(a::value(vec![7, 2])))
+             .then(a::read_tag::none());
+        },
+        (read_packed_sfixed64_empty | read_packed_sfixed64_empty_any) = [0, 8, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF] => |r| {
+            r.then(a::read_packed_into::<raw::Sfixed64, _>(vec![]).with(a::value(vec![])))
+             .then(a::read_packed_into::<raw::Sfixed64, _>(vec![]).with(a::value(vec![-2])));
+        },
+        (read_packed_negative_size | read_packed_negative_size_any) = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F] => |r| {
+            r.then(a::read_packed_into::<raw::Fixed32, _>(vec![]).with(a::negative_size));
+        },
+        (read_packed_truncated | read_packed_truncated_any) = [8, 1, 0, 0, 0, 2] => |r| {
+            r.then(a::read_packed_into::<raw::Fixed32, _>(vec![]).with(a::io_error));
+        },
+        (read_string_multibyte | read_string_multibyte_any) = [7, 0x61, 0xF0, 0x9F, 0x98, 0x80, 0xC3, 0xA9] => |r| {
+            r.then(a::read_value::<raw::String, _>.with(a::value("a\u{1F600}\u{E9}".to_string())))
+             .then(a::read_tag::none());
+        },
+        (read_string_invalid | read_string_invalid_any) = [6, 0x61, 0x62, 0x63, 0xF0, 0x9F, 0x98] => |r| {
+            r.then(a::read_value::<raw::String, _>.with(a::invalid_string));
+        },
+        (read_string_invalid_after_valid | read_string_invalid_after_valid_any) = [1, 0x61, 2, 0xC3, 0x28] => |r| {
+            r.then(a::read_value::<raw::String, _>.with(a::value("a".to_string())))
+             .then(a::read_value::<raw::String, _>.with(a::invalid_string));
+        },
+        (read_field_filtered_allowed | read_field_filtered_allowed_any) = [8, 1, 18, 1, 2, 29, 1, 2, 3, 4] => |r| {
+            let filter = field_filter(&[(1, 1), (2, 3)]);
+            r.then(a::read_field_filtered(&filter).with(a::value(Some(8))))
+             .then(a::read_field_filtered(&filter).with(a::value(Some(18))))
+             .then(a::read_field_filtered(&filter).with(a::value(Some(29))))
+             .then(a::read_field_filtered(&filter).with(a::value(None)));
+        },
+        (read_field_filtered_rejected | read_field_filtered_rejected_any) = [8, 1, 32, 1] => |r| {
+            let filter = field_filter(&[(1, 3)]);
+            r.then(a::read_field_filtered(&filter).with(a::value(Some(8))))
+             .then(a::read_field_filtered(&filter).with(a::unexpected_field(4)));
+        },
+        (read_field_filtered_invalid | read_field_filtered_invalid_any) = [2] => |r| {
+            let filter = field_filter(&[(1, FieldNumber::MAX_VALUE)]);
+            r.then(a::read_field_filtered(&filter).with(a::invalid_tag(2)));
+        },
+        (position_after_values | position_after_values_any) =
+            [8, 150, 1, 21, 1, 2, 3, 4, 25, 1, 2, 3, 4, 5, 6, 7, 8, 34, 12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 40] => |r| {
+            r.then(a::position(0))
+             .then(a::read_tag::value(8))
+             .then(a::position(1))
+             .then(a::read_varint32.with(a::value(150)))
+             .then(a::position(3))
+             .then(a::read_tag::value(21))
+             .then(a::read_bit32.with(a::value(0x0403_0201)))
+             .then(a::position(8))
+             .then(a::read_tag::value(25))
+             .then(a::read_bit64.with(a::value(0x0807_0605_0403_0201)))
+             .then(a::position(17))
+             .then(a::read_tag::value(34))
+             .then(a::read_length_delimited.with(a::value(vec![1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])))
+             .then(a::position(31))
+             .then(a::read_tag::value(40))
+             .then(a::position(32))
+             .then(a::read_varint32.with(a::io_error));
+        },
+        (position_after_skip | position_after_skip_any) =
+            [34, 12, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 11, 8, 1, 12, 25, 1, 2, 3, 4, 5, 6, 7, 8, 16, 1] => |r| {
+            r.then(a::read_tag::value(34))
+             .then(a::skip.with(a::value(())))
+             .then(a::position(14))
+             .then(a::read_tag::value(11))
+             .then(a::skip.with(a::value(())))
+             .then(a::position(18))
+             .then(a::read_tag::value(25))
+             .then(a::skip.with(a::value(())))
+             .then(a::position(27))
+             .then(a::read_tag::value(16))
+             .then(a::read_varint32.with(a::value(1)))
+             .then(a::position(29))
+             .then(a::read_tag::none())
+             .then(a::position(29));
+        },
+        (position_in_limit | position_in_limit_any) = [10, 4, 8, 1, 16, 2, 24, 3] => |r| {
+            r.then(a::read_tag::value(10))
+             .then(a::read_limited(|r| {
+                r.then(a::position(2))
+                 .then(a::read_tag::value(8))
+                 .then(a::read_varint32.with(a::value(1)))
+                 .then(a::position(4))
+                 .then(a::read_tag::value(16))
+                 .then(a::read_varint32.with(a::value(2)))
+                 .then(a::read_tag::none())
+                 .then(a::position(6));
+                Ok(())
+              }).with(a::value(())))
+             .then(a::position(6))
+             .then(a::read_tag::value(24))
+             .then(a::read_varint32.with(a::value(3)))
+             .then(a::position(8));
+        },
+        (skip_to_end_of_limit | skip_to_end_of_limit_any) = [10, 3, 1, 2, 3, 16, 4] => |r| {
+            r.then(a::read_tag::value(10))
+             .then(a::read_limited(|r| {
+                r.then(a::read_varint32.with(a::value(1)))
+                 .then(a::skip_to_end.with(a::value(2)))
+                 .then(a::skip_to_end.with(a::value(0)));
+                Ok(())
+              }).with(a::value(())))
+             .then(a::read_tag::value(16))
+             .then(a::read_varint32.with(a::value(4)))
+             .then(a::read_tag::none());
+        },
+        (skip_to_end_of_input | skip_to_end_of_input_any) = [8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12] => |r| {
+            r.then(a::read_tag::value(8))
+             .then(a::skip_to_end.with(a::value(12)))
+             .then(a::skip_to_end.with(a::value(0)))
+             .then(a::read_tag::none());
+        },
+        (remaining_in_nested_limits | remaining_in_nested_limits_any) = [6, 2, 1, 2, 3, 4, 5, 6] => |r| {
+            r.then(a::remaining_limit(None))
+             .then(a::read_limited(|r| {
+                r.then(a::remaining_limit(Some(6)))
+                 .then(a::read_limited(|r| {
+                    r.then(a::remaining_limit(Some(2)))
+                     .then(a::read_varint32.with(a::value(1)))
+                     .then(a::remaining_limit(Some(1)))
+                     .then(a::read_varint32.with(a::value(2)));
+                    Ok(())
+                  }).with(a::value(())))
+                 .then(a::remaining_limit(Some(3)))
+                 .then(a::read_varint32.with(a::value(3)))
+                 .then(a::read_varint32.with(a::value(4)))
+                 .then(a::read_varint32.with(a::value(5)))
+                 .then(a::remaining_limit(Some(0)));
+                Ok(())
+              }).with(a::value(())))
+             .then(a::remaining_limit(None))
+             .then(a::read_varint32.with(a::value(6)));
         },
         (read_delimited_varint_field | read_delimited_varint_field_any) = [10, 2, 10, 1] => |r| {
             r.then(a::read_tag::value(10))
@@ -2125,16 +4460,24 @@ mod test {
                     read_truncated_tag, read_truncated_tag_any,
                     read_truncated_9byte_tag, read_truncated_9byte_tag_any,
                     read_malformed_tag, read_malformed_tag_any,
+                    read_tag_wire_type_6, read_tag_wire_type_6_any,
+                    read_tag_wire_type_7, read_tag_wire_type_7_any,
+                    read_tag_field_0, read_tag_field_0_any,
                     read_truncated_varint32_empty, read_truncated_varint32_empty_any,
                     read_truncated_varint32_5byte, read_truncated_varint32_5byte_any,
                     read_truncated_varint32_9byte, read_truncated_varint32_9byte_any,
                     read_malformed_varint32, read_malformed_varint32_any,
+                    read_overlong_varint32, read_overlong_varint32_any,
                     read_varint32, read_varint32_any,
                     read_varint32_5byte, read_varint32_5byte_any,
                     read_varint32_10byte, read_varint32_10byte_any,
+                    read_negative_int32, read_negative_int32_any,
+                    read_min_int32, read_min_int32_any,
+                    read_truncated_negative_int32, read_truncated_negative_int32_any,
                     read_truncated_varint64_empty, read_truncated_varint64_empty_any,
                     read_truncated_varint64_9byte, read_truncated_varint64_9byte_any,
                     read_malformed_varint64, read_malformed_varint64_any,
+                    read_overlong_varint64, read_overlong_varint64_any,
                     read_varint64, read_varint64_any,
                     read_varint64_10byte, read_varint64_10byte_any,
                     read_truncated_bit32, read_truncated_bit32_any,
@@ -2161,6 +4504,38 @@ mod test {
                     skip_length_delimited_truncated_byte, skip_length_delimited_truncated_byte_any,
                     skip_group, skip_group_any,
                     skip_group_other_field_end, skip_group_other_field_end_any,
+                    skip_nested_group, skip_nested_group_any,
+                    skip_crossed_group, skip_crossed_group_any,
+                    skip_unterminated_group, skip_unterminated_group_any,
+                    skip_unterminated_nested_group, skip_unterminated_nested_group_any,
+                    skip_group_unterminated_in_limit, skip_group_unterminated_in_limit_any,
+                    skip_end_group, skip_end_group_any,
+                    read_bytes_into_reused, read_bytes_into_reused_any,
+                    read_bytes_into_truncated, read_bytes_into_truncated_any,
+                    read_bytes_into_negative, read_bytes_into_negative_any,
+                    read_string_into_reused, read_string_into_reused_any,
+                    read_string_into_invalid, read_string_into_invalid_any,
+                    read_string_into_truncated, read_string_into_truncated_any,
+                    read_map_entry, read_map_entry_any,
+                    read_map_entry_missing_fields, read_map_entry_missing_fields_any,
+                    read_map_entry_repeated_and_unknown_fields, read_map_entry_repeated_and_unknown_fields_any,
+                    read_map_entry_truncated, read_map_entry_truncated_any,
+                    read_packed_fixed32, read_packed_fixed32_any,
+                    read_packed_sfixed64_empty, read_packed_sfixed64_empty_any,
+                    read_packed_negative_size, read_packed_negative_size_any,
+                    read_packed_truncated, read_packed_truncated_any,
+                    read_string_multibyte, read_string_multibyte_any,
+                    read_string_invalid, read_string_invalid_any,
+                    read_string_invalid_after_valid, read_string_invalid_after_valid_any,
+                    read_field_filtered_allowed, read_field_filtered_allowed_any,
+                    read_field_filtered_rejected, read_field_filtered_rejected_any,
+                    read_field_filtered_invalid, read_field_filtered_invalid_any,
+                    position_after_values, position_after_values_any,
+                    position_after_skip, position_after_skip_any,
+                    position_in_limit, position_in_limit_any,
+                    remaining_in_nested_limits, remaining_in_nested_limits_any,
+                    skip_to_end_of_limit, skip_to_end_of_limit_any,
+                    skip_to_end_of_input, skip_to_end_of_input_any,
                     read_delimited_varint_field, read_delimited_varint_field_any,
                     read_truncated_delimited_field, read_truncated_delimited_field_any,
                     read_negative_delimited_field, read_negative_delimited_field_any,
@@ -2,13 +2,15 @@
 
 use crate::collections::{RepeatedValue, FieldSet};
 use crate::io::{FieldNumber, WireType, Tag, Length, DEFAULT_BUF_SIZE};
-use crate::raw::Value;
+use crate::io::{MESSAGE_SET_ITEM_START, MESSAGE_SET_ITEM_END, MESSAGE_SET_TYPE_ID, MESSAGE_SET_MESSAGE};
+use crate::raw::{self, Value};
+use std::cmp;
 use std::convert::TryFrom;
 use std::error;
 use std::fmt::{self, Display, Formatter};
 use std::marker::PhantomData;
-use std::io::{self, Write, ErrorKind};
-use std::mem::ManuallyDrop;
+use std::io::{self, Write};
+use std::mem::{self, ManuallyDrop};
 use std::ops::Range;
 use std::ptr::{self, NonNull};
 use std::slice;
@@ -18,7 +20,7 @@ mod internal {
     use crate::internal::Sealed;
     use crate::io::{raw_varint32_size, raw_varint64_size};
     use std::convert::TryFrom;
-    use std::io::{self, Write, ErrorKind};
+    use std::io::Write;
     use std::ptr::{self, NonNull};
     use std::slice;
     use super::{Result, Error, write_varint32_unchecked, write_varint64_unchecked, write_bytes_unchecked};
@@ -30,6 +32,9 @@ mod internal {
         fn write_bit64(&mut self, value: u64) -> Result;
         fn write_length_delimited(&mut self, value: &[u8]) -> Result;
 
+        /// Makes room in the output's buffer for at least `additional` more bytes. Outputs without a growable buffer ignore this.
+        fn reserve(&mut self, _additional: usize) { }
+
         fn as_any(&mut self) -> Any;
     }
 
@@ -146,6 +151,17 @@ mod internal {
                 None => true
             }
         }
+        /// Writes the bytes to the output without a length prefix
+        pub(in crate::io::write) fn write_bytes(&mut self, value: &[u8]) -> Result {
+            if self.can_write(value.len()) {
+                unsafe { write_bytes_unchecked(value, self.current); }
+                Ok(())
+            } else if let Some(mut buffer) = self.as_borrowed_stream() {
+                buffer.write_bytes(value)
+            } else {
+                Err(Error::OutOfSpace)
+            }
+        }
         fn as_borrowed_stream(&mut self) -> Option<BorrowedStream> {
             match &mut self.stream {
                 Some(w) => {
@@ -170,7 +186,7 @@ mod internal {
             } else if let Some(mut buffer) = self.as_borrowed_stream() {
                 buffer.write_varint32(value, len)
             } else {
-                Err(io::Error::from(ErrorKind::WriteZero).into())
+                Err(Error::OutOfSpace)
             }
         }
         fn write_varint64(&mut self, value: u64) -> Result {
@@ -181,7 +197,7 @@ mod internal {
             } else if let Some(mut buffer) = self.as_borrowed_stream() {
                 buffer.write_varint64(value, len)
             } else {
-                Err(io::Error::from(ErrorKind::WriteZero).into())
+                Err(Error::OutOfSpace)
             }
         }
         fn write_bit32(&mut self, value: u32) -> Result {
@@ -192,7 +208,7 @@ mod internal {
             } else if let Some(mut buffer) = self.as_borrowed_stream() {
                 buffer.write_bit32(value)
             } else {
-                Err(io::Error::from(ErrorKind::WriteZero).into())
+                Err(Error::OutOfSpace)
             }
         }
         fn write_bit64(&mut self, value: u64) -> Result {
@@ -203,21 +219,17 @@ mod internal {
             } else if let Some(mut buffer) = self.as_borrowed_stream() {
                 buffer.write_bit64(value)
             } else {
-                Err(io::Error::from(ErrorKind::WriteZero).into())
+                Err(Error::OutOfSpace)
             }
         }
         fn write_length_delimited(&mut self, value: &[u8]) -> Result {
             let len = value.len();
             let delimiter = i32::try_from(len).map_err(|_| Error::ValueTooLarge)? as u32;
-            self.write_varint32(delimiter)?;
-            if self.can_write(len) {
-                unsafe { write_bytes_unchecked(value, self.current); }
-                Ok(())
-            } else if let Some(mut buffer) = self.as_borrowed_stream() {
-                buffer.write_bytes(value)
-            } else {
-                Err(io::Error::from(ErrorKind::WriteZero).into())
+            if self.stream.is_none() && !self.can_write(raw_varint32_size(delimiter).get() as usize + len) {
+                return Err(Error::OutOfSpace);
             }
+            self.write_varint32(delimiter)?;
+            self.write_bytes(value)
         }
         #[allow(clippy::map_clone)]
         fn as_any<'a>(&'a mut self) -> Any<'a> {
@@ -241,16 +253,22 @@ pub enum Error {
     /// too large to write to an output.
     ValueTooLarge,
     /// An error occured while writing data to the output.
-    /// For slice outputs, this is used to indicate if
-    /// not all data could be written to the slice.
-    IoError(io::Error)
+    IoError(io::Error),
+    /// The value could not be written since there wasn't enough space left in a slice output.
+    /// Nothing is written to the slice when this is returned.
+    OutOfSpace,
+    /// An extension that isn't a singular message extension was written as a MessageSet item.
+    /// Only singular message extensions can extend a MessageSet.
+    InvalidMessageSetItem(FieldNumber),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Error::ValueTooLarge => write!(f, "the value was too large to write to the output"),
-            Error::IoError(_) => write!(f, "an error occured while writing to the output")
+            Error::IoError(_) => write!(f, "an error occured while writing to the output"),
+            Error::OutOfSpace => write!(f, "there wasn't enough space left in the output to write the value"),
+            Error::InvalidMessageSetItem(num) => write!(f, "extension {} isn't a singular message and can't be written as a MessageSet item", num),
         }
     }
 }
@@ -270,6 +288,21 @@ impl From<io::Error> for Error {
     }
 }
 
+/// Converts the error into an [`io::Error`](https://doc.rust-lang.org/nightly/std/io/struct.Error.html),
+/// allowing writes to be used with `?` in functions returning `io::Result`.
+/// 
+/// Underlying IO errors are unwrapped, `ValueTooLarge` and `InvalidMessageSetItem` become `InvalidInput` errors,
+/// and `OutOfSpace` becomes a `WriteZero` error.
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::IoError(e) => e,
+            Error::ValueTooLarge | Error::InvalidMessageSetItem(_) => io::Error::new(io::ErrorKind::InvalidInput, e),
+            Error::OutOfSpace => io::Error::new(io::ErrorKind::WriteZero, e),
+        }
+    }
+}
+
 /// A result for a [`CodedWriter`](struct.CodedWriter.html) read operation
 pub type Result = std::result::Result<(), Error>;
 
@@ -411,7 +444,7 @@ impl Writer for Slice<'_> {
             debug_assert!(self.start <= self.end);
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::WriteZero).into())
+            Err(Error::OutOfSpace)
         }
     }
     fn write_varint64(&mut self, value: u64) -> Result {
@@ -423,7 +456,7 @@ impl Writer for Slice<'_> {
             debug_assert!(self.start <= self.end);
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::WriteZero).into())
+            Err(Error::OutOfSpace)
         }
     }
     fn write_bit32(&mut self, value: u32) -> Result {
@@ -436,7 +469,7 @@ impl Writer for Slice<'_> {
             debug_assert!(self.start <= self.end);
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::WriteZero).into())
+            Err(Error::OutOfSpace)
         }
     }
     fn write_bit64(&mut self, value: u64) -> Result {
@@ -449,7 +482,7 @@ impl Writer for Slice<'_> {
             debug_assert!(self.start <= self.end);
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::WriteZero).into())
+            Err(Error::OutOfSpace)
         }
     }
     fn write_length_delimited(&mut self, value: &[u8]) -> Result {
@@ -464,7 +497,7 @@ impl Writer for Slice<'_> {
             debug_assert!(self.start <= self.end);
             Ok(())
         } else {
-            Err(io::Error::from(ErrorKind::WriteZero).into())
+            Err(Error::OutOfSpace)
         }
     }
 
@@ -529,6 +562,22 @@ impl<T: Write> Stream<T> {
         std::mem::forget(self);
         output
     }
+    fn grow(&mut self, additional: usize) {
+        if self.remaining() >= additional {
+            return;
+        }
+
+        let buffered = self.buffered();
+        let cap = cmp::max(buffered + additional, self.capacity() * 2);
+        let new = Box::leak(vec![0; cap].into_boxed_slice());
+        new[..buffered].copy_from_slice(unsafe { slice::from_raw_parts(self.start.as_ptr(), buffered) });
+        unsafe { self.drop_inner(DropFlag::Moved) };
+
+        let Range { start, end } = new.as_mut_ptr_range();
+        self.start = unsafe { NonNull::new_unchecked(start) };
+        self.current = unsafe { start.add(buffered) };
+        self.end = unsafe { NonNull::new_unchecked(end) };
+    }
     #[inline]
     unsafe fn drop_inner(&mut self, flag: DropFlag) {
         let raw_slice = slice::from_raw_parts_mut(self.start.as_ptr(), self.capacity());
@@ -606,6 +655,10 @@ impl<T: Write> Writer for Stream<T> {
         Ok(())
     }
 
+    fn reserve(&mut self, additional: usize) {
+        self.grow(additional)
+    }
+
     fn as_any(&mut self) -> Any {
         Any {
             stream: Some(&mut *self.output),
@@ -617,10 +670,166 @@ impl<T: Write> Writer for Stream<T> {
 }
 impl<T: Write> Drop for Stream<T> {
     fn drop(&mut self) {
+        // best effort, errors can't be reported from here
+        let _ = self.flush();
         unsafe { self.drop_inner(DropFlag::Owned) }
     }
 }
 
+/// An output that forwards writes to an inner output while passing every written chunk of bytes to a callback.
+/// 
+/// The callback sees bytes in the order they're written, which for slice outputs is also the order
+/// they appear in the buffer. It's only called after a write to the inner output succeeds.
+/// 
+/// Writes made through [`CodedWriter::as_any`](struct.CodedWriter.html#method.as_any) are also observed,
+/// but since they're passed through an `io::Write` adapter, running out of space in the inner output
+/// is reported as a `WriteZero` IO error instead of `OutOfSpace`.
+pub struct TeeWriter<O: Output, F: FnMut(&[u8])> {
+    observed: Observed<O, F>,
+    empty: *mut u8,
+}
+
+struct Observed<O: Output, F: FnMut(&[u8])> {
+    inner: O,
+    f: F,
+}
+
+impl<O: Output, F: FnMut(&[u8])> Write for Observed<O, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.as_any().write_bytes(buf)?;
+        (self.f)(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<O: Output, F: FnMut(&[u8])> Writer for TeeWriter<O, F> {
+    fn write_varint32(&mut self, value: u32) -> Result {
+        self.observed.inner.write_varint32(value)?;
+        let mut buf = [0; 5];
+        let len = raw_varint32_size(value).get() as usize;
+        unsafe { write_varint32_unchecked(value, &mut buf.as_mut_ptr()); }
+        (self.observed.f)(&buf[..len]);
+        Ok(())
+    }
+    fn write_varint64(&mut self, value: u64) -> Result {
+        self.observed.inner.write_varint64(value)?;
+        let mut buf = [0; 10];
+        let len = raw_varint64_size(value).get() as usize;
+        unsafe { write_varint64_unchecked(value, &mut buf.as_mut_ptr()); }
+        (self.observed.f)(&buf[..len]);
+        Ok(())
+    }
+    fn write_bit32(&mut self, value: u32) -> Result {
+        self.observed.inner.write_bit32(value)?;
+        (self.observed.f)(&value.to_le_bytes());
+        Ok(())
+    }
+    fn write_bit64(&mut self, value: u64) -> Result {
+        self.observed.inner.write_bit64(value)?;
+        (self.observed.f)(&value.to_le_bytes());
+        Ok(())
+    }
+    fn write_length_delimited(&mut self, value: &[u8]) -> Result {
+        self.observed.inner.write_length_delimited(value)?;
+        let len = value.len() as u32;
+        let mut buf = [0; 5];
+        unsafe { write_varint32_unchecked(len, &mut buf.as_mut_ptr()); }
+        (self.observed.f)(&buf[..raw_varint32_size(len).get() as usize]);
+        (self.observed.f)(value);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.observed.inner.reserve(additional)
+    }
+
+    fn as_any(&mut self) -> Any {
+        // an empty buffer makes every write go straight to the stream, which observes it
+        let empty = unsafe { NonNull::new_unchecked(self.empty) };
+        Any {
+            stream: Some(&mut self.observed),
+            start: Some(empty),
+            current: &mut self.empty,
+            end: Some(empty),
+        }
+    }
+}
+
+/// An output that formats every written byte as two lowercase hex digits into a [`fmt::Write`](https://doc.rust-lang.org/nightly/std/fmt/trait.Write.html).
+/// 
+/// This is a debugging aid for dumping the wire format of a value or generating test vectors without
+/// writing to an intermediate buffer first. It formats each byte as it's written, so it's much slower
+/// than writing to a slice or stream and shouldn't be used to write output that isn't read by a person.
+/// 
+/// Lengths are calculated the same way as for any other output, so a message written to a hex output
+/// produces exactly the hex representation of the bytes it would write to a slice. Errors returned by the
+/// formatter are reported as `IoError`s with the `Other` kind.
+pub struct Hex<W: fmt::Write> {
+    output: HexStream<W>,
+    empty: *mut u8,
+}
+
+struct HexStream<W: fmt::Write>(W);
+
+impl<W: fmt::Write> HexStream<W> {
+    fn write_hex(&mut self, buf: &[u8]) -> Result {
+        for b in buf {
+            write!(self.0, "{:02x}", b).map_err(|_| io::Error::new(io::ErrorKind::Other, "the formatter returned an error"))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> Write for HexStream<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_hex(buf)?;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write> Writer for Hex<W> {
+    fn write_varint32(&mut self, value: u32) -> Result {
+        let mut buf = [0; 5];
+        let len = raw_varint32_size(value).get() as usize;
+        unsafe { write_varint32_unchecked(value, &mut buf.as_mut_ptr()); }
+        self.output.write_hex(&buf[..len])
+    }
+    fn write_varint64(&mut self, value: u64) -> Result {
+        let mut buf = [0; 10];
+        let len = raw_varint64_size(value).get() as usize;
+        unsafe { write_varint64_unchecked(value, &mut buf.as_mut_ptr()); }
+        self.output.write_hex(&buf[..len])
+    }
+    fn write_bit32(&mut self, value: u32) -> Result {
+        self.output.write_hex(&value.to_le_bytes())
+    }
+    fn write_bit64(&mut self, value: u64) -> Result {
+        self.output.write_hex(&value.to_le_bytes())
+    }
+    fn write_length_delimited(&mut self, value: &[u8]) -> Result {
+        let len = i32::try_from(value.len()).map_err(|_| Error::ValueTooLarge)? as u32;
+        self.write_varint32(len)?;
+        self.output.write_hex(value)
+    }
+
+    fn as_any(&mut self) -> Any {
+        // an empty buffer makes every write go straight to the stream, which formats it
+        let empty = unsafe { NonNull::new_unchecked(self.empty) };
+        Any {
+            stream: Some(&mut self.output),
+            start: Some(empty),
+            current: &mut self.empty,
+            end: Some(empty),
+        }
+    }
+}
+
 /// A protobuf coded output writer that writes to the specified output
 pub struct CodedWriter<T: Output> {
     inner: T,
@@ -631,6 +840,27 @@ impl<'a> CodedWriter<Slice<'a>> {
     pub fn with_slice(s: &'a mut [u8]) -> Self {
         Self { inner: Slice::new(s), }
     }
+    /// Returns the number of bytes left to write in the slice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use protrust::io::{write, CodedWriter};
+    ///
+    /// let mut output = [0u8; 6];
+    /// let mut writer = CodedWriter::with_slice(&mut output);
+    /// assert_eq!(writer.remaining(), 6);
+    ///
+    /// writer.write_bit32(1)?;
+    /// assert_eq!(writer.remaining(), 2);
+    ///
+    /// assert!(matches!(writer.write_bit32(2), Err(write::Error::OutOfSpace)));
+    /// assert_eq!(writer.remaining(), 2);
+    /// # Ok::<(), write::Error>(())
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.inner.len()
+    }
     /// Returns ownership of the buffer at the current point in the slice
     pub fn into_inner(self) -> &'a mut [u8] {
         self.inner.into_inner()
@@ -654,6 +884,9 @@ impl<'a> CodedWriter<SliceUnchecked<'a>> {
     }
 }
 
+/// Writers over streams buffer their output. Any buffered data is flushed when the writer is dropped,
+/// but since errors can't be returned from a drop, they're ignored. Use [`finish`](#method.finish)
+/// or [`flush`](#method.flush) when write errors need to be handled.
 impl<T: Write> CodedWriter<Stream<T>> {
     /// Creates a coded writer that writes to the specified stream with the default buffer capacity
     pub fn with_stream(inner: T) -> Self {
@@ -668,13 +901,101 @@ impl<T: Write> CodedWriter<Stream<T>> {
     pub fn flush(&mut self) -> Result {
         self.inner.flush()
     }
-    /// Returns ownership of the inner stream, discarding any data in the buffer
+    /// Returns ownership of the inner stream.
+    /// 
+    /// **Any data still in the buffer is discarded, not written.** Unlike dropping the writer, this doesn't flush,
+    /// so the inner stream only holds what previous flushes wrote to it. Use [`finish`](#method.finish) to flush
+    /// the buffer before returning the inner stream, or call [`flush`](#method.flush) first.
     pub fn into_inner(self) -> T {
         self.inner.into_inner()
     }
+    /// Flushes the stream buffer and returns ownership of the inner stream.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{write, CodedWriter};
+    /// 
+    /// let mut writer = CodedWriter::with_stream(Vec::new());
+    /// writer.write_varint32(150)?;
+    /// 
+    /// assert_eq!(writer.finish()?, [150, 1]);
+    /// # Ok::<(), write::Error>(())
+    /// ```
+    pub fn finish(mut self) -> std::result::Result<T, Error> {
+        self.flush()?;
+        Ok(self.into_inner())
+    }
+}
+
+impl<W: fmt::Write> CodedWriter<Hex<W>> {
+    /// Creates a coded writer that writes every byte as hex digits to the specified formatter.
+    /// See [`Hex`](struct.Hex.html) for more information.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedWriter;
+    /// 
+    /// let mut dump = String::new();
+    /// let mut writer = CodedWriter::with_hex(&mut dump);
+    /// writer.write_varint32(150)?;
+    /// writer.write_length_delimited(b"hi")?;
+    /// 
+    /// assert_eq!(dump, "9601026869");
+    /// # Ok::<(), protrust::io::write::Error>(())
+    /// ```
+    pub fn with_hex(inner: W) -> Self {
+        Self {
+            inner: Hex {
+                output: HexStream(inner),
+                empty: NonNull::dangling().as_ptr(),
+            }
+        }
+    }
+    /// Returns ownership of the inner formatter
+    pub fn into_inner(self) -> W {
+        self.inner.output.0
+    }
+}
+
+impl<T: Output, F: FnMut(&[u8])> CodedWriter<TeeWriter<T, F>> {
+    /// Returns the inner writer, dropping the callback
+    pub fn into_inner(self) -> CodedWriter<T> {
+        CodedWriter { inner: self.inner.observed.inner }
+    }
 }
 
 impl<T: Output> CodedWriter<T> {
+    /// Wraps the writer in a [`TeeWriter`](struct.TeeWriter.html), calling the function with every chunk of bytes written.
+    /// 
+    /// This can be used to hash, log, or measure the exact encoded bytes without a second pass over the output.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::CodedWriter;
+    /// 
+    /// let mut output = [0u8; 4];
+    /// let mut observed = Vec::new();
+    /// let mut writer = CodedWriter::with_slice(&mut output).tee(|b| observed.extend_from_slice(b));
+    /// 
+    /// writer.write_varint32(150)?;
+    /// writer.write_length_delimited(&[1])?;
+    /// drop(writer);
+    /// 
+    /// assert_eq!(observed, [150, 1, 1, 1]);
+    /// assert_eq!(output, [150, 1, 1, 1]);
+    /// # Ok::<(), protrust::io::write::Error>(())
+    /// ```
+    pub fn tee<F: FnMut(&[u8])>(self, f: F) -> CodedWriter<TeeWriter<T, F>> {
+        CodedWriter {
+            inner: TeeWriter {
+                observed: Observed { inner: self.inner, f },
+                empty: NonNull::dangling().as_ptr(),
+            }
+        }
+    }
     /// Converts the generic writer into a writer over Any input
     pub fn as_any(&mut self) -> CodedWriter<Any> {
         CodedWriter {
@@ -682,6 +1003,31 @@ impl<T: Output> CodedWriter<T> {
         }
     }
 
+    /// Ensures the writer's buffer can hold at least `additional` more bytes before it needs to flush.
+    /// 
+    /// This can be used with a calculated message size to avoid flushing the buffer part way through
+    /// writing a large message. Writers over slices don't have a buffer, so this does nothing for them.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{write, Length, CodedWriter};
+    /// 
+    /// let mut writer = CodedWriter::with_capacity(2, Vec::new());
+    /// writer.reserve(Length::new(3).unwrap());
+    /// writer.write_varint32(1)?;
+    /// writer.write_varint32(2)?;
+    /// writer.write_varint32(3)?;
+    /// 
+    /// // all three values fit in the buffer, so they're written to the Vec in one flush
+    /// writer.flush()?;
+    /// assert_eq!(writer.into_inner(), [1, 2, 3]);
+    /// # Ok::<(), write::Error>(())
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self, additional: Length) {
+        self.inner.reserve(additional.get() as usize)
+    }
     /// Writes a 32-bit varint value to the output
     #[inline]
     pub fn write_varint32(&mut self, value: u32) -> Result {
@@ -729,11 +1075,49 @@ impl<T: Output> CodedWriter<T> {
     pub fn write_field<V: Value>(&mut self, num: FieldNumber, value: &V::Inner) -> Result {
         self.write_tag(Tag::new(num, V::WIRE_TYPE))?;
         self.write_value::<V>(value)?;
-        if V::WIRE_TYPE != WireType::StartGroup {
+        if V::WIRE_TYPE == WireType::StartGroup {
             self.write_tag(Tag::new(num, WireType::EndGroup))?;
         }
         Ok(())
     }
+    /// Writes a packed run of constant sized values to the output, including the length prefix.
+    /// 
+    /// On little endian targets, the encoding of every constant sized value is the same as its representation
+    /// in memory, so the whole slice is copied to the output at once instead of being written value by value.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{write, CodedWriter};
+    /// use protrust::raw::Fixed32;
+    /// 
+    /// let mut output = [0u8; 9];
+    /// let mut writer = CodedWriter::with_slice(&mut output);
+    /// writer.write_fixed_slice::<Fixed32>(&[1, 2])?;
+    /// 
+    /// assert_eq!(output, [8, 1, 0, 0, 0, 2, 0, 0, 0]);
+    /// # Ok::<(), write::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If the length of the run overflows an `i32`, this returns a `ValueTooLarge` error.
+    pub fn write_fixed_slice<V: raw::ConstSized>(&mut self, values: &[V::Inner]) -> Result {
+        let size = V::SIZE.get() as usize;
+        let len = values.len().checked_mul(size).ok_or(Error::ValueTooLarge)?;
+        if cfg!(target_endian = "little") && mem::size_of::<V::Inner>() == size {
+            // every ConstSized value is a fixed width integer or a bool, which are encoded as their little endian bytes
+            let bytes = unsafe { slice::from_raw_parts(values.as_ptr() as *const u8, len) };
+            self.write_length_delimited(bytes)
+        } else {
+            let len = i32::try_from(len).ok().and_then(Length::new).ok_or(Error::ValueTooLarge)?;
+            self.write_length(len)?;
+            for value in values {
+                self.write_value::<V>(value)?;
+            }
+            Ok(())
+        }
+    }
     /// Writes the values in the repeated field to the output. This uses an alias to `RepeatedValue::write_to`.
     #[inline]
     pub fn write_values<U: RepeatedValue<V>, V>(&mut self, value: &U, num: FieldNumber) -> Result {
@@ -744,11 +1128,216 @@ impl<T: Output> CodedWriter<T> {
     pub fn write_fields<U: FieldSet>(&mut self, value: &U) -> Result {
         value.write_to(self)
     }
+    /// Writes an item in the legacy proto2 MessageSet wire format: a group (field 1) containing
+    /// the `type_id` (field 2) and the encoded message (field 3).
+    /// 
+    /// Extension sets are written in this format with
+    /// [`ExtensionSet::write_message_set_to`](../../extend/struct.ExtensionSet.html#method.write_message_set_to).
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use protrust::io::{write, CodedWriter, FieldNumber};
+    /// 
+    /// let mut output = [0u8; 9];
+    /// let mut writer = CodedWriter::with_slice(&mut output);
+    /// 
+    /// // type_id 1000 with an encoded message containing field 1 = 5
+    /// writer.write_message_set_item(FieldNumber::new(1000).unwrap(), &[8, 5])?;
+    /// 
+    /// assert_eq!(output, [11, 16, 0xE8, 0x07, 26, 2, 8, 5, 12]);
+    /// # Ok::<(), write::Error>(())
+    /// ```
+    pub fn write_message_set_item(&mut self, type_id: FieldNumber, message: &[u8]) -> Result {
+        self.write_message_set_item_with(type_id, |output| {
+            output.write_tag(MESSAGE_SET_MESSAGE)?;
+            output.write_length_delimited(message)
+        })
+    }
+    /// Writes a MessageSet item, using the function to write the item's message field
+    pub(crate) fn write_message_set_item_with<F: FnOnce(&mut Self) -> Result>(&mut self, type_id: FieldNumber, f: F) -> Result {
+        self.write_tag(MESSAGE_SET_ITEM_START)?;
+        self.write_tag(MESSAGE_SET_TYPE_ID)?;
+        self.write_varint32(type_id.get())?;
+        f(self)?;
+        self.write_tag(MESSAGE_SET_ITEM_END)
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::unreadable_literal)]
 mod test {
+    use crate::io::{FieldNumber, Length};
     use crate::io::write::{self, Any, Output, CodedWriter};
+    use crate::raw;
+    use std::io;
+
+    #[test]
+    fn into_io_error() {
+        let err = io::Error::from(write::Error::OutOfSpace);
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert!(matches!(err.into_inner().unwrap().downcast::<write::Error>().map(|e| *e), Ok(write::Error::OutOfSpace)));
+
+        assert_eq!(io::Error::from(write::Error::ValueTooLarge).kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(io::Error::from(write::Error::IoError(io::ErrorKind::BrokenPipe.into())).kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    /// A stream that records each call to write
+    #[derive(Default)]
+    struct Writes(Vec<Vec<u8>>);
+
+    impl io::Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.push(buf.to_vec());
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reserve_avoids_flushes() {
+        let write = |reserve: Option<i32>| {
+            let mut writer = CodedWriter::with_capacity(2, Writes::default());
+            writer.write_varint32(1).unwrap();
+            if let Some(r) = reserve {
+                writer.reserve(Length::new(r).unwrap());
+            }
+            for v in 2..=5 {
+                writer.write_varint32(v).unwrap();
+            }
+            writer.flush().unwrap();
+            writer.into_inner().0
+        };
+
+        assert_eq!(write(None), [vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(write(Some(4)), [vec![1, 2, 3, 4, 5]]);
+        assert_eq!(write(Some(1)), [vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn reserve_slice_is_noop() {
+        let mut output = [0u8; 2];
+        let mut writer = CodedWriter::with_slice(&mut output);
+        writer.reserve(Length::new(10).unwrap());
+
+        assert_eq!(writer.remaining(), 2);
+    }
+
+    #[test]
+    fn question_mark_in_io_result() {
+        fn write(output: &mut [u8]) -> io::Result<()> {
+            CodedWriter::with_slice(output).write_bit32(1)?;
+            Ok(())
+        }
+
+        assert!(write(&mut [0; 4]).is_ok());
+        assert_eq!(write(&mut [0; 2]).unwrap_err().kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn tee_observes_slice_writes() {
+        let mut output = [0u8; 17];
+        let mut observed = Vec::new();
+        let mut writer = CodedWriter::with_slice(&mut output).tee(|b| observed.extend_from_slice(b));
+
+        writer.write_varint32(300).unwrap();
+        writer.write_varint64(1).unwrap();
+        writer.write_bit32(2).unwrap();
+        writer.write_bit64(3).unwrap();
+        writer.write_length_delimited(&[4]).unwrap();
+        assert!(matches!(writer.write_bit32(5), Err(write::Error::OutOfSpace)));
+        assert_eq!(writer.into_inner().remaining(), 0);
+
+        assert_eq!(observed, [172, 2, 1, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 1, 4]);
+        assert_eq!(observed, output);
+    }
+
+    #[test]
+    fn stream_flushes_on_drop() {
+        let mut output = Vec::new();
+        let mut writer = CodedWriter::with_capacity(4, &mut output);
+        writer.write_varint32(1).unwrap();
+        writer.write_varint32(150).unwrap();
+        drop(writer);
+
+        assert_eq!(output, [1, 150, 1]);
+    }
+
+    #[test]
+    fn stream_finish_flushes_buffer() {
+        let mut writer = CodedWriter::with_capacity(4, Vec::new());
+        writer.write_varint32(1).unwrap();
+        writer.write_varint32(150).unwrap();
+
+        assert_eq!(writer.finish().unwrap(), [1, 150, 1]);
+    }
+
+    #[test]
+    fn tee_observes_any_writes() {
+        let mut observed = Vec::new();
+        let mut writer = CodedWriter::with_capacity(4, Vec::new()).tee(|b| observed.extend_from_slice(b));
+
+        writer.write_varint32(1).unwrap();
+        writer.as_any().write_length_delimited(&[1, 2, 3, 4, 5]).unwrap();
+        writer.as_any().write_bit32(6).unwrap();
+        writer.write_varint32(7).unwrap();
+
+        let mut writer = writer.into_inner();
+        writer.flush().unwrap();
+        let output = writer.into_inner();
+
+        assert_eq!(observed, [1, 5, 1, 2, 3, 4, 5, 6, 0, 0, 0, 7]);
+        assert_eq!(output, observed);
+    }
+
+    #[test]
+    fn hex_matches_slice_output() {
+        use crate::doctest::timestamp::Timestamp;
+        use crate::Message;
+
+        let mut timestamp = Timestamp::new();
+        *timestamp.seconds_mut() = 5;
+        *timestamp.nanos_mut() = 300;
+
+        let mut output = [0u8; 5];
+        timestamp.write_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+
+        let mut dump = String::new();
+        timestamp.write_to(&mut CodedWriter::with_hex(&mut dump)).unwrap();
+
+        assert_eq!(dump, "080510ac02");
+        assert_eq!(dump, output.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+    }
+
+    #[test]
+    fn hex_formats_all_writes() {
+        let mut writer = CodedWriter::with_hex(String::new());
+        writer.write_varint64(u64::max_value()).unwrap();
+        writer.write_bit32(0xdead_beef).unwrap();
+        writer.write_bit64(1).unwrap();
+        writer.as_any().write_length_delimited(&[0xab; 3]).unwrap();
+        writer.as_any().write_bit32(2).unwrap();
+
+        assert_eq!(writer.into_inner(), "ffffffffffffffffff01efbeadde010000000000000003ababab02000000");
+    }
+
+    #[test]
+    fn hex_reports_formatter_errors() {
+        use std::fmt;
+
+        struct Failing;
+        impl fmt::Write for Failing {
+            fn write_str(&mut self, _: &str) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        let mut writer = CodedWriter::with_hex(Failing);
+        assert!(matches!(writer.write_varint32(1), Err(write::Error::IoError(ref e)) if e.kind() == io::ErrorKind::Other));
+        assert!(matches!(writer.as_any().write_bit32(1), Err(write::Error::IoError(ref e)) if e.kind() == io::ErrorKind::Other));
+    }
 
     pub trait WriterOutput<'a> {
         type Writer: Output + 'a;
@@ -847,6 +1436,52 @@ mod test {
             w.write_length_delimited(&[1, 2, 3])
         } => Ok(([3, 1, 2, 3], [])),
 
+        (write_field | write_field_any | size: 6) = |w| {
+            w.write_field::<raw::Int32>(FieldNumber::new(1).unwrap(), &5)?;
+            w.write_field::<raw::String>(FieldNumber::new(2).unwrap(), &"ab".to_string())
+        } => Ok(([8, 5, 18, 2, 97, 98], [])),
+
+        (write_fixed_slice | write_fixed_slice_any | size: 13) = |w| {
+            w.write_fixed_slice::<raw::Fixed32>(&[1, 0x0403_0201])?;
+            w.write_fixed_slice::<raw::Bool>(&[true, false, true])
+        } => Ok(([8, 1, 0, 0, 0, 1, 2, 3, 4, 3, 1, 0, 1], [])),
+
+        (write_fixed_slice_signed | write_fixed_slice_signed_any | size: 10) = |w| {
+            w.write_fixed_slice::<raw::Sfixed64>(&[])?;
+            w.write_fixed_slice::<raw::Sfixed64>(&[-2])
+        } => Ok(([0, 8, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF], [])),
+
+        (write_fixed_slice_out_of_space | write_fixed_slice_out_of_space_any | size: 8) = |w| {
+            w.write_fixed_slice::<raw::Fixed32>(&[1, 2])
+        } => Err(write::Error::OutOfSpace),
+
+        (write_varint32_out_of_space | write_varint32_out_of_space_any | size: 1) = |w| {
+            w.write_varint32(128)
+        } => Err(write::Error::OutOfSpace),
+
+        (write_varint64_out_of_space | write_varint64_out_of_space_any | size: 9) = |w| {
+            w.write_varint64(u64::max_value())
+        } => Err(write::Error::OutOfSpace),
+
+        (write_bit32_out_of_space | write_bit32_out_of_space_any | size: 2) = |w| {
+            w.write_bit32(0)
+        } => Err(write::Error::OutOfSpace),
+
+        (write_bit64_out_of_space | write_bit64_out_of_space_any | size: 7) = |w| {
+            w.write_bit64(0)
+        } => Err(write::Error::OutOfSpace),
+
+        (write_length_delimited_out_of_space | write_length_delimited_out_of_space_any | size: 3) = |w| {
+            w.write_length_delimited(&[1, 2, 3])
+        } => Err(write::Error::OutOfSpace),
+
+        (write_out_of_space_keeps_output | write_out_of_space_keeps_output_any | size: 3) = |w| {
+            w.write_varint32(1)?;
+            assert!(matches!(w.write_length_delimited(&[1, 2]), Err(write::Error::OutOfSpace)));
+            assert!(matches!(w.write_bit32(1), Err(write::Error::OutOfSpace)));
+            w.write_varint32(2)
+        } => Ok(([1, 2], [0])),
+
         (write_as_any | write_as_any_any | size: 6) = |w| {
             w.write_varint32(8)?;
 
@@ -886,6 +1521,9 @@ mod test {
                     write_bit32, write_bit32_any,
                     write_bit64, write_bit64_any,
                     write_length_delimited, write_length_delimited_any,
+                    write_fixed_slice, write_fixed_slice_any,
+                    write_fixed_slice_signed, write_fixed_slice_signed_any,
+                    write_field, write_field_any,
                     write_as_any, write_as_any_any
                 }
             }
@@ -909,6 +1547,17 @@ mod test {
             }
 
             run_suite!(SliceOutput);
+            run! {
+                SliceOutput => {
+                    write_varint32_out_of_space, write_varint32_out_of_space_any,
+                    write_varint64_out_of_space, write_varint64_out_of_space_any,
+                    write_bit32_out_of_space, write_bit32_out_of_space_any,
+                    write_bit64_out_of_space, write_bit64_out_of_space_any,
+                    write_length_delimited_out_of_space, write_length_delimited_out_of_space_any,
+                    write_fixed_slice_out_of_space, write_fixed_slice_out_of_space_any,
+                    write_out_of_space_keeps_output, write_out_of_space_keeps_output_any
+                }
+            }
         }
         mod slice_unchecked {
             use crate::io::write::{self, SliceUnchecked, CodedWriter, test::WriterOutput};
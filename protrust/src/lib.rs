@@ -17,19 +17,47 @@
 compile_error!("This library does not support 16-bit platforms");
 
 mod internal {
+    use crate::Mergable;
+
     pub trait Sealed { }
+
+    /// Merges values that are `Mergable` (like messages) and replaces other values,
+    /// for extension sets, which hold any kind of value.
+    pub trait MergeValue {
+        fn merge_value(&mut self, other: &Self);
+    }
+
+    impl<V: Clone> MergeValue for V {
+        default fn merge_value(&mut self, other: &Self) {
+            self.clone_from(other)
+        }
+    }
+
+    impl<V: Clone + Mergable> MergeValue for V {
+        fn merge_value(&mut self, other: &Self) {
+            self.merge(other)
+        }
+    }
 }
 
-#[cfg(doctest)]
+#[cfg(any(test, doctest))]
+#[allow(missing_docs)]
 pub mod doctest;
 
+pub mod cmp;
 pub mod collections;
+#[cfg(feature = "prost-compat")]
+pub mod compat;
 pub mod extend;
+pub mod field_mask;
 pub mod io;
 pub mod raw;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 use crate::io::{read, write, Length, CodedReader, CodedWriter, Input, Output};
-use std::fmt::Debug;
+use std::error;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::Hash;
 
 pub use collections::unknown_fields::UnknownFieldSet;
@@ -96,6 +124,75 @@ pub trait Message: Default + Clone + PartialEq + Debug + Sized {
     /// assert_eq!(timestamp.nanos(), &100);
     /// ```
     fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()>;
+    /// Parses a new message from the slice, reading fields until the end of the slice.
+    /// 
+    /// Encoded messages can be concatenated: parsing the concatenation of two encoded messages is the same
+    /// as parsing both and merging the second into the first. Since this reads to the end of the slice,
+    /// a buffer of appended messages is parsed as one merged message, where later singular fields replace
+    /// earlier ones and repeated fields are appended.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::Message;
+    /// 
+    /// // seconds = 5, nanos = 100 followed by seconds = 10
+    /// let input = [8, 5, 16, 100, 8, 10];
+    /// let timestamp = Timestamp::parse_merged_from_slice(&input)?;
+    /// 
+    /// assert_eq!(timestamp.seconds(), &10);
+    /// assert_eq!(timestamp.nanos(), &100);
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    fn parse_merged_from_slice(data: &[u8]) -> read::Result<Self> {
+        let mut message = Self::default();
+        message.merge_from(&mut CodedReader::with_slice(data))?;
+        Ok(message)
+    }
+    /// Merges fields from the reader into this message, keeping the fields read before an error.
+    /// 
+    /// Normal merges are strict, so an error part way through the input fails the whole merge.
+    /// When recovering data from damaged input like a partially written log record, this can be used
+    /// instead to keep everything that was read successfully. If an error is returned while merging,
+    /// the rest of the input (or the rest of the reader's current limit) is skipped and the error
+    /// is returned as a [`Truncated`] value, along with where the error happened and how many bytes were skipped.
+    /// 
+    /// Since fields are merged as they're read, a repeated field or nested message that was
+    /// being read when the error happened may only contain some of its values.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::Message;
+    /// use protrust::io::CodedReader;
+    /// 
+    /// // seconds = 5, followed by a truncated nanos field
+    /// let input = [8, 5, 16, 128];
+    /// let mut timestamp = Timestamp::new();
+    /// let truncated = timestamp.merge_best_effort(&mut CodedReader::with_slice(&input))?;
+    /// 
+    /// assert_eq!(timestamp.seconds(), &5);
+    /// assert!(truncated.is_some());
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// This only returns an error if the rest of the input couldn't be skipped.
+    /// 
+    /// [`Truncated`]: io/read/struct.Truncated.html
+    fn merge_best_effort<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<Option<read::Truncated>> {
+        match self.merge_from(input) {
+            Ok(()) => Ok(None),
+            Err(error) => {
+                let position = input.position();
+                let skipped = input.skip_to_end()?;
+                Ok(Some(read::Truncated::new(error, position, skipped)))
+            }
+        }
+    }
     /// Calculates the size of this message, returning None if the size overflows an `i32`.
     /// 
     /// # Examples
@@ -132,8 +229,126 @@ pub trait Message: Default + Clone + PartialEq + Debug + Sized {
     /// timestamp.write_to(&mut writer).expect("size is calculated ahead of time");
     /// ```
     fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result;
+    /// Writes this message's data to the [`Write`](https://doc.rust-lang.org/nightly/std/io/trait.Write.html),
+    /// buffering the output and flushing it once the message is written.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::Message;
+    /// 
+    /// let mut timestamp = Timestamp::new();
+    /// *timestamp.seconds_mut() = 5;
+    /// 
+    /// let mut output = Vec::new();
+    /// timestamp.write_to_writer(&mut output)?;
+    /// 
+    /// assert_eq!(output, [8, 5]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// If the message's size overflows an `i32`, this returns an `InvalidInput` error.
+    /// Errors from the writer are returned as-is. Output still buffered when an error is returned
+    /// is discarded rather than written, so a message that fits in the buffer is never partially written.
+    fn write_to_writer<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        let len = self.calculate_size().ok_or(write::Error::ValueTooLarge)?.get() as usize;
+        let mut writer = CodedWriter::with_capacity(std::cmp::min(len, io::DEFAULT_BUF_SIZE), w);
+        match self.write_to(&mut writer) {
+            Ok(()) => {
+                writer.finish()?;
+                Ok(())
+            },
+            Err(e) => {
+                // discard the buffer so a partially written message isn't flushed to the writer
+                writer.into_inner();
+                Err(e.into())
+            }
+        }
+    }
+    /// Encodes this message into the buffer, replacing its contents.
+    ///
+    /// The buffer is cleared and resized to the message's size before writing, so its existing
+    /// allocation is reused. When encoding many messages, this avoids allocating a new buffer for each one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::Message;
+    ///
+    /// let mut timestamp = Timestamp::new();
+    /// *timestamp.seconds_mut() = 5;
+    ///
+    /// let mut buf = vec![1, 2, 3];
+    /// timestamp.encode_into(&mut buf)?;
+    ///
+    /// assert_eq!(buf, [8, 5]);
+    /// # Ok::<(), protrust::io::write::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the message's size overflows an `i32`, this returns a `ValueTooLarge` error and leaves the buffer unchanged.
+    /// If writing the message fails, the buffer is left empty.
+    fn encode_into(&self, buf: &mut Vec<u8>) -> write::Result {
+        let len = self.calculate_size().ok_or(write::Error::ValueTooLarge)?.get() as usize;
+        buf.clear();
+        buf.resize(len, 0);
+        let result = self.write_to(&mut CodedWriter::with_slice(buf));
+        if result.is_err() {
+            buf.clear();
+        }
+        result
+    }
     /// Returns whether the message value is initialized.
     fn is_initialized(&self) -> bool;
+    /// Adds the paths of any required fields that aren't set in this message to `missing`,
+    /// prefixing each path with `path`.
+    /// 
+    /// The default implementation can't see individual fields, so if the message isn't initialized
+    /// it adds `path` itself. Messages with required fields override this to report each unset field,
+    /// recursing into nested messages with their own path.
+    fn missing_fields(&self, path: &str, missing: &mut Vec<String>) {
+        if !self.is_initialized() {
+            missing.push(path.to_string());
+        }
+    }
+    /// Merges another message into this one, returning an error listing the paths of required fields
+    /// that are still unset after the merge.
+    /// 
+    /// The merge is always performed, the result only reports whether the merged message is initialized.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::required::Required;
+    /// use protrust::Message;
+    /// 
+    /// let mut message = Required::new();
+    /// *message.a_mut() = 1;
+    /// 
+    /// let mut other = Required::new();
+    /// *other.b_mut() = 2;
+    /// 
+    /// let err = message.try_merge(&other).unwrap_err();
+    /// assert_eq!(err.paths(), &["c"]);
+    /// ```
+    fn try_merge(&mut self, other: &Self) -> Result<(), MissingFields>
+        where Self: Mergable
+    {
+        self.merge(other);
+
+        let mut missing = Vec::new();
+        self.missing_fields("", &mut missing);
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingFields { paths: missing })
+        }
+    }
 
     /// Gets a shared reference to the unknown fields in this message.
     /// 
@@ -185,6 +400,112 @@ pub trait Message: Default + Clone + PartialEq + Debug + Sized {
     /// assert!(unknown_fields.is_empty());
     /// ```
     fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet;
+
+    /// Returns whether this message's known fields are equal to another message's, ignoring unknown fields.
+    /// 
+    /// `PartialEq` on messages is strict: two messages with the same known fields but different unknown
+    /// fields aren't equal. This is the lenient alternative, useful when comparing a message parsed from
+    /// a peer using a newer schema against an expected value.
+    /// 
+    /// The default implementation can't see individual fields, so it only ignores this message's own unknown fields:
+    /// unknown fields in message fields are still compared. If either message has unknown fields, it also clones
+    /// both messages to clear them before comparing. Generated messages override this to compare each known field
+    /// without cloning, comparing message fields with `semantic_eq` so unknown fields are ignored at every level.
+    /// 
+    /// # Examples
+    /// 
+    /// ```ignore
+    /// # use protrust::doctest::timestamp::Timestamp;
+    /// use protrust::Message;
+    /// 
+    /// // seconds = 5, with an unknown field 3 = 1
+    /// let parsed = Timestamp::parse_merged_from_slice(&[8, 5, 24, 1])?;
+    /// 
+    /// let mut expected = Timestamp::new();
+    /// *expected.seconds_mut() = 5;
+    /// 
+    /// assert_ne!(parsed, expected);
+    /// assert!(parsed.semantic_eq(&expected));
+    /// # Ok::<(), protrust::io::read::Error>(())
+    /// ```
+    fn semantic_eq(&self, other: &Self) -> bool {
+        if self.unknown_fields().is_empty() && other.unknown_fields().is_empty() {
+            return self == other;
+        }
+
+        let mut this = self.clone();
+        let mut other = other.clone();
+        this.unknown_fields_mut().clear();
+        other.unknown_fields_mut().clear();
+        this == other
+    }
+}
+
+/// The error returned by [`Message::try_merge`](trait.Message.html#method.try_merge)
+/// when the merged message is missing required fields.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingFields {
+    paths: Vec<String>,
+}
+
+impl MissingFields {
+    /// Gets the paths of the required fields that aren't set. Nested fields are separated by a `.`,
+    /// and an empty path refers to the message itself.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+}
+
+impl Display for MissingFields {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "the message is missing required fields")?;
+        let mut paths = self.paths.iter().filter(|p| !p.is_empty());
+        if let Some(first) = paths.next() {
+            write!(fmt, ": {}", first)?;
+            for path in paths {
+                write!(fmt, ", {}", path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for MissingFields { }
+
+/// Extension methods for reporting which required fields of a message aren't set.
+///
+/// This is implemented for all messages using [`Message::missing_fields`](trait.Message.html#method.missing_fields),
+/// while [`Message::is_initialized`](trait.Message.html#tymethod.is_initialized) remains the fast check.
+pub trait MessageExt: Message {
+    /// Gets the path of the first required field that isn't set, or `None` if the message is initialized.
+    ///
+    /// Nested fields are separated by a `.`. Messages that don't report individual fields
+    /// return an empty path if they aren't initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// # use protrust::doctest::required::Required;
+    /// use protrust::MessageExt;
+    ///
+    /// let mut message = Required::new();
+    /// *message.a_mut() = 1;
+    ///
+    /// assert_eq!(message.first_missing_field().as_deref(), Some("b"));
+    /// ```
+    fn first_missing_field(&self) -> Option<String>;
+}
+
+impl<M: Message> MessageExt for M {
+    fn first_missing_field(&self) -> Option<String> {
+        if self.is_initialized() {
+            return None;
+        }
+
+        let mut missing = Vec::new();
+        self.missing_fields("", &mut missing);
+        missing.into_iter().next()
+    }
 }
 
 /// A marker trait used to mark enum types in generated code.
@@ -285,7 +606,44 @@ pub trait Message: Default + Clone + PartialEq + Debug + Sized {
 /// # }
 /// assert_eq!(format!("{:?}", Aliased::ALIAS), "FOO");
 /// ```
-pub trait Enum: From<i32> + Into<i32> + Default + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash + Debug { }
+pub trait Enum: From<i32> + Into<i32> + Default + Clone + Copy + PartialEq + Eq + PartialOrd + Ord + Hash + Debug {
+    /// Returns whether the value is one of the values defined by the enum.
+    /// 
+    /// This is used when reading closed (proto2) enums, where unrecognized values are treated as unknown fields.
+    /// The default implementation treats every value as known.
+    fn is_known(self) -> bool {
+        true
+    }
+    /// Returns the value if it's known, or the enum's default value if it isn't.
+    /// 
+    /// This can be used to sanitize values from untrusted peers before branching on them.
+    /// The raw value remains available through the newtype's field if it's needed before replacing it.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// # use protrust::Enum;
+    /// # #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    /// # pub struct Syntax(pub i32);
+    /// # impl From<i32> for Syntax { fn from(x: i32) -> Self { Self(x) } }
+    /// # impl From<Syntax> for i32 { fn from(x: Syntax) -> Self { x.0 } }
+    /// impl Enum for Syntax {
+    ///     fn is_known(self) -> bool {
+    ///         matches!(self.0, 0 | 1)
+    ///     }
+    /// }
+    /// 
+    /// assert_eq!(Syntax(1).or_default_if_unknown(), Syntax(1));
+    /// assert_eq!(Syntax(7).or_default_if_unknown(), Syntax(0));
+    /// ```
+    fn or_default_if_unknown(self) -> Self {
+        if self.is_known() {
+            self
+        } else {
+            Self::default()
+        }
+    }
+}
 
 /// A type that can be merged with one of `T`. Merge behavior is specific to each type.
 /// 
@@ -337,4 +695,296 @@ pub trait Mergable<T = Self>: Sized {
 /// ```
 pub fn merge<T: Mergable<V>, V>(value: &mut T, other: &V) {
     value.merge(other)
-}
\ No newline at end of file
+}
+
+/// Merges the boxed values, allowing boxed messages (like recursive message fields) to be merged in place.
+impl<T: Mergable> Mergable for Box<T> {
+    fn merge(&mut self, other: &Self) {
+        (**self).merge(other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Message, Mergable, UnknownFieldSet};
+    use crate::io::{read, write, FieldNumber, Length, CodedReader, CodedWriter, Input, Output};
+    use crate::doctest::required::Required;
+    use crate::doctest::timestamp::Timestamp;
+
+    fn encode<M: Message>(message: &M) -> Vec<u8> {
+        let mut output = vec![0; message.calculate_size().unwrap().get() as usize];
+        message.write_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+        output
+    }
+
+    /// Creates a `Required` message with all of its required fields set
+    fn required(a: i32) -> Required {
+        let mut message = Required::new();
+        *message.a_mut() = a;
+        *message.b_mut() = 0;
+        *message.c_mut() = 0;
+        message
+    }
+
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Sparse(i32);
+
+    impl From<i32> for Sparse {
+        fn from(i: i32) -> Self {
+            Sparse(i)
+        }
+    }
+
+    impl From<Sparse> for i32 {
+        fn from(s: Sparse) -> i32 {
+            s.0
+        }
+    }
+
+    impl crate::Enum for Sparse {
+        fn is_known(self) -> bool {
+            matches!(self.0, 0 | 5 | 10)
+        }
+    }
+
+    #[test]
+    fn or_default_if_unknown() {
+        use crate::Enum;
+
+        for &known in &[0, 5, 10] {
+            assert_eq!(Sparse(known).or_default_if_unknown(), Sparse(known));
+        }
+        // below the smallest value, in a gap, and above the largest value
+        for &unknown in &[-1, 3, 11] {
+            assert_eq!(Sparse(unknown).or_default_if_unknown(), Sparse(0));
+        }
+    }
+
+    #[test]
+    fn parse_merged_concatenated_timestamps() {
+        let mut first = Timestamp::new();
+        *first.seconds_mut() = 5;
+        *first.nanos_mut() = 100;
+        let mut second = Timestamp::new();
+        *second.seconds_mut() = 10;
+
+        let mut input = encode(&first);
+        input.extend(encode(&second));
+
+        let parsed = Timestamp::parse_merged_from_slice(&input).unwrap();
+        assert_eq!(parsed.seconds(), &10);
+        assert_eq!(parsed.nanos(), &100);
+
+        first.merge(&second);
+        assert_eq!(parsed, first);
+    }
+
+    #[test]
+    fn parse_merged_truncated() {
+        assert!(Timestamp::parse_merged_from_slice(&[8, 5, 16]).is_err());
+    }
+
+    #[test]
+    fn merge_best_effort_keeps_read_fields() {
+        let mut timestamp = Timestamp::new();
+        let input = [8, 5, 16, 128];
+        let truncated = timestamp.merge_best_effort(&mut CodedReader::with_slice(&input)).unwrap().unwrap();
+
+        assert_eq!(timestamp.seconds(), &5);
+        assert!(matches!(truncated.error().kind(), read::ErrorKind::IoError(_)));
+        assert_eq!(truncated.position() + truncated.skipped(), 4);
+
+        let mut timestamp = Timestamp::new();
+        assert!(timestamp.merge_best_effort(&mut CodedReader::with_slice(&[8, 5])).unwrap().is_none());
+        assert_eq!(timestamp.seconds(), &5);
+    }
+
+    #[test]
+    fn merge_best_effort_skips_rest_of_stream() {
+        // seconds = 5, then an invalid tag (field 0) followed by more data
+        let input: &[u8] = &[8, 5, 0, 8, 6, 16, 7];
+        let mut reader = CodedReader::with_capacity(2, input);
+        let mut timestamp = Timestamp::new();
+        let truncated = timestamp.merge_best_effort(&mut reader).unwrap().unwrap();
+
+        assert_eq!(timestamp.seconds(), &5);
+        assert!(matches!(truncated.error().kind(), read::ErrorKind::InvalidTag(0)));
+        assert_eq!((truncated.position(), truncated.skipped()), (3, 4));
+        assert!(reader.read_tag().unwrap().is_none());
+    }
+
+    #[test]
+    fn write_to_writer_flushes() {
+        let mut timestamp = Timestamp::new();
+        *timestamp.seconds_mut() = 5;
+        *timestamp.nanos_mut() = 100;
+
+        let mut output = vec![1];
+        timestamp.write_to_writer(&mut output).unwrap();
+        assert_eq!(output, [1, 8, 5, 16, 100]);
+
+        let mut full = [0u8; 2];
+        let err = timestamp.write_to_writer(&mut &mut full[..]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+    }
+
+    /// A message that writes a timestamp and then fails
+    #[derive(Default, Clone, Debug, PartialEq)]
+    struct FailingWrite(Timestamp);
+
+    impl Mergable for FailingWrite {
+        fn merge(&mut self, other: &Self) { self.0.merge(&other.0) }
+    }
+
+    impl Message for FailingWrite {
+        fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> { self.0.merge_from(input) }
+        fn calculate_size(&self) -> Option<Length> { self.0.calculate_size() }
+        fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
+            self.0.write_to(output)?;
+            Err(write::Error::ValueTooLarge)
+        }
+        fn is_initialized(&self) -> bool { self.0.is_initialized() }
+        fn unknown_fields(&self) -> &UnknownFieldSet { self.0.unknown_fields() }
+        fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet { self.0.unknown_fields_mut() }
+    }
+
+    #[test]
+    fn write_to_writer_discards_failed_writes() {
+        let mut message = FailingWrite::default();
+        *message.0.seconds_mut() = 5;
+
+        let mut output = Vec::new();
+        let err = message.write_to_writer(&mut output).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(output.is_empty());
+    }
+
+    #[cfg(feature = "grpc-framing")]
+    #[test]
+    fn write_grpc_frame_discards_failed_writes() {
+        let mut message = FailingWrite::default();
+        *message.0.seconds_mut() = 5;
+
+        let mut output = Vec::new();
+        assert!(crate::io::grpc::write_grpc_frame(&mut output, &message, false).is_err());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn encode_into_reuses_buffer() {
+        let mut timestamp = Timestamp::new();
+        *timestamp.seconds_mut() = 5;
+        *timestamp.nanos_mut() = 100;
+
+        let mut buf = Vec::with_capacity(16);
+        buf.extend_from_slice(&[1, 2, 3, 4, 5, 6]);
+        let ptr = buf.as_ptr();
+        timestamp.encode_into(&mut buf).unwrap();
+        assert_eq!(buf, [8, 5, 16, 100]);
+        assert_eq!(buf.as_ptr(), ptr);
+
+        timestamp.encode_into(&mut buf).unwrap();
+        assert_eq!(buf, [8, 5, 16, 100]);
+    }
+
+    #[test]
+    fn semantic_eq_ignores_unknown_fields() {
+        let parsed = Timestamp::parse_merged_from_slice(&[8, 5, 24, 1]).unwrap();
+        let mut expected = Timestamp::new();
+        *expected.seconds_mut() = 5;
+
+        assert_ne!(parsed, expected);
+        assert!(parsed.semantic_eq(&expected));
+        assert!(expected.semantic_eq(&parsed));
+
+        *expected.nanos_mut() = 1;
+        assert!(!parsed.semantic_eq(&expected));
+    }
+
+    #[test]
+    fn semantic_eq_ignores_nested_unknown_fields() {
+        use crate::collections::unknown_fields::UnknownField;
+
+        let mut child = required(1);
+        child.unknown_fields_mut().push_value(unsafe { FieldNumber::new_unchecked(5) }, UnknownField::Varint(1));
+        let mut parsed = required(2);
+        parsed.child_mut().merge(&child);
+        let mut expected = required(2);
+        expected.child_mut().merge(&required(1));
+
+        assert_ne!(parsed, expected);
+        assert!(parsed.semantic_eq(&expected));
+        assert!(expected.semantic_eq(&parsed));
+
+        assert!(!parsed.semantic_eq(&required(2)));
+    }
+
+    #[test]
+    fn try_merge_initialized() {
+        let mut message = Required::new();
+        let other = required(1);
+
+        assert_eq!(message.try_merge(&other), Ok(()));
+        assert_eq!(message.a(), Some(&1));
+    }
+
+    #[test]
+    fn try_merge_reports_nested_paths() {
+        let mut message = required(1);
+        let mut other = Required::new();
+        *other.child_mut().b_mut() = 2;
+        *other.child_mut().c_mut() = 3;
+
+        let err = message.try_merge(&other).unwrap_err();
+        assert_eq!(err.paths(), &["child.a"]);
+        assert_eq!(err.to_string(), "the message is missing required fields: child.a");
+        assert_eq!(message.child(), other.child());
+    }
+
+    #[test]
+    fn required_round_trips_child_and_unknown_fields() {
+        // a = 1, child = { a = 2 }, unknown field 5 = 1
+        let input = [8, 1, 34, 2, 8, 2, 40, 1];
+        let message = Required::parse_merged_from_slice(&input).unwrap();
+        assert_eq!(message.child().and_then(Required::a), Some(&2));
+        assert_eq!(encode(&message), input);
+    }
+
+    #[test]
+    fn try_merge_default_missing_fields() {
+        #[derive(Default, Clone, Debug, PartialEq)]
+        struct Opaque(Required);
+
+        impl Mergable for Opaque {
+            fn merge(&mut self, other: &Self) { self.0.merge(&other.0) }
+        }
+
+        impl Message for Opaque {
+            fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> { self.0.merge_from(input) }
+            fn calculate_size(&self) -> Option<Length> { self.0.calculate_size() }
+            fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result { self.0.write_to(output) }
+            fn is_initialized(&self) -> bool { self.0.is_initialized() }
+            fn unknown_fields(&self) -> &UnknownFieldSet { self.0.unknown_fields() }
+            fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet { self.0.unknown_fields_mut() }
+        }
+
+        let err = Opaque::default().try_merge(&Opaque::default()).unwrap_err();
+        assert_eq!(err.paths(), &[""]);
+        assert_eq!(err.to_string(), "the message is missing required fields");
+    }
+
+    #[test]
+    fn first_missing_field() {
+        use crate::MessageExt;
+
+        let mut message = required(1);
+        assert_eq!(message.first_missing_field(), None);
+
+        message.child_mut().child_mut();
+        assert_eq!(message.first_missing_field().as_deref(), Some("child.a"));
+
+        let mut message = Required::new();
+        message.child_mut().merge(&required(1));
+        assert_eq!(message.first_missing_field().as_deref(), Some("a"));
+    }
+}
@@ -89,6 +89,8 @@ impl Value for Int32 {
     }
     fn is_initialized(_this: &Self::Inner) -> bool { true }
     fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
+        // negative values are sign-extended to 10 bytes, read_varint32 consumes
+        // the whole varint and keeps the low 32 bits
         input.read_varint32().map(|v| v as i32)
     }
 }
@@ -165,6 +167,44 @@ impl Value for Uint64 {
     }
 }
 
+pub mod zigzag {
+    //! Contains the zig-zag transforms used by [`Sint32`](../struct.Sint32.html) and [`Sint64`](../struct.Sint64.html).
+    //! 
+    //! Zig-zag encoding maps signed integers to unsigned integers so that values with a small magnitude
+    //! have a small encoding, no matter their sign: 0 maps to 0, -1 to 1, 1 to 2, -2 to 3, and so on.
+    //! 
+    //! # Examples
+    //! 
+    //! ```
+    //! use protrust::raw::zigzag;
+    //! 
+    //! assert_eq!(zigzag::encode_i32(-1), 1);
+    //! assert_eq!(zigzag::encode_i32(i32::min_value()), u32::max_value());
+    //! assert_eq!(zigzag::decode_i64(zigzag::encode_i64(-150)), -150);
+    //! ```
+
+    /// Zig-zag encodes a 32-bit signed integer.
+    #[inline]
+    pub const fn encode_i32(value: i32) -> u32 {
+        ((value << 1) ^ (value >> 31)) as u32
+    }
+    /// Decodes a zig-zag encoded 32-bit signed integer.
+    #[inline]
+    pub const fn decode_i32(value: u32) -> i32 {
+        ((value >> 1) ^ 0u32.wrapping_sub(value & 1)) as i32
+    }
+    /// Zig-zag encodes a 64-bit signed integer.
+    #[inline]
+    pub const fn encode_i64(value: i64) -> u64 {
+        ((value << 1) ^ (value >> 63)) as u64
+    }
+    /// Decodes a zig-zag encoded 64-bit signed integer.
+    #[inline]
+    pub const fn decode_i64(value: u64) -> i64 {
+        ((value >> 1) ^ 0u64.wrapping_sub(value & 1)) as i64
+    }
+}
+
 /// A varint encoded 32-bit value. This is encoded using zig-zag encoding, 
 /// which makes it more effecient at encoding negative values.
 pub struct Sint32;
@@ -176,17 +216,17 @@ impl Value for Sint32 {
     const WIRE_TYPE: WireType = WireType::Varint;
 
     fn calculate_size(&this: &Self::Inner, builder: LengthBuilder) -> Option<LengthBuilder> {
-        builder.add_bytes(io::raw_varint32_size(((this << 1) ^ (this >> 31)) as u32))
+        builder.add_bytes(io::raw_varint32_size(zigzag::encode_i32(this)))
     }
     fn merge_from<T: Input>(this: &mut Self::Inner, input: &mut CodedReader<T>) -> read::Result<()> {
         Self::read_new(input).map(|v| *this = v)
     }
     fn write_to<T: Output>(&this: &Self::Inner, output: &mut CodedWriter<T>) -> write::Result {
-        output.write_varint32(((this << 1) ^ (this >> 31)) as u32)
+        output.write_varint32(zigzag::encode_i32(this))
     }
     fn is_initialized(_this: &Self::Inner) -> bool { true }
     fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
-        input.read_varint32().map(|v| ((v >> 1) ^ (v << 31)) as i32)
+        input.read_varint32().map(zigzag::decode_i32)
     }
 }
 
@@ -201,17 +241,17 @@ impl Value for Sint64 {
     const WIRE_TYPE: WireType = WireType::Varint;
 
     fn calculate_size(&this: &Self::Inner, builder: LengthBuilder) -> Option<LengthBuilder> {
-        builder.add_bytes(io::raw_varint64_size(((this << 1) ^ (this >> 63)) as u64))
+        builder.add_bytes(io::raw_varint64_size(zigzag::encode_i64(this)))
     }
     fn merge_from<T: Input>(this: &mut Self::Inner, input: &mut CodedReader<T>) -> read::Result<()> {
         Self::read_new(input).map(|v| *this = v)
     }
     fn write_to<T: Output>(&this: &Self::Inner, output: &mut CodedWriter<T>) -> write::Result {
-        output.write_varint64(((this << 1) ^ (this >> 63)) as u64)
+        output.write_varint64(zigzag::encode_i64(this))
     }
     fn is_initialized(_this: &Self::Inner) -> bool { true }
     fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
-        input.read_varint64().map(|v| ((v >> 1) ^ (v << 63)) as i64)
+        input.read_varint64().map(zigzag::decode_i64)
     }
 }
 
@@ -366,7 +406,7 @@ impl Value for String {
             .add_bytes(unsafe { Length::new_unchecked(len) })
     }
     fn merge_from<T: Input>(this: &mut Self::Inner, input: &mut CodedReader<T>) -> read::Result<()> {
-        Self::read_new(input).map(|v| *this = v)
+        input.read_string_into(this)
     }
     fn write_to<T: Output>(this: &Self::Inner, output: &mut CodedWriter<T>) -> write::Result {
         output.write_length_delimited(this.as_bytes())
@@ -374,11 +414,28 @@ impl Value for String {
     fn is_initialized(_this: &Self::Inner) -> bool { true }
     fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
         std::string::String::from_utf8(input.read_value::<Bytes<Vec<_>>>()?)
-            .map_err(io::read::Error::InvalidString)
+            .map_err(|e| input.error(io::read::ErrorKind::InvalidString(e)))
     }
 }
 
 /// A bytes value. This is encoded as a length-delimited series of bytes.
+/// 
+/// The bytes can be stored in any [`ByteString`](../io/trait.ByteString.html). `Bytes<Vec<u8>>` is growable,
+/// while `Bytes<Box<[u8]>>` is an owned alternative that never holds excess capacity.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::{read, CodedReader};
+/// use protrust::raw::Bytes;
+/// 
+/// let data = [3, 1, 2, 3];
+/// let mut reader = CodedReader::with_slice(&data);
+/// 
+/// let value: Box<[u8]> = reader.read_value::<Bytes<Box<[u8]>>>()?;
+/// assert_eq!(&*value, &[1, 2, 3]);
+/// # Ok::<(), read::Error>(())
+/// ```
 pub struct Bytes<T>(T);
 impl<T> Sealed for Bytes<T> { }
 impl<T: ByteString> ValueType for Bytes<T> {
@@ -457,18 +514,16 @@ impl<T: TraitMessage> Value for Message<T> {
         this.is_initialized()
     }
     default fn read_new<U: Input>(input: &mut CodedReader<U>) -> read::Result<Self::Inner> {
-        input.recurse(|input| {
-            let mut t = T::default();
-            t.merge_from(input)?;
-            Ok(t)
-        })
+        let mut t = T::default();
+        Self::merge_from(&mut t, input)?;
+        Ok(t)
     }
 }
 impl<T: TraitMessage + ExtendableMessage + 'static> Value for Message<T> {
     fn read_new<U: Input>(input: &mut CodedReader<U>) -> read::Result<Self::Inner> {
         let mut t = T::default();
         t.extensions_mut().replace_registry(input.registry());
-        t.merge_from(input)?;
+        Self::merge_from(&mut t, input)?;
         Ok(t)
     }
 }
@@ -501,7 +556,122 @@ impl<T: TraitMessage> Value for Group<T> {
     }
 }
 
+/// A value stored in a `Box`, encoded the same as the value it wraps.
+/// 
+/// Singular message fields are boxed in generated code so recursive messages have a known size. This allows the same
+/// for values in other collections, like message values in map fields, where `MapField<K, Box<T>>` keeps each
+/// entry small and allows a message to contain a map of itself.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::CodedReader;
+/// use protrust::raw::{Boxed, Int32};
+/// 
+/// let data = [150, 1];
+/// let value = CodedReader::with_slice(&data).read_value::<Boxed<Int32>>()?;
+/// 
+/// assert_eq!(value, Box::new(150));
+/// # Ok::<(), protrust::io::read::Error>(())
+/// ```
+pub struct Boxed<V>(V);
+impl<V> Sealed for Boxed<V> { }
+impl<V: Value> ValueType for Boxed<V> {
+    type Inner = Box<V::Inner>;
+}
+impl<V: Value> Value for Boxed<V> {
+    const WIRE_TYPE: WireType = V::WIRE_TYPE;
+
+    fn calculate_size(this: &Self::Inner, builder: LengthBuilder) -> Option<LengthBuilder> {
+        V::calculate_size(this, builder)
+    }
+    fn merge_from<T: Input>(this: &mut Self::Inner, input: &mut CodedReader<T>) -> read::Result<()> {
+        V::merge_from(this, input)
+    }
+    fn write_to<T: Output>(this: &Self::Inner, output: &mut CodedWriter<T>) -> write::Result {
+        V::write_to(this, output)
+    }
+    fn is_initialized(this: &Self::Inner) -> bool {
+        V::is_initialized(this)
+    }
+    fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
+        V::read_new(input).map(Box::new)
+    }
+}
+
+/// An inclusive range of values accepted by a [`Bounded`](struct.Bounded.html) value.
+pub trait Bounds {
+    /// The type of value being bounded
+    type Value: PartialOrd + Copy;
+
+    /// The smallest value in the range
+    const MIN: Self::Value;
+    /// The largest value in the range
+    const MAX: Self::Value;
+}
+
+/// A value which is checked to be within the range specified by the [`Bounds`](trait.Bounds.html)
+/// when it's read, returning an [`OutOfRange`](../io/read/enum.ErrorKind.html#variant.OutOfRange) error if it's not.
+/// 
+/// The value is encoded the same as the value it wraps. Values aren't checked when they're written.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use protrust::io::{read, CodedReader};
+/// use protrust::raw::{Bounded, Bounds, Int32};
+/// 
+/// struct Percent;
+/// impl Bounds for Percent {
+///     type Value = i32;
+/// 
+///     const MIN: i32 = 0;
+///     const MAX: i32 = 100;
+/// }
+/// 
+/// let data = [100, 101];
+/// let mut reader = CodedReader::with_slice(&data);
+/// 
+/// assert_eq!(reader.read_value::<Bounded<Int32, Percent>>()?, 100);
+/// assert!(matches!(reader.read_value::<Bounded<Int32, Percent>>().map_err(read::Error::into_kind), Err(read::ErrorKind::OutOfRange)));
+/// # Ok::<(), read::Error>(())
+/// ```
+pub struct Bounded<V, B>(V, B);
+impl<V, B> Sealed for Bounded<V, B> { }
+impl<V: Value<Inner = B::Value>, B: Bounds> ValueType for Bounded<V, B> {
+    type Inner = V::Inner;
+}
+impl<V: Value<Inner = B::Value>, B: Bounds> Value for Bounded<V, B> {
+    const WIRE_TYPE: WireType = V::WIRE_TYPE;
+
+    fn calculate_size(this: &Self::Inner, builder: LengthBuilder) -> Option<LengthBuilder> {
+        V::calculate_size(this, builder)
+    }
+    fn merge_from<T: Input>(this: &mut Self::Inner, input: &mut CodedReader<T>) -> read::Result<()> {
+        Self::read_new(input).map(|v| *this = v)
+    }
+    fn write_to<T: Output>(this: &Self::Inner, output: &mut CodedWriter<T>) -> write::Result {
+        V::write_to(this, output)
+    }
+    fn is_initialized(this: &Self::Inner) -> bool {
+        V::is_initialized(this)
+    }
+    fn read_new<T: Input>(input: &mut CodedReader<T>) -> read::Result<Self::Inner> {
+        let value = V::read_new(input)?;
+        if value < B::MIN || value > B::MAX {
+            Err(input.error(read::ErrorKind::OutOfRange))
+        } else {
+            Ok(value)
+        }
+    }
+}
+impl<V: ConstSized<Inner = B::Value>, B: Bounds> ConstSized for Bounded<V, B> {
+    const SIZE: Length = V::SIZE;
+}
+impl<V: Packable<Inner = B::Value>, B: Bounds> Packable for Bounded<V, B> { }
+
 #[cfg(test)]
+#[allow(clippy::unreadable_literal)]
 mod test {
     macro_rules! test_cases {
         ($t:ty => {
@@ -558,7 +728,7 @@ mod test {
                     let input = $i;
                     let mut reader = CodedReader::with_slice(&input);
 
-                    let result = reader.read_value::<$t>();
+                    let result = reader.read_value::<$t>().map_err(crate::io::read::Error::into_kind);
 
                     assert!(matches!(result, $o $(if $e)?))
                 })+
@@ -614,6 +784,8 @@ mod test {
                     [128, 1] => Ok(128),
                     [128, 128, 1] => Ok(16384),
                     [255, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Ok(-1),
+                    [128, 128, 128, 128, 248, 255, 255, 255, 255, 1] => Ok(-2_147_483_648),
+                    [255, 255, 255, 255, 15] => Ok(-1), // 32-bit encoding from non-conforming peers
                 },
             }
         }
@@ -675,11 +847,143 @@ mod test {
     mod uint64 {
 
     }
+    /// A small xorshift generator, so the round trip tests cover the same values on every run.
+    fn random_values(mut state: u64) -> impl Iterator<Item = u64> {
+        std::iter::repeat_with(move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+    }
+
     mod sint32 {
+        use crate::io::{CodedReader, CodedWriter, Length};
+        use crate::raw::{zigzag, Sint32};
 
+        test_cases! {
+            Sint32 => {
+                write: write_sint32 => {
+                    0 => [0],
+                    -1 => [1],
+                    1 => [2],
+                    -64 => [127],
+                    64 => [128, 1],
+                    i32::max_value() => [254, 255, 255, 255, 15],
+                    i32::min_value() => [255, 255, 255, 255, 15],
+                },
+                size: calculate_sint32_size => {
+                    0                => Length::new(1),
+                    -1               => Length::new(1),
+                    -64              => Length::new(1),
+                    64               => Length::new(2),
+                    i32::max_value() => Length::new(5),
+                    i32::min_value() => Length::new(5),
+                },
+                read: read_sint32 => {
+                    [0] => Ok(0),
+                    [1] => Ok(-1),
+                    [2] => Ok(1),
+                    [127] => Ok(-64),
+                    [128, 1] => Ok(64),
+                    [254, 255, 255, 255, 15] => Ok(2_147_483_647),
+                    [255, 255, 255, 255, 15] => Ok(-2_147_483_648),
+                },
+            }
+        }
+
+        fn round_trip(value: i32) {
+            assert_eq!(zigzag::decode_i32(zigzag::encode_i32(value)), value);
+
+            let mut output = [0; 5];
+            let mut writer = CodedWriter::with_slice(&mut output);
+            writer.write_value::<Sint32>(&value).unwrap();
+            let mut reader = CodedReader::with_slice(&output);
+            assert_eq!(reader.read_value::<Sint32>().unwrap(), value);
+        }
+
+        #[test]
+        fn round_trip_boundaries() {
+            for &value in &[i32::min_value(), i32::min_value() + 1, -1, 0, 1, i32::max_value() - 1, i32::max_value()] {
+                round_trip(value);
+            }
+        }
+
+        #[test]
+        fn round_trip_random() {
+            for value in super::random_values(0x2545_F491_4F6C_DD1D).take(10_000) {
+                round_trip(value as i32);
+            }
+        }
+
+        #[test]
+        fn decode_sign_matches_low_bit() {
+            for value in super::random_values(0x9E37_79B9_7F4A_7C15).take(10_000) {
+                let value = value as u32;
+                let decoded = zigzag::decode_i32(value);
+                assert_eq!(zigzag::encode_i32(decoded), value);
+                assert_eq!(decoded < 0, value & 1 == 1);
+            }
+        }
     }
     mod sint64 {
+        use crate::io::{CodedReader, CodedWriter, Length};
+        use crate::raw::{zigzag, Sint64};
 
+        test_cases! {
+            Sint64 => {
+                write: write_sint64 => {
+                    0 => [0],
+                    -1 => [1],
+                    1 => [2],
+                    -64 => [127],
+                    64 => [128, 1],
+                    i64::max_value() => [254, 255, 255, 255, 255, 255, 255, 255, 255, 1],
+                    i64::min_value() => [255, 255, 255, 255, 255, 255, 255, 255, 255, 1],
+                },
+                size: calculate_sint64_size => {
+                    0                => Length::new(1),
+                    -1               => Length::new(1),
+                    -64              => Length::new(1),
+                    64               => Length::new(2),
+                    i64::max_value() => Length::new(10),
+                    i64::min_value() => Length::new(10),
+                },
+                read: read_sint64 => {
+                    [0] => Ok(0),
+                    [1] => Ok(-1),
+                    [2] => Ok(1),
+                    [127] => Ok(-64),
+                    [128, 1] => Ok(64),
+                    [254, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Ok(9_223_372_036_854_775_807),
+                    [255, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Ok(-9_223_372_036_854_775_808),
+                },
+            }
+        }
+
+        fn round_trip(value: i64) {
+            assert_eq!(zigzag::decode_i64(zigzag::encode_i64(value)), value);
+
+            let mut output = [0; 10];
+            let mut writer = CodedWriter::with_slice(&mut output);
+            writer.write_value::<Sint64>(&value).unwrap();
+            let mut reader = CodedReader::with_slice(&output);
+            assert_eq!(reader.read_value::<Sint64>().unwrap(), value);
+        }
+
+        #[test]
+        fn round_trip_boundaries() {
+            for &value in &[i64::min_value(), i64::min_value() + 1, -1, 0, 1, i64::max_value() - 1, i64::max_value()] {
+                round_trip(value);
+            }
+        }
+
+        #[test]
+        fn round_trip_random() {
+            for value in super::random_values(0x2545_F491_4F6C_DD1D).take(10_000) {
+                round_trip(value as i64);
+            }
+        }
     }
     mod fixed32 {
 
@@ -736,6 +1040,23 @@ mod test {
                 }
             }
         }
+        test_cases! {
+            Bytes<Box<[u8]>> => {
+                size: calculate_boxed_bytes_size => {
+                    Box::<[u8]>::from(&[][..])   => Length::new(1),
+                    Box::<[u8]>::from(&[1, 2][..]) => Length::new(3),
+                },
+                read: read_boxed_bytes => {
+                    [0] => Ok(ref val) if val.is_empty(),
+                    [3, 1, 2, 3] => Ok(ref val) if **val == [1, 2, 3],
+                    [3, 1, 2] => Err(_),
+                },
+                write: write_boxed_bytes => {
+                    Box::<[u8]>::from(&[][..]) => [0],
+                    Box::<[u8]>::from(&[1, 2, 3][..]) => [3, 1, 2, 3],
+                }
+            }
+        }
     }
     mod r#enum {
         use crate::io::Length;
@@ -805,10 +1126,108 @@ mod test {
             }
         }
     }
+    mod bounded {
+        use crate::io::{read::{Error, ErrorKind}, Length};
+        use crate::raw::{Bounded, Bounds, Int32, Uint64};
+
+        pub struct Small;
+        impl Bounds for Small {
+            type Value = i32;
+
+            const MIN: i32 = -2;
+            const MAX: i32 = 2;
+        }
+
+        pub struct NonZero;
+        impl Bounds for NonZero {
+            type Value = u64;
+
+            const MIN: u64 = 1;
+            const MAX: u64 = u64::max_value();
+        }
+
+        test_cases! {
+            Bounded<Int32, Small> => {
+                write: write_bounded_int32 => {
+                    2 => [2],
+                    10 => [10],
+                },
+                size: calculate_bounded_int32_size => {
+                    0 => Length::new(1),
+                    -1 => Length::new(10),
+                },
+                read: read_bounded_int32 => {
+                    [254, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Ok(-2),
+                    [0] => Ok(0),
+                    [2] => Ok(2),
+                    [3] => Err(ErrorKind::OutOfRange),
+                    [253, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Err(ErrorKind::OutOfRange),
+                    [128] => Err(ErrorKind::IoError(_)),
+                },
+            }
+        }
+        test_cases! {
+            Bounded<Uint64, NonZero> => {
+                read: read_bounded_uint64 => {
+                    [1] => Ok(1),
+                    [255, 255, 255, 255, 255, 255, 255, 255, 255, 1] => Ok(v) if v == u64::max_value(),
+                    [0] => Err(ErrorKind::OutOfRange),
+                },
+            }
+        }
+
+        #[test]
+        fn merge_leaves_value_on_error() {
+            use crate::io::CodedReader;
+
+            let data = [0];
+            let mut value = 5;
+            let result = CodedReader::with_slice(&data).merge_value::<Bounded<Uint64, NonZero>>(&mut value);
+
+            assert!(matches!(result.map_err(Error::into_kind), Err(ErrorKind::OutOfRange)));
+            assert_eq!(value, 5);
+        }
+    }
     mod message {
 
     }
     mod group {
+        use crate::Message;
+        use crate::doctest::node::Node;
+        use crate::io::{read, Length, CodedWriter};
+
+        #[test]
+        fn round_trip_nested() {
+            let node = Node {
+                value: 1,
+                nested: Some(Box::new(Node {
+                    value: 2,
+                    nested: Some(Box::new(Node { value: 3, ..Default::default() })),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            };
+            // value = 1, start group 2 { value = 2, start group 2 { value = 3 } end group 2 } end group 2
+            let expected = [8, 1, 19, 8, 2, 19, 8, 3, 20, 20];
+
+            assert_eq!(node.calculate_size(), Length::new(expected.len() as i32));
+
+            let mut output = [0u8; 10];
+            node.write_to(&mut CodedWriter::with_slice(&mut output)).unwrap();
+            assert_eq!(output, expected);
+
+            assert_eq!(Node::parse_merged_from_slice(&output).unwrap(), node);
+        }
+
+        #[test]
+        fn unterminated() {
+            assert!(matches!(Node::parse_merged_from_slice(&[19, 8, 1]).map_err(read::Error::into_kind), Err(read::ErrorKind::IoError(_))));
+        }
 
+        #[test]
+        fn mismatched_end() {
+            let result = Node::parse_merged_from_slice(&[19, 8, 1, 28]);
+            assert!(matches!(result.map_err(read::Error::into_kind), Err(read::ErrorKind::GroupMismatch { start, end }) if start.get() == 2 && end.get() == 3));
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,293 @@
+//! Helpers for testing and fuzzing message implementations.
+//!
+//! These check the core invariant of the wire format: a message that's encoded and parsed again
+//! is equal to the original message, and encoding the parsed message again produces an equivalent output.
+//!
+//! Maps and unknown fields are backed by hash maps, so two equal messages may write their fields in
+//! different orders. Because of this, encoded outputs are compared by size and by parsing them again
+//! rather than byte for byte. Messages containing `NaN` floating point values never compare equal and
+//! can't be checked with these helpers.
+//!
+//! This also contains helpers for golden byte tests, which compare a message against a known encoding.
+//! [`assert_encodes_to`](fn.assert_encodes_to.html) does compare bytes exactly, so it should only be
+//! used with messages that write their fields in a fixed order.
+
+use std::fmt::Write;
+
+use crate::Message;
+use crate::io::{read, CodedReader, CodedWriter};
+use std::cmp;
+
+/// Encodes the message into a new `Vec`, panicking if the message is too large to encode.
+fn encode<M: Message>(message: &M) -> Vec<u8> {
+    let len = message.calculate_size().expect("message size overflowed an i32");
+    let mut output = vec![0; len.get() as usize];
+    let mut writer = CodedWriter::with_slice(&mut output);
+    message.write_to(&mut writer).expect("message did not write the size it calculated");
+    output
+}
+
+/// Parses a new message from the slice.
+fn parse<M: Message>(data: &[u8]) -> read::Result<M> {
+    let mut message = M::default();
+    message.merge_from(&mut CodedReader::with_slice(data))?;
+    Ok(message)
+}
+
+/// Asserts that the message survives a round-trip through the wire format.
+///
+/// This encodes the message, parses it back, and checks the parsed message is equal to the original.
+/// It then encodes the parsed message again and checks the new output is the same size
+/// and parses to the same message.
+///
+/// # Panics
+///
+/// Panics if any step fails or the round-tripped message isn't equal to the original.
+pub fn assert_roundtrip<M: Message>(message: &M) {
+    let encoded = encode(message);
+    let parsed = parse::<M>(&encoded).expect("failed to parse an encoded message");
+    assert_eq!(&parsed, message, "parsed message doesn't match the original message");
+
+    let reencoded = encode(&parsed);
+    assert_eq!(reencoded.len(), encoded.len(), "re-encoded message has a different size");
+
+    let reparsed = parse::<M>(&reencoded).expect("failed to parse a re-encoded message");
+    assert_eq!(&reparsed, message, "re-parsed message doesn't match the original message");
+}
+
+/// Formats the bytes as space separated hex pairs.
+fn hex(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len() * 3);
+    for (i, b) in data.iter().enumerate() {
+        if i != 0 {
+            output.push(' ');
+        }
+        write!(output, "{:02x}", b).unwrap();
+    }
+    output
+}
+
+/// Describes how the actual bytes differ from the expected bytes, or returns `None` if they're the same.
+/// The description includes both outputs in hex, with a marker under the first differing byte.
+fn hex_diff(actual: &[u8], expected: &[u8]) -> Option<String> {
+    let offset = actual.iter().zip(expected).position(|(a, e)| a != e)
+        .or_else(|| if actual.len() != expected.len() { Some(cmp::min(actual.len(), expected.len())) } else { None })?;
+
+    Some(format!(
+        "first difference at byte {} (expected {} bytes, got {})\nexpected: {}\n  actual: {}\n          {}^",
+        offset, expected.len(), actual.len(), hex(expected), hex(actual), " ".repeat(offset * 3)))
+}
+
+/// Asserts that the message encodes to exactly the expected bytes.
+///
+/// # Panics
+///
+/// Panics if the message can't be encoded or the output isn't the same as the expected bytes.
+/// The panic message shows both outputs in hex and the offset of the first byte that's different.
+///
+/// # Examples
+///
+/// ```ignore
+/// # use protrust::doctest::timestamp::Timestamp;
+/// use protrust::test_support::assert_encodes_to;
+///
+/// let mut timestamp = Timestamp::new();
+/// *timestamp.seconds_mut() = 5;
+///
+/// assert_encodes_to(&timestamp, &[8, 5]);
+/// ```
+pub fn assert_encodes_to<M: Message>(message: &M, expected: &[u8]) {
+    let encoded = encode(message);
+    if let Some(diff) = hex_diff(&encoded, expected) {
+        panic!("message didn't encode to the expected bytes: {}", diff);
+    }
+}
+
+/// Asserts that the bytes parse to a message equal to the expected message.
+///
+/// # Panics
+///
+/// Panics if the bytes fail to parse or parse to a different message.
+pub fn assert_parses_to<M: Message>(data: &[u8], expected: &M) {
+    match parse::<M>(data) {
+        Ok(parsed) => assert_eq!(&parsed, expected, "bytes didn't parse to the expected message\n   input: {}", hex(data)),
+        Err(e) => panic!("failed to parse bytes: {}\n   input: {}", e, hex(data)),
+    }
+}
+
+/// Parses arbitrary data as a message, asserting that a successful parse is stable.
+///
+/// If the data doesn't parse, this does nothing. Otherwise the parsed message is checked with
+/// [`assert_roundtrip`](fn.assert_roundtrip.html). This is intended as the body of a fuzz target.
+///
+/// # Examples
+///
+/// ```ignore
+/// #![no_main]
+/// use libfuzzer_sys::fuzz_target;
+///
+/// fuzz_target!(|data: &[u8]| {
+///     protrust::test_support::fuzz_parse::<my_protos::MyMessage>(data);
+/// });
+/// ```
+pub fn fuzz_parse<M: Message>(data: &[u8]) {
+    if let Ok(message) = parse::<M>(data) {
+        assert_roundtrip(&message);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Message, Mergable, UnknownFieldSet};
+    use crate::io::{read, write, FieldNumber, Length, LengthBuilder, CodedReader, CodedWriter, Input, Output};
+    use crate::raw;
+    use super::{assert_roundtrip, assert_encodes_to, assert_parses_to, fuzz_parse, hex_diff};
+
+    #[derive(Default, Clone, Debug, PartialEq)]
+    struct TestMessage {
+        number: i32,
+        text: String,
+        unknown_fields: UnknownFieldSet,
+    }
+
+    const NUMBER_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(1) };
+    const TEXT_FIELD: FieldNumber = unsafe { FieldNumber::new_unchecked(2) };
+
+    impl Mergable for TestMessage {
+        fn merge(&mut self, other: &Self) {
+            if other.number != 0 {
+                self.number = other.number;
+            }
+            if !other.text.is_empty() {
+                self.text.clone_from(&other.text);
+            }
+            self.unknown_fields.merge(&other.unknown_fields);
+        }
+    }
+
+    impl Message for TestMessage {
+        fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+            while let Some(field) = input.read_field()? {
+                match field.tag() {
+                    8 => field.merge_value::<raw::Int32>(NUMBER_FIELD, &mut self.number)?,
+                    18 => field.merge_value::<raw::String>(TEXT_FIELD, &mut self.text)?,
+                    _ => field.check_and_try_add_field_to(&mut self.unknown_fields)?.or_skip()?,
+                }
+            }
+            Ok(())
+        }
+        fn calculate_size(&self) -> Option<Length> {
+            let mut builder = LengthBuilder::new();
+            if self.number != 0 {
+                builder = builder.add_field::<raw::Int32>(NUMBER_FIELD, &self.number)?;
+            }
+            if !self.text.is_empty() {
+                builder = builder.add_field::<raw::String>(TEXT_FIELD, &self.text)?;
+            }
+            builder = builder.add_fields(&self.unknown_fields)?;
+            Some(builder.build())
+        }
+        fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result {
+            if self.number != 0 {
+                output.write_field::<raw::Int32>(NUMBER_FIELD, &self.number)?;
+            }
+            if !self.text.is_empty() {
+                output.write_field::<raw::String>(TEXT_FIELD, &self.text)?;
+            }
+            output.write_fields(&self.unknown_fields)
+        }
+        fn is_initialized(&self) -> bool {
+            true
+        }
+        fn unknown_fields(&self) -> &UnknownFieldSet {
+            &self.unknown_fields
+        }
+        fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet {
+            &mut self.unknown_fields
+        }
+    }
+
+    #[test]
+    fn roundtrip_default() {
+        assert_roundtrip(&TestMessage::default());
+    }
+
+    #[test]
+    fn roundtrip_fields() {
+        assert_roundtrip(&TestMessage { number: -1, text: "foo".to_string(), ..Default::default() });
+        assert_roundtrip(&TestMessage { number: i32::min_value(), ..Default::default() });
+    }
+
+    #[test]
+    fn fuzz_valid_inputs() {
+        fuzz_parse::<TestMessage>(&[8, 255, 255, 255, 255, 255, 255, 255, 255, 255, 1, 18, 3, 102, 111, 111]);
+        fuzz_parse::<TestMessage>(&[24, 1, 33, 1, 2, 3, 4, 5, 6, 7, 8, 43, 8, 1, 44]);
+    }
+
+    #[test]
+    fn fuzz_invalid_inputs() {
+        fuzz_parse::<TestMessage>(&[8]);
+        fuzz_parse::<TestMessage>(&[18, 2, 0xC3, 0x28]);
+        fuzz_parse::<TestMessage>(&[43, 8, 1, 52]);
+        fuzz_parse::<TestMessage>(&[0]);
+    }
+
+    #[test]
+    fn hex_diff_marks_first_difference() {
+        assert_eq!(hex_diff(&[8, 1], &[8, 1]), None);
+        assert_eq!(
+            hex_diff(&[8, 1, 16, 2], &[8, 1, 18, 2]).unwrap(),
+            "first difference at byte 2 (expected 4 bytes, got 4)\nexpected: 08 01 12 02\n  actual: 08 01 10 02\n                ^");
+        assert_eq!(
+            hex_diff(&[8], &[8, 1]).unwrap(),
+            "first difference at byte 1 (expected 2 bytes, got 1)\nexpected: 08 01\n  actual: 08\n             ^");
+    }
+
+    #[test]
+    fn golden_bytes() {
+        let message = TestMessage { number: 150, text: "hi".to_string(), ..Default::default() };
+        assert_encodes_to(&message, &[8, 150, 1, 18, 2, 104, 105]);
+        assert_parses_to(&[8, 150, 1, 18, 2, 104, 105], &message);
+        assert_parses_to(&[18, 2, 104, 105, 8, 150, 1], &message);
+    }
+
+    #[test]
+    #[should_panic(expected = "first difference at byte 1")]
+    fn encodes_to_reports_difference() {
+        assert_encodes_to(&TestMessage { number: 1, ..Default::default() }, &[8, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse bytes")]
+    fn parses_to_reports_errors() {
+        assert_parses_to(&[8], &TestMessage::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "parsed message doesn't match the original message")]
+    fn roundtrip_detects_lost_fields() {
+        #[derive(Default, Clone, Debug, PartialEq)]
+        struct Lossy(TestMessage);
+
+        impl Mergable for Lossy {
+            fn merge(&mut self, other: &Self) {
+                self.0.merge(&other.0)
+            }
+        }
+
+        impl Message for Lossy {
+            fn merge_from<T: Input>(&mut self, input: &mut CodedReader<T>) -> read::Result<()> {
+                self.0.merge_from(input)?;
+                self.0.text.clear();
+                Ok(())
+            }
+            fn calculate_size(&self) -> Option<Length> { self.0.calculate_size() }
+            fn write_to<T: Output>(&self, output: &mut CodedWriter<T>) -> write::Result { self.0.write_to(output) }
+            fn is_initialized(&self) -> bool { true }
+            fn unknown_fields(&self) -> &UnknownFieldSet { self.0.unknown_fields() }
+            fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet { self.0.unknown_fields_mut() }
+        }
+
+        assert_roundtrip(&Lossy(TestMessage { text: "foo".to_string(), ..Default::default() }));
+    }
+}